@@ -1,10 +1,101 @@
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate, NaiveDateTime};
 use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use orgtools::clocktable::{parse_range_bound, GroupBy};
 use orgtools::config::Config;
+use orgtools::datetree::DatetreeKind;
+use orgtools::flow::FlowGroup;
+use orgtools::heatmap::WeekStart;
+use orgtools::matcher::Matcher;
+use orgtools::prune::OpenDescendants;
+use orgtools::report::OutputFormat;
+use orgtools::sort::SortKey;
+use orgtools::split::SplitBy;
+use orgtools::stats::Period;
+use orgtools::subtree::SubtreeEdit;
+use orgtools::timestamp::Timestamp;
 
 fn parse_keyword(s: &str) -> Result<String, String> {
     Ok(s.trim().to_uppercase())
 }
 
+fn parse_priority(s: &str) -> Result<char, String> {
+    let s = s.trim().trim_start_matches("[#").trim_end_matches(']');
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => Ok(c.to_ascii_uppercase()),
+        _ => Err(format!("Invalid priority: {}", s)),
+    }
+}
+
+fn parse_date(s: &str) -> Result<Timestamp, String> {
+    Timestamp::parse_user_input_local(s, true).map_err(|err| err.to_string())
+}
+
+fn parse_range_start(s: &str) -> Result<NaiveDateTime, String> {
+    parse_range_bound(s, Local::now().date_naive(), false).map_err(|err| err.to_string())
+}
+
+fn parse_range_end(s: &str) -> Result<NaiveDateTime, String> {
+    parse_range_bound(s, Local::now().date_naive(), true).map_err(|err| err.to_string())
+}
+
+fn parse_datetree(s: &str) -> Result<DatetreeKind, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_open_descendants(s: &str) -> Result<OpenDescendants, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_sort_key(s: &str) -> Result<SortKey, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_group_by(s: &str) -> Result<GroupBy, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_output_format(s: &str) -> Result<OutputFormat, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_period(s: &str) -> Result<Period, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_flow_group(s: &str) -> Result<FlowGroup, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_week_start(s: &str) -> Result<WeekStart, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_split_by(s: &str) -> Result<SplitBy, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_matcher(s: &str) -> Result<Matcher, String> {
+    s.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+fn parse_property(s: &str) -> Result<(String, String), String> {
+    parse_assignment(s).map(|(key, value)| (key.to_uppercase(), value))
+}
+
+fn parse_assignment(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("Invalid value, expected KEY=VALUE: {}", s)),
+    }
+}
+
 pub fn cli() -> Cli {
     let matches = create_command().get_matches();
     Cli::from_matches(&matches)
@@ -18,31 +109,93 @@ fn create_command() -> Command {
         .long("output-file")
         .help("Output file path")
         .required(false);
+    let stats_args = [
+        Arg::new("root")
+            .long("root")
+            .help("Directory of org files, defaults to the workspace or the current directory"),
+        Arg::new("from")
+            .long("from")
+            .value_parser(parse_date)
+            .help("Only count tasks closed on or after this date"),
+        Arg::new("to")
+            .long("to")
+            .value_parser(parse_date)
+            .help("Only count tasks closed on or before this date"),
+        Arg::new("match")
+            .long("match")
+            .value_parser(parse_matcher)
+            .help("Only count tasks matching tags and properties, as in +work-someday"),
+        Arg::new("format")
+            .long("format")
+            .value_parser(parse_output_format)
+            .default_value("table")
+            .help("Output as a table, csv or json"),
+    ];
+    let search = Arg::new("search")
+        .required(true)
+        .help("Headline of the subtree");
 
     Command::new("orgtools")
         .about("A tool for managing org files")
         .subcommand_required(true)
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .global(true)
+                .help("Config file path, defaults to $ORGTOOLS_CONFIG or config.toml in $XDG_CONFIG_HOME/orgtools or ~/.config/orgtools"),
+        )
         .arg(
             Arg::new("keywords_unfinished")
                 .long("keywords-unfinished")
                 .value_parser(parse_keyword)
                 .value_delimiter(',')
-                .default_value("TODO,DOING,BLOCKED")
-                .help("Keywords for unfinished tasks"),
+                .help("Keywords for unfinished tasks [default: TODO,DOING,BLOCKED]"),
         )
         .arg(
             Arg::new("keywords_finished")
                 .long("keywords-finished")
                 .value_parser(parse_keyword)
                 .value_delimiter(',')
-                .default_value("DONE,ABANDONED")
-                .help("Keywords for finished tasks"),
+                .help("Keywords for finished tasks [default: DONE,ABANDONED]"),
         )
         .subcommand(
             Command::new("prune")
                 .about("Remove finished tasks")
                 .arg(input_file.clone())
-                .arg(output_file.clone()),
+                .arg(output_file.clone())
+                .arg(
+                    Arg::new("older_than")
+                        .long("older-than")
+                        .value_parser(clap::value_parser!(i64))
+                        .help("Only remove tasks closed more than this many days ago"),
+                )
+                .arg(
+                    Arg::new("open_descendants")
+                        .long("open-descendants")
+                        .value_parser(parse_open_descendants)
+                        .help("Keep, promote or prune finished tasks with open subtasks [default: keep]"),
+                )
+                .arg(
+                    Arg::new("keep_tag")
+                        .long("keep-tag")
+                        .action(ArgAction::Append)
+                        .help("Never remove tasks with this tag [default: keep]"),
+                )
+                .arg(
+                    Arg::new("dry_run")
+                        .long("dry-run")
+                        .action(ArgAction::SetTrue)
+                        .help("Print what would be removed instead of removing it"),
+                ),
+        )
+        .subcommand(
+            Command::new("archive")
+                .about("Move finished tasks to an archive file")
+                .arg(
+                    Arg::new("input_file")
+                        .required(true)
+                        .help("Input file path"),
+                ),
         )
         .subcommand(
             Command::new("tree")
@@ -59,6 +212,19 @@ fn create_command() -> Command {
                         .long("sections")
                         .action(ArgAction::SetTrue)
                         .help("Display sections"),
+                )
+                .arg(
+                    Arg::new("match")
+                        .long("match")
+                        .value_parser(parse_matcher)
+                        .help("Only display sections matching tags and properties, with their ancestors"),
+                )
+                .arg(
+                    Arg::new("org")
+                        .long("org")
+                        .action(ArgAction::SetTrue)
+                        .requires("match")
+                        .help("Display matching sections as an Org file instead of indented headlines"),
                 ),
         )
         .subcommand(
@@ -69,8 +235,55 @@ fn create_command() -> Command {
         .subcommand(
             Command::new("add")
                 .about("Add a task")
+                .arg(
+                    Arg::new("headline")
+                        .required(true)
+                        .help("Headline text of the task"),
+                )
                 .arg(input_file.clone())
                 .arg(output_file.clone())
+                .arg(
+                    Arg::new("keyword")
+                        .long("keyword")
+                        .value_parser(parse_keyword)
+                        .help("TODO keyword of the task"),
+                )
+                .arg(
+                    Arg::new("priority")
+                        .long("priority")
+                        .value_parser(parse_priority)
+                        .help("Priority of the task, e.g. A"),
+                )
+                .arg(
+                    Arg::new("tags")
+                        .long("tags")
+                        .value_delimiter(',')
+                        .help("Comma separated tags for the task"),
+                )
+                .arg(
+                    Arg::new("scheduled")
+                        .long("scheduled")
+                        .value_parser(parse_date)
+                        .help("Scheduled date, e.g. 2026-10-17, '2026-10-17 10:00' or tomorrow"),
+                )
+                .arg(
+                    Arg::new("deadline")
+                        .long("deadline")
+                        .value_parser(parse_date)
+                        .help("Deadline date, e.g. 2026-10-17, '2026-10-17 10:00' or +1w"),
+                )
+                .arg(
+                    Arg::new("property")
+                        .long("property")
+                        .value_parser(parse_property)
+                        .action(ArgAction::Append)
+                        .help("Property to set on the task as KEY=VALUE"),
+                )
+                .arg(
+                    Arg::new("body")
+                        .long("body")
+                        .help("Body text of the task, or - to read it from stdin"),
+                )
                 .arg(
                     Arg::new("under")
                         .long("under")
@@ -81,51 +294,537 @@ fn create_command() -> Command {
                         .long("after")
                         .help("Headline after which to add the task"),
                 )
+                .arg(
+                    Arg::new("datetree")
+                        .long("datetree")
+                        .value_parser(parse_datetree)
+                        .help("Add the task under today in a day, week or month date tree"),
+                )
                 .group(
                     ArgGroup::new("under_or_after")
-                        .args(["under", "after"])
+                        .args(["under", "after", "datetree"])
                         .required(false)
                         .multiple(false),
                 ),
         )
+        .subcommand(
+            Command::new("state")
+                .about("Change the TODO state of a task")
+                .arg(
+                    Arg::new("search")
+                        .required(true)
+                        .help("Headline of the task"),
+                )
+                .arg(
+                    Arg::new("keyword")
+                        .required(true)
+                        .value_parser(parse_keyword)
+                        .help("New TODO keyword"),
+                )
+                .arg(input_file.clone())
+                .arg(output_file.clone()),
+        )
+        .subcommand(
+            Command::new("done")
+                .about("Mark a task with the first finished keyword")
+                .arg(
+                    Arg::new("search")
+                        .required(true)
+                        .help("Headline of the task"),
+                )
+                .arg(input_file.clone())
+                .arg(output_file.clone()),
+        )
+        .subcommands(
+            [
+                ("promote", "Move a subtree up a level"),
+                ("demote", "Move a subtree down a level"),
+                ("move-up", "Move a subtree before its previous sibling"),
+                ("move-down", "Move a subtree after its next sibling"),
+            ]
+            .map(|(name, about)| {
+                Command::new(name)
+                    .about(about)
+                    .arg(search.clone())
+                    .arg(input_file.clone())
+                    .arg(output_file.clone())
+            }),
+        )
+        .subcommand(
+            Command::new("set-level")
+                .about("Change the level of a subtree")
+                .arg(search.clone())
+                .arg(
+                    Arg::new("level")
+                        .required(true)
+                        .value_parser(clap::value_parser!(usize))
+                        .help("New level of the subtree's headline"),
+                )
+                .arg(input_file.clone())
+                .arg(output_file.clone()),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Format a file")
+                .arg(input_file.clone())
+                .arg(output_file.clone())
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .action(ArgAction::SetTrue)
+                        .help("Exit with an error if the file isn't formatted, without changing it"),
+                ),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Check files for likely mistakes")
+                .arg(
+                    Arg::new("input_files")
+                        .num_args(0..)
+                        .help("Input file paths, defaults to stdin"),
+                ),
+        )
+        .subcommand(
+            Command::new("clock")
+                .about("Clock in and out of tasks")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("in")
+                        .about("Start a clock, stopping the one already running")
+                        .arg(search.clone())
+                        .arg(
+                            Arg::new("input_file")
+                                .required(true)
+                                .help("Input file path"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("out")
+                        .about("Stop the running clock")
+                        .arg(input_file.clone()),
+                )
+                .subcommand(
+                    Command::new("status")
+                        .about("Show the running clock")
+                        .arg(input_file.clone()),
+                ),
+        )
+        .subcommand(
+            Command::new("clocktable")
+                .about("Report the time clocked in files")
+                .arg(
+                    Arg::new("input_files")
+                        .num_args(0..)
+                        .help("Input file paths, defaults to stdin"),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_parser(parse_range_start)
+                        .help("Only count time from this date or time"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_parser(parse_range_end)
+                        .help("Only count time up to this date (inclusive) or time"),
+                )
+                .arg(
+                    Arg::new("by")
+                        .long("by")
+                        .value_parser(parse_group_by)
+                        .default_value("heading")
+                        .help("Group time by heading, tag, day or file"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Count time against headings at most this deep"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(parse_output_format)
+                        .default_value("table")
+                        .help("Output as a table, csv or json"),
+                )
+                .arg(
+                    Arg::new("update")
+                        .long("update")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all(["from", "to", "by", "depth", "format"])
+                        .help("Update the #+BEGIN: clocktable blocks in the files instead"),
+                ),
+        )
+        .subcommand(
+            Command::new("effort")
+                .about("Compare effort estimates with the time clocked")
+                .arg(
+                    Arg::new("input_files")
+                        .num_args(0..)
+                        .help("Input file paths, defaults to stdin"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Only list headings at most this deep"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(parse_output_format)
+                        .default_value("table")
+                        .help("Output as a table, csv or json"),
+                ),
+        )
+        .subcommand(
+            Command::new("habits")
+                .about("Show the consistency graphs and streaks of habits")
+                .arg(
+                    Arg::new("input_files")
+                        .num_args(0..)
+                        .help("Input file paths, defaults to stdin"),
+                )
+                .arg(
+                    Arg::new("days_before")
+                        .long("days-before")
+                        .value_parser(clap::value_parser!(i64))
+                        .default_value("21")
+                        .help("Days before today to graph"),
+                )
+                .arg(
+                    Arg::new("days_after")
+                        .long("days-after")
+                        .value_parser(clap::value_parser!(i64))
+                        .default_value("7")
+                        .help("Days after today to graph"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(parse_output_format)
+                        .default_value("table")
+                        .help("Output graphs as a table, or statistics as csv or json"),
+                ),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Statistics about finished tasks")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("closed")
+                        .about("Count the tasks closed in each period")
+                        .arg(
+                            Arg::new("by")
+                                .long("by")
+                                .value_parser(parse_period)
+                                .default_value("week")
+                                .help("Count by day, week, month or year"),
+                        )
+                        .args(stats_args.clone()),
+                )
+                .subcommand(
+                    Command::new("flow")
+                        .about("Report lead time, time in state, throughput and stale work")
+                        .arg(
+                            Arg::new("by")
+                                .long("by")
+                                .value_parser(parse_flow_group)
+                                .default_value("tag")
+                                .help("Group by tag, file, day, week, month or year"),
+                        )
+                        .arg(
+                            Arg::new("period")
+                                .long("period")
+                                .value_parser(parse_period)
+                                .default_value("week")
+                                .help("Count throughput by day, week, month or year"),
+                        )
+                        .arg(
+                            Arg::new("stale_days")
+                                .long("stale-days")
+                                .value_parser(clap::value_parser!(i64))
+                                .default_value("30")
+                                .help("Open tasks in the same state for longer are stale"),
+                        )
+                        .args(stats_args.clone()),
+                )
+                .subcommand(
+                    Command::new("heatmap")
+                        .about("Show the tasks closed each day as a calendar heatmap")
+                        .arg(
+                            Arg::new("week_start")
+                                .long("week-start")
+                                .value_parser(parse_week_start)
+                                .help("Start weeks on monday or sunday, defaults to the config"),
+                        )
+                        .arg(
+                            Arg::new("colors")
+                                .long("colors")
+                                .value_delimiter(',')
+                                .help("Comma separated #rrggbb colours from none to the most, defaults to the config"),
+                        )
+                        .arg(
+                            Arg::new("svg")
+                                .long("svg")
+                                .action(ArgAction::SetTrue)
+                                .help("Output an SVG image instead of terminal colours"),
+                        )
+                        .args(stats_args[..4].to_vec()),
+                ),
+        )
+        .subcommand(
+            Command::new("review")
+                .about("List next actions, stuck projects and waiting items")
+                .arg(
+                    Arg::new("root")
+                        .long("root")
+                        .help("Directory of org files, defaults to the workspace or the current directory"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(parse_output_format)
+                        .default_value("table")
+                        .help("Output as tables, csv or json"),
+                ),
+        )
+        .subcommand(
+            Command::new("extract")
+                .about("Write a subtree to a standalone file, to splice back after editing")
+                .arg(search.clone())
+                .arg(input_file.clone())
+                .arg(
+                    Arg::new("output_file")
+                        .long("output-file")
+                        .help("Output file path, defaults to stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("splice")
+                .about("Replace the subtree a file was extracted from with the file")
+                .arg(
+                    Arg::new("extracted_file")
+                        .required(true)
+                        .help("File written by extract"),
+                )
+                .arg(
+                    Arg::new("into")
+                        .long("into")
+                        .help("File to splice into, defaults to the file it was extracted from"),
+                )
+                .arg(output_file.clone()),
+        )
+        .subcommand(
+            Command::new("split")
+                .about("Write a file per top-level section")
+                .arg(input_file.clone())
+                .arg(
+                    Arg::new("by")
+                        .long("by")
+                        .value_parser(parse_split_by)
+                        .default_value("level1")
+                        .help("Split by level1 sections"),
+                )
+                .arg(
+                    Arg::new("out_dir")
+                        .long("out-dir")
+                        .required(true)
+                        .help("Directory to write the files to"),
+                ),
+        )
+        .subcommand(
+            Command::new("join")
+                .about("Join files into one outline, each under a heading named after it")
+                .arg(
+                    Arg::new("input_files")
+                        .num_args(1..)
+                        .required(true)
+                        .help("Input file paths"),
+                )
+                .arg(
+                    Arg::new("output_file")
+                        .long("output-file")
+                        .help("Output file path, defaults to stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export a file or subtree to another format")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("md")
+                        .about("Export to Markdown")
+                        .arg(input_file.clone())
+                        .arg(
+                            Arg::new("subtree")
+                                .long("subtree")
+                                .help("Headline of a subtree to export on its own"),
+                        )
+                        .arg(
+                            Arg::new("output_file")
+                                .long("output-file")
+                                .help("Output file path, defaults to stdout"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("html")
+                        .about("Export to an HTML page")
+                        .arg(input_file.clone())
+                        .arg(
+                            Arg::new("subtree")
+                                .long("subtree")
+                                .help("Headline of a subtree to export on its own"),
+                        )
+                        .arg(
+                            Arg::new("output_file")
+                                .long("output-file")
+                                .help("Output file path, defaults to stdout"),
+                        )
+                        .arg(
+                            Arg::new("template")
+                                .long("template")
+                                .help("HTML file with {{title}}, {{toc}} and {{body}} placeholders"),
+                        )
+                        .arg(
+                            Arg::new("toc_depth")
+                                .long("toc-depth")
+                                .value_parser(clap::value_parser!(usize))
+                                .help("Deepest heading level in the table of contents, 0 for none"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("sort")
+                .about("Sort the children of a heading")
+                .arg(
+                    Arg::new("parent")
+                        .required(true)
+                        .help("Headline whose children to sort, or \"\" for the top level"),
+                )
+                .arg(input_file.clone())
+                .arg(output_file.clone())
+                .arg(
+                    Arg::new("by")
+                        .long("by")
+                        .required(true)
+                        .value_parser(parse_sort_key)
+                        .help("priority, todo, scheduled, deadline, closed, alpha or property:NAME"),
+                )
+                .arg(
+                    Arg::new("reverse")
+                        .long("reverse")
+                        .action(ArgAction::SetTrue)
+                        .help("Sort in descending order"),
+                ),
+        )
+        .subcommand(
+            Command::new("refile")
+                .about("Move a subtree under another heading")
+                .arg(
+                    Arg::new("search")
+                        .required(true)
+                        .help("Headline of the subtree to move"),
+                )
+                .arg(
+                    Arg::new("target")
+                        .required(true)
+                        .help("Target heading, as HEADING, FILE::HEADING or FILE:: for the top level"),
+                )
+                .arg(input_file.clone())
+                .arg(output_file.clone()),
+        )
+        .subcommand(
+            Command::new("capture")
+                .about("Add an entry from a capture template")
+                .arg(
+                    Arg::new("template")
+                        .required(true)
+                        .help("Name of the capture template"),
+                )
+                .arg(
+                    Arg::new("file")
+                        .long("file")
+                        .help("File to capture into, overriding the template"),
+                )
+                .arg(output_file.clone())
+                .arg(
+                    Arg::new("set")
+                        .long("set")
+                        .value_parser(parse_assignment)
+                        .action(ArgAction::Append)
+                        .help("Answer a template prompt as PROMPT=VALUE"),
+                )
+                .arg(
+                    Arg::new("annotation")
+                        .long("annotation")
+                        .help("Annotation for %a, e.g. a link"),
+                ),
+        )
 }
 
 #[derive(Debug)]
 pub struct Cli {
-    pub keywords_unfinished: Vec<String>,
-    pub keywords_finished: Vec<String>,
+    pub config_file: Option<String>,
+    pub keywords_unfinished: Option<Vec<String>>,
+    pub keywords_finished: Option<Vec<String>>,
     pub command: Commands,
 }
 
 impl Cli {
-    pub fn config(&self) -> Config {
-        Config {
-            keywords_unfinished: self.keywords_unfinished.clone(),
-            keywords_finished: self.keywords_finished.clone(),
+    /// Load the config file and apply any overrides from the command line.
+    ///
+    /// The default config file is optional, one given with `--config` is not.
+    pub fn config(&self) -> Result<Config> {
+        let mut config = match &self.config_file {
+            Some(path) => Config::load(&PathBuf::from(path))?,
+            None => match default_config_path().filter(|path| path.exists()) {
+                Some(path) => Config::load(&path)?,
+                None => Config::default(),
+            },
+        };
+        if let Some(keywords) = &self.keywords_unfinished {
+            config.keywords_unfinished = keywords.clone();
         }
+        if let Some(keywords) = &self.keywords_finished {
+            config.keywords_finished = keywords.clone();
+        }
+        Ok(config)
     }
 
     fn from_matches(matches: &ArgMatches) -> Self {
+        let config_file = matches.get_one::<String>("config").cloned();
         let keywords_unfinished = matches
             .get_many::<String>("keywords_unfinished")
-            .unwrap()
-            .cloned()
-            .collect();
+            .map(|keywords| keywords.cloned().collect());
         let keywords_finished = matches
             .get_many::<String>("keywords_finished")
-            .unwrap()
-            .cloned()
-            .collect();
+            .map(|keywords| keywords.cloned().collect());
 
         let command = match matches.subcommand() {
             Some(("prune", sub_matches)) => Commands::Prune {
                 input_file: sub_matches.get_one::<String>("input_file").cloned(),
                 output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                older_than: sub_matches.get_one::<i64>("older_than").copied(),
+                open_descendants: sub_matches
+                    .get_one::<OpenDescendants>("open_descendants")
+                    .copied(),
+                keep_tags: sub_matches
+                    .get_many::<String>("keep_tag")
+                    .map(|tags| tags.cloned().collect()),
+                dry_run: sub_matches.get_flag("dry_run"),
+            },
+            Some(("archive", sub_matches)) => Commands::Archive {
+                input_file: sub_matches.get_one::<String>("input_file").unwrap().clone(),
             },
             Some(("tree", sub_matches)) => Commands::Tree {
                 input_file: sub_matches.get_one::<String>("input_file").cloned(),
                 sexp: sub_matches.get_flag("sexp"),
                 sections: sub_matches.get_flag("sections"),
+                matcher: sub_matches.get_one::<Matcher>("match").cloned(),
+                org: sub_matches.get_flag("org"),
             },
             Some(("list", sub_matches)) => Commands::List {
                 input_file: sub_matches.get_one::<String>("input_file").cloned(),
@@ -134,13 +833,216 @@ impl Cli {
                 input_file: sub_matches.get_one::<String>("input_file").cloned(),
                 output_file: sub_matches.get_one::<String>("output_file").cloned(),
                 headline: sub_matches.get_one::<String>("headline").unwrap().clone(),
+                keyword: sub_matches.get_one::<String>("keyword").cloned(),
+                priority: sub_matches.get_one::<char>("priority").copied(),
+                tags: sub_matches
+                    .get_many::<String>("tags")
+                    .map(|tags| tags.cloned().collect())
+                    .unwrap_or_default(),
+                scheduled: sub_matches.get_one::<Timestamp>("scheduled").cloned(),
+                deadline: sub_matches.get_one::<Timestamp>("deadline").cloned(),
+                properties: sub_matches
+                    .get_many::<(String, String)>("property")
+                    .map(|properties| properties.cloned().collect())
+                    .unwrap_or_default(),
+                body: sub_matches.get_one::<String>("body").cloned(),
                 under: sub_matches.get_one::<String>("under").cloned(),
                 after: sub_matches.get_one::<String>("after").cloned(),
+                datetree: sub_matches.get_one::<DatetreeKind>("datetree").copied(),
+            },
+            Some(("state", sub_matches)) => Commands::State {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                search: sub_matches.get_one::<String>("search").unwrap().clone(),
+                keyword: sub_matches.get_one::<String>("keyword").cloned(),
+            },
+            Some(("done", sub_matches)) => Commands::State {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                search: sub_matches.get_one::<String>("search").unwrap().clone(),
+                keyword: None,
+            },
+            Some((
+                name @ ("promote" | "demote" | "move-up" | "move-down" | "set-level"),
+                sub_matches,
+            )) => Commands::EditSubtree {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                search: sub_matches.get_one::<String>("search").unwrap().clone(),
+                edit: match name {
+                    "promote" => SubtreeEdit::Promote,
+                    "demote" => SubtreeEdit::Demote,
+                    "move-up" => SubtreeEdit::MoveUp,
+                    "move-down" => SubtreeEdit::MoveDown,
+                    _ => SubtreeEdit::SetLevel(*sub_matches.get_one::<usize>("level").unwrap()),
+                },
+            },
+            Some(("fmt", sub_matches)) => Commands::Fmt {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                check: sub_matches.get_flag("check"),
+            },
+            Some(("lint", sub_matches)) => Commands::Lint {
+                input_files: sub_matches
+                    .get_many::<String>("input_files")
+                    .map(|files| files.cloned().collect())
+                    .unwrap_or_default(),
+            },
+            Some(("clock", sub_matches)) => match sub_matches.subcommand() {
+                Some(("in", clock_matches)) => Commands::ClockIn {
+                    input_file: clock_matches
+                        .get_one::<String>("input_file")
+                        .unwrap()
+                        .clone(),
+                    search: clock_matches.get_one::<String>("search").unwrap().clone(),
+                },
+                Some(("out", clock_matches)) => Commands::ClockOut {
+                    input_file: clock_matches.get_one::<String>("input_file").cloned(),
+                },
+                Some(("status", clock_matches)) => Commands::ClockStatus {
+                    input_file: clock_matches.get_one::<String>("input_file").cloned(),
+                },
+                _ => unreachable!(),
+            },
+            Some(("clocktable", sub_matches)) => Commands::Clocktable {
+                input_files: sub_matches
+                    .get_many::<String>("input_files")
+                    .map(|files| files.cloned().collect())
+                    .unwrap_or_default(),
+                from: sub_matches.get_one::<NaiveDateTime>("from").copied(),
+                to: sub_matches.get_one::<NaiveDateTime>("to").copied(),
+                by: *sub_matches.get_one::<GroupBy>("by").unwrap(),
+                depth: sub_matches.get_one::<usize>("depth").copied(),
+                format: *sub_matches.get_one::<OutputFormat>("format").unwrap(),
+                update: sub_matches.get_flag("update"),
+            },
+            Some(("effort", sub_matches)) => Commands::Effort {
+                input_files: sub_matches
+                    .get_many::<String>("input_files")
+                    .map(|files| files.cloned().collect())
+                    .unwrap_or_default(),
+                depth: sub_matches.get_one::<usize>("depth").copied(),
+                format: *sub_matches.get_one::<OutputFormat>("format").unwrap(),
+            },
+            Some(("habits", sub_matches)) => Commands::Habits {
+                input_files: sub_matches
+                    .get_many::<String>("input_files")
+                    .map(|files| files.cloned().collect())
+                    .unwrap_or_default(),
+                days_before: *sub_matches.get_one::<i64>("days_before").unwrap(),
+                days_after: *sub_matches.get_one::<i64>("days_after").unwrap(),
+                format: *sub_matches.get_one::<OutputFormat>("format").unwrap(),
+            },
+            Some(("stats", sub_matches)) => match sub_matches.subcommand() {
+                Some(("closed", stats_matches)) => Commands::StatsClosed {
+                    by: *stats_matches.get_one::<Period>("by").unwrap(),
+                    root: stats_matches.get_one::<String>("root").cloned(),
+                    from: stats_matches
+                        .get_one::<Timestamp>("from")
+                        .map(|from| from.date),
+                    to: stats_matches.get_one::<Timestamp>("to").map(|to| to.date),
+                    matcher: stats_matches.get_one::<Matcher>("match").cloned(),
+                    format: *stats_matches.get_one::<OutputFormat>("format").unwrap(),
+                },
+                Some(("flow", stats_matches)) => Commands::StatsFlow {
+                    by: *stats_matches.get_one::<FlowGroup>("by").unwrap(),
+                    period: *stats_matches.get_one::<Period>("period").unwrap(),
+                    stale_days: *stats_matches.get_one::<i64>("stale_days").unwrap(),
+                    root: stats_matches.get_one::<String>("root").cloned(),
+                    from: stats_matches
+                        .get_one::<Timestamp>("from")
+                        .map(|from| from.date),
+                    to: stats_matches.get_one::<Timestamp>("to").map(|to| to.date),
+                    matcher: stats_matches.get_one::<Matcher>("match").cloned(),
+                    format: *stats_matches.get_one::<OutputFormat>("format").unwrap(),
+                },
+                Some(("heatmap", stats_matches)) => Commands::StatsHeatmap {
+                    week_start: stats_matches.get_one::<WeekStart>("week_start").copied(),
+                    colors: stats_matches
+                        .get_many::<String>("colors")
+                        .map(|colors| colors.cloned().collect()),
+                    svg: stats_matches.get_flag("svg"),
+                    root: stats_matches.get_one::<String>("root").cloned(),
+                    from: stats_matches
+                        .get_one::<Timestamp>("from")
+                        .map(|from| from.date),
+                    to: stats_matches.get_one::<Timestamp>("to").map(|to| to.date),
+                    matcher: stats_matches.get_one::<Matcher>("match").cloned(),
+                },
+                _ => unreachable!(),
+            },
+            Some(("review", sub_matches)) => Commands::Review {
+                root: sub_matches.get_one::<String>("root").cloned(),
+                format: *sub_matches.get_one::<OutputFormat>("format").unwrap(),
+            },
+            Some(("extract", sub_matches)) => Commands::Extract {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                search: sub_matches.get_one::<String>("search").unwrap().clone(),
+            },
+            Some(("splice", sub_matches)) => Commands::Splice {
+                extracted_file: sub_matches
+                    .get_one::<String>("extracted_file")
+                    .unwrap()
+                    .clone(),
+                into: sub_matches.get_one::<String>("into").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+            },
+            Some(("split", sub_matches)) => Commands::Split {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                by: *sub_matches.get_one::<SplitBy>("by").unwrap(),
+                out_dir: sub_matches.get_one::<String>("out_dir").unwrap().clone(),
+            },
+            Some(("join", sub_matches)) => Commands::Join {
+                input_files: sub_matches
+                    .get_many::<String>("input_files")
+                    .map(|files| files.cloned().collect())
+                    .unwrap_or_default(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+            },
+            Some(("export", sub_matches)) => match sub_matches.subcommand() {
+                Some(("md", export_matches)) => Commands::ExportMarkdown {
+                    input_file: export_matches.get_one::<String>("input_file").cloned(),
+                    output_file: export_matches.get_one::<String>("output_file").cloned(),
+                    subtree: export_matches.get_one::<String>("subtree").cloned(),
+                },
+                Some(("html", export_matches)) => Commands::ExportHtml {
+                    input_file: export_matches.get_one::<String>("input_file").cloned(),
+                    output_file: export_matches.get_one::<String>("output_file").cloned(),
+                    subtree: export_matches.get_one::<String>("subtree").cloned(),
+                    template: export_matches.get_one::<String>("template").cloned(),
+                    toc_depth: export_matches.get_one::<usize>("toc_depth").copied(),
+                },
+                _ => unreachable!(),
+            },
+            Some(("sort", sub_matches)) => Commands::Sort {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                parent: sub_matches.get_one::<String>("parent").unwrap().clone(),
+                by: sub_matches.get_one::<SortKey>("by").unwrap().clone(),
+                reverse: sub_matches.get_flag("reverse"),
+            },
+            Some(("refile", sub_matches)) => Commands::Refile {
+                input_file: sub_matches.get_one::<String>("input_file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                search: sub_matches.get_one::<String>("search").unwrap().clone(),
+                target: sub_matches.get_one::<String>("target").unwrap().clone(),
+            },
+            Some(("capture", sub_matches)) => Commands::Capture {
+                template: sub_matches.get_one::<String>("template").unwrap().clone(),
+                file: sub_matches.get_one::<String>("file").cloned(),
+                output_file: sub_matches.get_one::<String>("output_file").cloned(),
+                answers: sub_matches
+                    .get_many::<(String, String)>("set")
+                    .map(|answers| answers.cloned().collect())
+                    .unwrap_or_default(),
+                annotation: sub_matches.get_one::<String>("annotation").cloned(),
             },
             _ => unreachable!(),
         };
 
         Cli {
+            config_file,
             keywords_unfinished,
             keywords_finished,
             command,
@@ -149,15 +1051,25 @@ impl Cli {
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     Prune {
         input_file: Option<String>,
         output_file: Option<String>,
+        older_than: Option<i64>,
+        open_descendants: Option<OpenDescendants>,
+        keep_tags: Option<Vec<String>>,
+        dry_run: bool,
+    },
+    Archive {
+        input_file: String,
     },
     Tree {
         input_file: Option<String>,
         sexp: bool,
         sections: bool,
+        matcher: Option<Matcher>,
+        org: bool,
     },
     List {
         input_file: Option<String>,
@@ -166,7 +1078,160 @@ pub enum Commands {
         input_file: Option<String>,
         output_file: Option<String>,
         headline: String,
+        keyword: Option<String>,
+        priority: Option<char>,
+        tags: Vec<String>,
+        scheduled: Option<Timestamp>,
+        deadline: Option<Timestamp>,
+        properties: Vec<(String, String)>,
+        body: Option<String>,
         under: Option<String>,
         after: Option<String>,
+        datetree: Option<DatetreeKind>,
+    },
+    State {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        search: String,
+        /// `None` for the first finished keyword.
+        keyword: Option<String>,
+    },
+    EditSubtree {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        search: String,
+        edit: SubtreeEdit,
+    },
+    Fmt {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        check: bool,
+    },
+    Lint {
+        input_files: Vec<String>,
     },
+    ClockIn {
+        input_file: String,
+        search: String,
+    },
+    /// The input file locates the workspace if none is configured.
+    ClockOut {
+        input_file: Option<String>,
+    },
+    ClockStatus {
+        input_file: Option<String>,
+    },
+    Clocktable {
+        input_files: Vec<String>,
+        from: Option<NaiveDateTime>,
+        to: Option<NaiveDateTime>,
+        by: GroupBy,
+        depth: Option<usize>,
+        format: OutputFormat,
+        update: bool,
+    },
+    Effort {
+        input_files: Vec<String>,
+        depth: Option<usize>,
+        format: OutputFormat,
+    },
+    Habits {
+        input_files: Vec<String>,
+        days_before: i64,
+        days_after: i64,
+        format: OutputFormat,
+    },
+    StatsClosed {
+        by: Period,
+        root: Option<String>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        matcher: Option<Matcher>,
+        format: OutputFormat,
+    },
+    StatsFlow {
+        by: FlowGroup,
+        period: Period,
+        stale_days: i64,
+        root: Option<String>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        matcher: Option<Matcher>,
+        format: OutputFormat,
+    },
+    StatsHeatmap {
+        week_start: Option<WeekStart>,
+        colors: Option<Vec<String>>,
+        svg: bool,
+        root: Option<String>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        matcher: Option<Matcher>,
+    },
+    Review {
+        root: Option<String>,
+        format: OutputFormat,
+    },
+    Extract {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        search: String,
+    },
+    Splice {
+        extracted_file: String,
+        into: Option<String>,
+        output_file: Option<String>,
+    },
+    Split {
+        input_file: Option<String>,
+        by: SplitBy,
+        out_dir: String,
+    },
+    Join {
+        input_files: Vec<String>,
+        output_file: Option<String>,
+    },
+    ExportMarkdown {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        subtree: Option<String>,
+    },
+    ExportHtml {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        subtree: Option<String>,
+        template: Option<String>,
+        toc_depth: Option<usize>,
+    },
+    Sort {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        parent: String,
+        by: SortKey,
+        reverse: bool,
+    },
+    Refile {
+        input_file: Option<String>,
+        output_file: Option<String>,
+        search: String,
+        target: String,
+    },
+    Capture {
+        template: String,
+        file: Option<String>,
+        output_file: Option<String>,
+        answers: Vec<(String, String)>,
+        annotation: Option<String>,
+    },
+}
+
+/// `$ORGTOOLS_CONFIG`, or `config.toml` in the orgtools XDG config directory.
+fn default_config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("ORGTOOLS_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("orgtools").join("config.toml"))
 }
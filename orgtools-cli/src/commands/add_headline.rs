@@ -1,19 +1,51 @@
 use crate::utils::fs::{read_input, write_output};
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, Utc};
+use anyhow::{bail, Result};
+use chrono::{Local, NaiveDateTime};
 use orgtools::config::Config;
-use orgtools::org::{Org, OutputBuilder, Position, Section};
+use orgtools::subtree::{add_subtree, Target};
+use orgtools::timestamp::Timestamp;
+use std::io::{self, Read};
 
+/// The contents of a headline to add.
+#[derive(Debug, Default)]
+pub struct NewHeadline {
+    pub title: String,
+    pub keyword: Option<String>,
+    pub priority: Option<char>,
+    pub tags: Vec<String>,
+    pub scheduled: Option<Timestamp>,
+    pub deadline: Option<Timestamp>,
+    pub properties: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// Add a headline to a file.
+///
+/// A body of `-` is read from stdin.
 pub fn add_headline(
     config: &Config,
     input_file: Option<&str>,
     output_file: Option<&str>,
-    headline: &str,
-    position: Position,
-    search: &str,
+    mut headline: NewHeadline,
+    target: Target,
 ) -> Result<()> {
+    if headline.body.as_deref() == Some("-") {
+        if input_file.is_none() {
+            bail!("Cannot read both the input file and the body from stdin");
+        }
+        let mut body = String::new();
+        io::stdin().read_to_string(&mut body)?;
+        headline.body = Some(body);
+    }
+
     let input = read_input(input_file)?;
-    let output = add_headline_to_input(config, &input, headline, position, search)?;
+    let output = add_headline_to_input(
+        config,
+        &input,
+        &headline,
+        target,
+        Local::now().naive_local(),
+    )?;
 
     write_output(input_file, output_file, &output)?;
 
@@ -23,59 +55,92 @@ pub fn add_headline(
 fn add_headline_to_input(
     config: &Config,
     input: &str,
-    headline: &str,
-    position: Position,
-    search: &str,
+    headline: &NewHeadline,
+    target: Target,
+    now: NaiveDateTime,
 ) -> Result<String> {
-    let org_file = Org::from_config(config.clone()).load(input);
-    let mut output = org_file.output_builder();
-    if let Some(section) = org_file.find_section(search) {
-        add_headline_to_section(&section, &mut output, headline, position)?;
-        Ok(output.append_to_end_of_input())
-    } else {
-        Err(anyhow!("Could not find section with headline: {}", search))
+    if let Some(keyword) = &headline.keyword {
+        if !config.is_keyword(keyword) {
+            bail!("Unknown keyword: {}", keyword);
+        }
     }
-}
 
-fn add_headline_to_section(
-    section: &Section,
-    output: &mut OutputBuilder,
-    headline: &str,
-    position: Position,
-) -> Result<()> {
-    output.append_to_end_of_section(section);
-    let num_stars = match position {
-        Position::After => section.stars(),
-        Position::Under => section.stars() + 1,
-    };
-
-    // :PROPERTIES:
-    // :CREATED: [2021-08-15 Sun 14:00]
-    // :END:
-    output.insert_text(&make_headline(num_stars, headline));
-    Ok(())
+    add_subtree(config, input, &make_headline(1, headline, now), target)
 }
 
-fn make_headline(num_stars: usize, headline: &str) -> String {
-    let now: DateTime<Utc> = Utc::now();
+fn make_headline(num_stars: usize, headline: &NewHeadline, now: NaiveDateTime) -> String {
     let stamp = now.format("%Y-%m-%d %a %H:%M").to_string();
-    let stars = "*".repeat(num_stars);
+    let mut text = "*".repeat(num_stars);
 
-    format!(
-        r#"
-{stars} {headline}
-:PROPERTIES:
-:CREATED: [{stamp}]
-:END:
-"#
-    )
-    .trim_start()
-    .to_owned()
+    if let Some(keyword) = &headline.keyword {
+        text.push(' ');
+        text.push_str(keyword);
+    }
+    if let Some(priority) = headline.priority {
+        text.push_str(&format!(" [#{}]", priority));
+    }
+    text.push(' ');
+    text.push_str(headline.title.trim());
+    if !headline.tags.is_empty() {
+        text.push_str(&format!(" :{}:", headline.tags.join(":")));
+    }
+    text.push('\n');
+
+    let planning = [
+        ("DEADLINE", &headline.deadline),
+        ("SCHEDULED", &headline.scheduled),
+    ]
+    .into_iter()
+    .filter_map(|(name, timestamp)| {
+        timestamp
+            .as_ref()
+            .map(|timestamp| format!("{}: {}", name, timestamp))
+    })
+    .collect::<Vec<_>>();
+    if !planning.is_empty() {
+        text.push_str(&planning.join(" "));
+        text.push('\n');
+    }
+
+    text.push_str(":PROPERTIES:\n");
+    text.push_str(&format!(":CREATED: [{stamp}]\n"));
+    for (key, value) in &headline.properties {
+        text.push_str(&format!(":{}: {}\n", key, value));
+    }
+    text.push_str(":END:\n");
+
+    if let Some(body) = &headline.body {
+        let body = body.trim_end();
+        if !body.is_empty() {
+            text.push_str(body);
+            text.push('\n');
+        }
+    }
+
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{Duration, NaiveDate};
+    use orgtools::flow::task_flows;
+    use orgtools::org::{Org, Position};
+    use orgtools::state::change_state;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(14, 0, 0)
+            .unwrap()
+    }
+
+    fn new_headline(title: &str) -> NewHeadline {
+        NewHeadline {
+            title: title.to_string(),
+            ..NewHeadline::default()
+        }
+    }
 
     fn assert_add_headline(
         input: &str,
@@ -87,8 +152,16 @@ mod tests {
         let config = Config {
             keywords_finished: vec!["DONE".to_string(), "CANCELLED".to_string()],
             keywords_unfinished: vec!["TODO".to_string()],
+            ..Config::default()
         };
-        let result = add_headline_to_input(&config, input, headline, position, search).unwrap();
+        let result = add_headline_to_input(
+            &config,
+            input,
+            &new_headline(headline),
+            Target::Section(position, search),
+            now(),
+        )
+        .unwrap();
         assert_eq!(result, expected_output);
     }
 
@@ -97,7 +170,7 @@ mod tests {
         let input = "* TODO Task 1\n** DONE Subtask 1\n** Subtask 2\n* CANCELLED Task 2\n* Task 3";
         let search = "Task 1";
         let headline = "New Task";
-        let expected_headline = make_headline(1, headline);
+        let expected_headline = make_headline(1, &new_headline(headline), now());
         let expected_output =
             format!("* TODO Task 1\n** DONE Subtask 1\n** Subtask 2\n{expected_headline}* CANCELLED Task 2\n* Task 3");
         assert_add_headline(input, search, headline, Position::After, &expected_output);
@@ -108,8 +181,83 @@ mod tests {
         let input = "* TODO Task 1\n** DONE Subtask 1\n** Subtask 2\n* CANCELLED Task 2\n* Task 3";
         let search = "Task 1";
         let headline = "New Task";
-        let expected_headline = make_headline(2, headline);
+        let expected_headline = make_headline(2, &new_headline(headline), now());
         let expected_output = format!("* TODO Task 1\n** DONE Subtask 1\n** Subtask 2\n{expected_headline}* CANCELLED Task 2\n* Task 3");
         assert_add_headline(input, search, headline, Position::Under, &expected_output);
     }
+
+    #[test]
+    fn test_add_headline_at_end_of_file() {
+        let config = Config::default();
+        let input = "* Task 1";
+        let result =
+            add_headline_to_input(&config, input, &new_headline("Task 2"), Target::End, now())
+                .unwrap();
+        assert_eq!(
+            result,
+            "* Task 1\n* Task 2\n:PROPERTIES:\n:CREATED: [2026-10-17 Sat 14:00]\n:END:\n"
+        );
+    }
+
+    #[test]
+    fn test_created_and_closed_give_lead_time() {
+        // Given
+        let config = Config::default();
+        let headline = NewHeadline {
+            keyword: Some("TODO".to_string()),
+            ..new_headline("Task")
+        };
+        let added = add_headline_to_input(&config, "", &headline, Target::End, now()).unwrap();
+
+        // When
+        let org_file = Org::from_config(config.clone()).load(&added);
+        let mut entry = org_file.find_section("Task").unwrap().entry();
+        change_state(
+            &mut entry,
+            &config,
+            Some("DONE"),
+            now() + Duration::hours(2),
+        )
+        .unwrap();
+        let flows = task_flows(&config, &entry.to_string(), "tasks.org", None);
+
+        // Then
+        assert_eq!(flows[0].lead_time(), Some(120));
+    }
+
+    #[test]
+    fn test_make_headline_with_options() {
+        let today = now().date();
+        let headline = NewHeadline {
+            title: "Write report".to_string(),
+            keyword: Some("TODO".to_string()),
+            priority: Some('A'),
+            tags: vec!["work".to_string(), "urgent".to_string()],
+            scheduled: Some(Timestamp::parse_user_input("2026-10-18", true, today).unwrap()),
+            deadline: Some(Timestamp::parse_user_input("2026-10-20 17:00", true, today).unwrap()),
+            properties: vec![("EFFORT".to_string(), "1:00".to_string())],
+            body: Some("Some notes\n\n".to_string()),
+        };
+        assert_eq!(
+            make_headline(2, &headline, now()),
+            r#"** TODO [#A] Write report :work:urgent:
+DEADLINE: <2026-10-20 Tue 17:00> SCHEDULED: <2026-10-18 Sun>
+:PROPERTIES:
+:CREATED: [2026-10-17 Sat 14:00]
+:EFFORT: 1:00
+:END:
+Some notes
+"#
+        );
+    }
+
+    #[test]
+    fn test_add_headline_unknown_keyword() {
+        let config = Config::default();
+        let headline = NewHeadline {
+            keyword: Some("LATER".to_string()),
+            ..new_headline("Task")
+        };
+        assert!(add_headline_to_input(&config, "", &headline, Target::End, now()).is_err());
+    }
 }
@@ -0,0 +1,284 @@
+//! Move finished subtrees to an archive file.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::utils::fs::{expand_home, write_atomically};
+use anyhow::Result;
+use chrono::{Local, NaiveDateTime};
+use orgtools::archive::{
+    add_archive_properties, add_to_archive, file_keyword, ArchiveInfo, ArchiveLocation,
+    DEFAULT_ARCHIVE_LOCATION,
+};
+use orgtools::config::Config;
+use orgtools::entry::Entry;
+use orgtools::org::{Keyword, Org, OutputBuilder, Section};
+use orgtools::outline::relevel;
+
+/// Archive the finished subtrees of a file.
+///
+/// The file and all the archive files are written together once every
+/// subtree has been moved.
+pub fn archive(config: &Config, input_file: &str) -> Result<()> {
+    let source = fs::canonicalize(input_file)?;
+    let input = fs::read_to_string(&source)?;
+    let archived = archive_from_input(config, &input, &source, Local::now().naive_local())?;
+
+    let directory = source.parent().unwrap_or(Path::new("/"));
+    let mut files = BTreeMap::new();
+    files.insert(source.clone(), archived.source);
+    for (location, subtree) in &archived.subtrees {
+        let path = match &location.file {
+            Some(file) => directory.join(expand_home(file)),
+            None => source.clone(),
+        };
+        if !files.contains_key(&path) {
+            let content = if path.exists() {
+                fs::read_to_string(&path)?
+            } else {
+                String::new()
+            };
+            files.insert(path.clone(), content);
+        }
+        let content = files.get_mut(&path).expect("archive file was just loaded");
+        *content = add_to_archive(config, content, location.heading.as_deref(), subtree)?;
+    }
+
+    write_atomically(&files.into_iter().collect::<Vec<_>>())?;
+
+    Ok(())
+}
+
+struct Archived {
+    /// The input with the archived subtrees removed.
+    source: String,
+    /// The archived subtrees at level 1, with their archive locations.
+    subtrees: Vec<(ArchiveLocation, String)>,
+}
+
+/// The context for archiving the subtrees of one file.
+struct Archiver<'a> {
+    file: String,
+    file_name: String,
+    location: &'a str,
+    /// The top-level headings of this file that entries are archived under,
+    /// whose entries are already archived.
+    headings: Vec<String>,
+    category: String,
+    now: NaiveDateTime,
+}
+
+fn archive_from_input(
+    config: &Config,
+    input: &str,
+    source: &Path,
+    now: NaiveDateTime,
+) -> Result<Archived> {
+    let org = Org::from_config(config.clone()).load(input);
+    let category = match file_keyword(input, "CATEGORY") {
+        Some(category) => category.to_string(),
+        None => source
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let location = file_keyword(input, "ARCHIVE").unwrap_or(DEFAULT_ARCHIVE_LOCATION);
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let properties = org
+        .outline()
+        .into_iter()
+        .filter_map(|item| item.section.entry().property("ARCHIVE"));
+    let headings = std::iter::once(location.to_string())
+        .chain(properties)
+        .filter_map(|location| {
+            let target = ArchiveLocation::parse(&location, &file_name);
+            match target.file {
+                Some(file) if file != file_name => None,
+                _ => target.heading,
+            }
+        })
+        .collect();
+    let archiver = Archiver {
+        file: source.display().to_string(),
+        file_name,
+        location,
+        headings,
+        category,
+        now,
+    };
+
+    let mut output = org.output_builder();
+    let mut subtrees = vec![];
+    for section in org.subsections() {
+        if section
+            .headline_text()
+            .is_some_and(|title| archiver.headings.iter().any(|heading| heading == title))
+        {
+            continue;
+        }
+        archiver.archive_section(input, &section, &mut vec![], &mut output, &mut subtrees)?;
+    }
+
+    Ok(Archived {
+        source: output.append_to_end_of_input(),
+        subtrees,
+    })
+}
+
+impl Archiver<'_> {
+    fn archive_section(
+        &self,
+        input: &str,
+        section: &Section,
+        ancestors: &mut Vec<Entry>,
+        output: &mut OutputBuilder,
+        subtrees: &mut Vec<(ArchiveLocation, String)>,
+    ) -> Result<()> {
+        let mut entry = section.entry();
+        if entry.headline().tags.iter().any(|tag| tag == "ARCHIVE") {
+            return Ok(());
+        }
+        if let Keyword::Finished(_) = section.keyword() {
+            let inherited = |name: &str| {
+                std::iter::once(&entry)
+                    .chain(ancestors.iter().rev())
+                    .find_map(|entry| entry.property(name))
+            };
+            let location = inherited("ARCHIVE").unwrap_or_else(|| self.location.to_string());
+            let info = ArchiveInfo {
+                time: self.now,
+                file: self.file.clone(),
+                outline_path: ancestors
+                    .iter()
+                    .map(|entry| entry.headline().title.clone())
+                    .collect(),
+                category: inherited("CATEGORY").unwrap_or_else(|| self.category.clone()),
+            };
+            add_archive_properties(&mut entry, &info);
+
+            let mut subtree = entry.to_string();
+            subtree.push_str(&input[section.content_end_byte()..section.end_byte()]);
+            if !subtree.ends_with('\n') {
+                subtree.push('\n');
+            }
+            subtrees.push((
+                ArchiveLocation::parse(&location, &self.file_name),
+                relevel(&subtree, 1)?,
+            ));
+
+            output.append_to(section.start_byte());
+            output.skip_to(section.end_byte());
+        } else {
+            ancestors.push(entry);
+            for subsection in section.subsections() {
+                self.archive_section(input, &subsection, ancestors, output, subtrees)?;
+            }
+            ancestors.pop();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    fn archive_tasks(input: &str) -> Archived {
+        archive_from_input(
+            &Config::default(),
+            input,
+            Path::new("/notes/tasks.org"),
+            now(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_archive_to_default_location() {
+        let archived = archive_tasks("* Projects\n** DONE Paint\n*** Buy paint\n** TODO Sand\n");
+        assert_eq!(archived.source, "* Projects\n** TODO Sand\n");
+        assert_eq!(archived.subtrees.len(), 1);
+        let (location, subtree) = &archived.subtrees[0];
+        assert_eq!(location.file.as_deref(), Some("tasks.org_archive"));
+        assert_eq!(
+            subtree,
+            "* DONE Paint\n:PROPERTIES:\n:ARCHIVE_TIME: [2026-10-17 Sat 10:00]\n:ARCHIVE_FILE: /notes/tasks.org\n:ARCHIVE_OLPATH: Projects\n:ARCHIVE_CATEGORY: tasks\n:ARCHIVE_TODO: DONE\n:END:\n** Buy paint\n"
+        );
+    }
+
+    #[test]
+    fn test_archive_location_from_file_and_property() {
+        let input = "#+ARCHIVE: ::* Archive\n#+CATEGORY: home\n* Garden\n:PROPERTIES:\n:ARCHIVE: garden.org::\n:END:\n** DONE Mow\n* DONE Dishes\n";
+        let archived = archive_tasks(input);
+        let locations = archived
+            .subtrees
+            .iter()
+            .map(|(location, _)| location.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locations,
+            vec![
+                ArchiveLocation::parse("garden.org::", "tasks.org"),
+                ArchiveLocation::parse("::Archive", "tasks.org"),
+            ]
+        );
+        assert!(archived.subtrees[1].1.contains(":ARCHIVE_CATEGORY: home\n"));
+    }
+
+    #[test]
+    fn test_archive_twice_into_same_file() {
+        // Given
+        let config = Config::default();
+        let input = "#+ARCHIVE: ::* Archive\n* Projects\n** DONE Paint\n** TODO Sand\n";
+        let archived = archive_tasks(input);
+        let mut output = archived.source;
+        for (location, subtree) in &archived.subtrees {
+            output =
+                add_to_archive(&config, &output, location.heading.as_deref(), subtree).unwrap();
+        }
+
+        // When
+        let again = archive_tasks(&output);
+
+        // Then
+        assert!(output.contains("* Archive\n** DONE Paint\n:PROPERTIES:\n"));
+        assert!(output.contains(":ARCHIVE_OLPATH: Projects\n"));
+        assert!(again.subtrees.is_empty());
+        assert_eq!(again.source, output);
+    }
+
+    #[test]
+    fn test_archive_skips_headings_archived_to_by_property() {
+        let input = "* Garden\n:PROPERTIES:\n:ARCHIVE: ::* Garden archive\n:END:\n** DONE Mow\n* Garden archive\n** DONE Weed\n";
+        let archived = archive_tasks(input);
+        assert_eq!(
+            archived.source,
+            "* Garden\n:PROPERTIES:\n:ARCHIVE: ::* Garden archive\n:END:\n* Garden archive\n** DONE Weed\n"
+        );
+        assert_eq!(
+            archived.subtrees[0].0,
+            ArchiveLocation::parse("::Garden archive", "tasks.org")
+        );
+        assert_eq!(archived.subtrees.len(), 1);
+    }
+
+    #[test]
+    fn test_archive_skips_archive_tag() {
+        let input = "* Old :ARCHIVE:\n** DONE Paint\n* DONE Sand\n";
+        let archived = archive_tasks(input);
+        assert_eq!(archived.source, "* Old :ARCHIVE:\n** DONE Paint\n");
+        assert_eq!(archived.subtrees.len(), 1);
+    }
+}
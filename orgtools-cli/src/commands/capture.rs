@@ -0,0 +1,213 @@
+//! Add an entry to a file from a capture template.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process;
+
+use crate::utils::fs::{expand_home, write_output};
+use anyhow::{anyhow, bail, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use orgtools::capture::{expand_template, CaptureEnv, CaptureTemplate};
+use orgtools::config::Config;
+use orgtools::outline::headline_level;
+use orgtools::subtree::{add_subtree, Target};
+
+pub fn capture(
+    config: &Config,
+    name: &str,
+    file: Option<&str>,
+    output_file: Option<&str>,
+    answers: &[(String, String)],
+    annotation: Option<&str>,
+) -> Result<()> {
+    let template = config
+        .capture_templates
+        .get(name)
+        .ok_or_else(|| anyhow!("Unknown capture template: {}", name))?;
+    let file = file
+        .or(template.file.as_deref())
+        .ok_or_else(|| anyhow!("No file given for capture template: {}", name))?;
+    let file = expand_home(file);
+
+    let mut env = CliCaptureEnv {
+        now: Local::now().naive_local(),
+        answers: answers.iter().cloned().collect(),
+        annotation: annotation.map(str::to_string),
+        initial_content: None,
+    };
+    let entry = expand_entry(template, &mut env)?;
+
+    let input = if file.exists() {
+        fs::read_to_string(&file)?
+    } else {
+        String::new()
+    };
+    let output = add_subtree(config, &input, &entry, target(template, env.now.date()))?;
+
+    let file = file.to_string_lossy();
+    write_output(Some(&file), output_file, &output)?;
+
+    Ok(())
+}
+
+fn expand_entry(template: &CaptureTemplate, env: &mut dyn CaptureEnv) -> Result<String> {
+    let mut entry = expand_template(&template.template, env)?;
+    if entry.lines().next().and_then(headline_level) != Some(1) {
+        bail!("Capture template must start with a level 1 headline");
+    }
+    if !entry.ends_with('\n') {
+        entry.push('\n');
+    }
+    Ok(entry)
+}
+
+fn target(template: &CaptureTemplate, date: NaiveDate) -> Target {
+    if let Some(kind) = template.datetree {
+        Target::Datetree {
+            kind,
+            date,
+            path: &template.olp,
+        }
+    } else if template.olp.is_empty() {
+        Target::End
+    } else {
+        Target::OutlinePath(&template.olp)
+    }
+}
+
+/// Takes prompt answers from `--set`, falling back to asking on the terminal.
+struct CliCaptureEnv {
+    now: NaiveDateTime,
+    answers: HashMap<String, String>,
+    annotation: Option<String>,
+    initial_content: Option<String>,
+}
+
+impl CaptureEnv for CliCaptureEnv {
+    fn now(&self) -> NaiveDateTime {
+        self.now
+    }
+
+    fn initial_content(&mut self) -> Result<String> {
+        if self.initial_content.is_none() {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            self.initial_content = Some(content);
+        }
+        Ok(self.initial_content.clone().unwrap_or_default())
+    }
+
+    fn annotation(&self) -> Option<String> {
+        self.annotation.clone()
+    }
+
+    fn prompt(&mut self, prompt: &str, default: Option<&str>) -> Result<String> {
+        if let Some(answer) = self.answers.get(prompt) {
+            return Ok(answer.clone());
+        }
+        // stdin may be in use for %i so ask on the terminal directly.
+        let tty = fs::File::options()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(|_| anyhow!("No answer for prompt {:?}, pass it with --set", prompt))?;
+        let mut writer = tty.try_clone()?;
+        match default {
+            Some(default) => write!(writer, "{} [{}]: ", prompt, default)?,
+            None => write!(writer, "{}: ", prompt)?,
+        }
+        writer.flush()?;
+        let mut answer = String::new();
+        BufReader::new(tty).read_line(&mut answer)?;
+        let answer = answer.trim();
+        Ok(match default {
+            Some(default) if answer.is_empty() => default.to_string(),
+            _ => answer.to_string(),
+        })
+    }
+
+    fn shell(&mut self, command: &str) -> Result<String> {
+        let output = process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .output()?;
+        if !output.status.success() {
+            bail!("Command {:?} failed: {}", command, output.status);
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orgtools::datetree::DatetreeKind;
+
+    fn env(answers: &[(&str, &str)]) -> CliCaptureEnv {
+        CliCaptureEnv {
+            now: NaiveDate::from_ymd_opt(2026, 10, 17)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap(),
+            answers: answers
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            annotation: None,
+            initial_content: Some("From stdin".to_string()),
+        }
+    }
+
+    fn template(text: &str) -> CaptureTemplate {
+        CaptureTemplate {
+            template: text.to_string(),
+            file: None,
+            olp: vec![],
+            datetree: None,
+        }
+    }
+
+    #[test]
+    fn test_capture_into_outline_path() {
+        let config = Config::default();
+        let template = CaptureTemplate {
+            olp: vec!["Meetings".to_string()],
+            ..template("* Meeting with %^{Who} %U\n%i")
+        };
+        let entry = expand_entry(&template, &mut env(&[("Who", "Sam")])).unwrap();
+        let target = target(&template, NaiveDate::from_ymd_opt(2026, 10, 17).unwrap());
+        let output = add_subtree(&config, "* Meetings\n* Other\n", &entry, target).unwrap();
+        assert_eq!(
+            output,
+            "* Meetings\n** Meeting with Sam [2026-10-17 Sat 09:30]\nFrom stdin\n* Other\n"
+        );
+    }
+
+    #[test]
+    fn test_capture_into_datetree() {
+        let config = Config::default();
+        let template = CaptureTemplate {
+            datetree: Some(DatetreeKind::Month),
+            ..template("* %u %^{Title}")
+        };
+        let entry = expand_entry(&template, &mut env(&[("Title", "Standup")])).unwrap();
+        let target = target(&template, NaiveDate::from_ymd_opt(2026, 10, 17).unwrap());
+        let output = add_subtree(&config, "", &entry, target).unwrap();
+        assert_eq!(
+            output,
+            "* 2026\n** 2026-10 October\n*** [2026-10-17 Sat] Standup\n"
+        );
+    }
+
+    #[test]
+    fn test_capture_requires_headline() {
+        assert!(expand_entry(&template("Just text"), &mut env(&[])).is_err());
+    }
+
+    #[test]
+    fn test_capture_shell_placeholder() {
+        let entry = expand_entry(&template("* %(echo hello)"), &mut env(&[])).unwrap();
+        assert_eq!(entry, "* hello\n");
+    }
+}
@@ -0,0 +1,200 @@
+//! Clock in and out of tasks, with at most one clock running in the workspace.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs::{expand_home, org_files, write_atomically};
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDateTime, Timelike};
+use orgtools::clock::{self, close_running_clocks, format_minutes, heading_clocks, HeadingClock};
+use orgtools::config::Config;
+use orgtools::org::Org;
+use orgtools::timestamp::Timestamp;
+
+/// Start a clock on the first section matching `search`, stopping any clock
+/// running elsewhere in the workspace first.
+pub fn clock_in(config: &Config, input_file: &str, search: &str) -> Result<()> {
+    let source = fs::canonicalize(input_file)?;
+    let now = now();
+    // Find the section before clocking out of anything.
+    let input = fs::read_to_string(&source)?;
+    let clocked_in = clock_in_input(config, &input, search, now)?;
+
+    let mut files = vec![];
+    for (name, path) in workspace_files(config, Some(&source))? {
+        let input = fs::read_to_string(&path)?;
+        print_clocked_out(config, &name, &input, now);
+        if path == source {
+            continue;
+        }
+        if let Some(output) = close_running_clocks(&input, now) {
+            files.push((path, output));
+        }
+    }
+    files.push((source, clocked_in));
+
+    write_atomically(&files)?;
+    Ok(())
+}
+
+/// Stop the running clock in the workspace.
+pub fn clock_out(config: &Config, input_file: Option<&str>) -> Result<()> {
+    let now = now();
+    let mut files = vec![];
+    for (name, path) in workspace_files(config, input_file.map(Path::new))? {
+        let input = fs::read_to_string(&path)?;
+        print_clocked_out(config, &name, &input, now);
+        if let Some(output) = close_running_clocks(&input, now) {
+            files.push((path, output));
+        }
+    }
+
+    if files.is_empty() {
+        println!("No clock running");
+    }
+    write_atomically(&files)?;
+    Ok(())
+}
+
+/// Print the running clock in the workspace.
+pub fn clock_status(config: &Config, input_file: Option<&str>) -> Result<()> {
+    let now = now();
+    let mut running = false;
+    for (name, path) in workspace_files(config, input_file.map(Path::new))? {
+        let input = fs::read_to_string(&path)?;
+        for clock in running_clocks(config, &input) {
+            println!(
+                "{}: {} since {} ({})",
+                name,
+                clock.outline_path.join(" / "),
+                Timestamp::inactive_now(clock.clock.start),
+                format_minutes((now - clock.clock.start).num_minutes())
+            );
+            running = true;
+        }
+    }
+
+    if !running {
+        println!("No clock running");
+    }
+    Ok(())
+}
+
+/// The current time to the minute, as clocks record it.
+fn now() -> NaiveDateTime {
+    let now = Local::now().naive_local();
+    now.with_second(0)
+        .and_then(|now| now.with_nanosecond(0))
+        .unwrap_or(now)
+}
+
+/// The org files in the workspace, or in the input file's directory if
+/// there is no workspace, with their names relative to that directory.
+///
+/// The input file is included even if it is outside the workspace.
+fn workspace_files(config: &Config, input_file: Option<&Path>) -> Result<Vec<(String, PathBuf)>> {
+    let directory = match (&config.workspace, input_file) {
+        (Some(workspace), _) => expand_home(workspace),
+        (None, Some(input_file)) => input_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+        (None, None) => env::current_dir()?,
+    };
+    let directory = match directory.as_os_str().is_empty() {
+        true => PathBuf::from("."),
+        false => directory,
+    };
+
+    let mut paths = org_files(&directory)?
+        .into_iter()
+        .map(fs::canonicalize)
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(input_file) = input_file {
+        let input_file = fs::canonicalize(input_file)?;
+        if !paths.contains(&input_file) {
+            paths.push(input_file);
+        }
+    }
+
+    let directory = fs::canonicalize(&directory)?;
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let name = path.strip_prefix(&directory).unwrap_or(&path);
+            (name.display().to_string(), path)
+        })
+        .collect())
+}
+
+fn running_clocks(config: &Config, input: &str) -> Vec<HeadingClock> {
+    heading_clocks(config, input)
+        .into_iter()
+        .filter(|heading_clock| heading_clock.clock.is_running())
+        .collect()
+}
+
+fn print_clocked_out(config: &Config, name: &str, input: &str, now: NaiveDateTime) {
+    for clock in running_clocks(config, input) {
+        println!(
+            "Clocked out of {}: {} ({})",
+            name,
+            clock.outline_path.join(" / "),
+            format_minutes((now - clock.clock.start).num_minutes().max(0))
+        );
+    }
+}
+
+/// Stop any running clocks in the input and start one on the first section
+/// matching `search`.
+fn clock_in_input(
+    config: &Config,
+    input: &str,
+    search: &str,
+    now: NaiveDateTime,
+) -> Result<String> {
+    let input = close_running_clocks(input, now).unwrap_or_else(|| input.to_string());
+    let org_file = Org::from_config(config.clone()).load(&input);
+    let section = org_file
+        .find_section(search)
+        .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+
+    let mut entry = section.entry();
+    clock::clock_in(&mut entry, now);
+
+    let mut output = org_file.output_builder();
+    output.append_up_to_section(&section);
+    output.insert_text(&entry.to_string());
+    output.skip_to(section.content_end_byte());
+    Ok(output.append_to_end_of_input())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_clock_in_input() {
+        // Given
+        let input = "* Report\n:LOGBOOK:\nCLOCK: [2026-10-17 Sat 09:00]\n:END:\n* Email\nText\n";
+
+        // When
+        let output = clock_in_input(&Config::default(), input, "Email", at(9, 45)).unwrap();
+
+        // Then
+        assert_eq!(
+            output,
+            "* Report\n:LOGBOOK:\nCLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 09:45] =>  0:45\n:END:\n* Email\n:LOGBOOK:\nCLOCK: [2026-10-17 Sat 09:45]\n:END:\nText\n"
+        );
+        assert!(clock_in_input(&Config::default(), input, "Other", at(9, 45)).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Report the time clocked in org files.
+
+use crate::utils::fs::{read_input, write_output};
+use anyhow::Result;
+use chrono::NaiveDate;
+use orgtools::clocktable::{
+    clock_records, clock_table, group_name, update_clocktable_blocks, ClockTableOptions,
+};
+use orgtools::config::Config;
+use orgtools::report::OutputFormat;
+
+/// Print a report of the time clocked in the files, or in stdin if there
+/// are none.
+///
+/// With `update` the `#+BEGIN: clocktable` blocks in each file are
+/// rewritten instead, using the blocks' own parameters.
+pub fn clocktable(
+    config: &Config,
+    input_files: &[String],
+    options: &ClockTableOptions,
+    format: OutputFormat,
+    update: bool,
+    today: NaiveDate,
+) -> Result<()> {
+    let stdin = [None];
+    let files = input_files
+        .iter()
+        .map(|file| Some(file.as_str()))
+        .collect::<Vec<_>>();
+    let files = if files.is_empty() {
+        &stdin[..]
+    } else {
+        &files[..]
+    };
+
+    let mut inputs = vec![];
+    for file in files {
+        let input = read_input(*file)?;
+        if update {
+            let output =
+                update_clocktable_blocks(config, &input, file.unwrap_or("<stdin>"), today)?;
+            if file.is_none() || output != input {
+                write_output(*file, None, &output)?;
+            }
+        } else {
+            inputs.push((file.unwrap_or("<stdin>").to_string(), input));
+        }
+    }
+
+    if !update {
+        print!(
+            "{}",
+            clocktable_from_inputs(config, &inputs, options, format)
+        );
+    }
+    Ok(())
+}
+
+/// Render a report of the time clocked in `(file name, contents)` pairs.
+fn clocktable_from_inputs(
+    config: &Config,
+    inputs: &[(String, String)],
+    options: &ClockTableOptions,
+    format: OutputFormat,
+) -> String {
+    let records = inputs
+        .iter()
+        .flat_map(|(file, input)| clock_records(config, input, file))
+        .collect::<Vec<_>>();
+    clock_table(&records, options).render(format, group_name(options.by))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orgtools::clocktable::GroupBy;
+
+    #[test]
+    fn test_clocktable_from_inputs() {
+        let inputs = [
+            (
+                "work.org".to_string(),
+                "* Report\nCLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 10:30] =>  1:30\n"
+                    .to_string(),
+            ),
+            (
+                "home.org".to_string(),
+                "* Garden\nCLOCK: [2026-10-18 Sun 09:00]--[2026-10-18 Sun 09:30] =>  0:30\n"
+                    .to_string(),
+            ),
+        ];
+        let options = ClockTableOptions {
+            by: GroupBy::File,
+            ..ClockTableOptions::default()
+        };
+
+        assert_eq!(
+            clocktable_from_inputs(&Config::default(), &inputs, &options, OutputFormat::Csv),
+            "File,minutes,time,percent\nwork.org,90,1:30,75.0\nhome.org,30,0:30,25.0\nTotal,120,2:00,100.0\n"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Promote, demote or move a subtree.
+
+use crate::utils::fs::{read_input, write_output};
+use anyhow::{anyhow, Result};
+use orgtools::config::Config;
+use orgtools::org::Org;
+use orgtools::subtree::SubtreeEdit;
+
+pub fn edit_subtree(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    search: &str,
+    edit: SubtreeEdit,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let output = edit_subtree_in_input(config, &input, search, edit)?;
+
+    write_output(input_file, output_file, &output)?;
+
+    Ok(())
+}
+
+fn edit_subtree_in_input(
+    config: &Config,
+    input: &str,
+    search: &str,
+    edit: SubtreeEdit,
+) -> Result<String> {
+    let org_file = Org::from_config(config.clone()).load(input);
+    let section = org_file
+        .find_section(search)
+        .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+    orgtools::subtree::edit_subtree(&org_file, &section, edit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_subtree_in_input() {
+        let config = Config::default();
+        let input = "* Projects\n** TODO House\n*** Paint\n** Garden\n";
+        let output = edit_subtree_in_input(&config, input, "House", SubtreeEdit::MoveDown).unwrap();
+        assert_eq!(output, "* Projects\n** Garden\n** TODO House\n*** Paint\n");
+        assert!(edit_subtree_in_input(&config, input, "Shed", SubtreeEdit::Promote).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Compare effort estimates with the time clocked.
+
+use crate::utils::fs::read_input;
+use anyhow::Result;
+use orgtools::config::Config;
+use orgtools::effort::{effort_rows, EffortReport};
+use orgtools::report::OutputFormat;
+
+/// Print the estimates and clocked time of the tasks in the files, or in
+/// stdin if there are none.
+pub fn effort_report(
+    config: &Config,
+    input_files: &[String],
+    depth: Option<usize>,
+    format: OutputFormat,
+) -> Result<()> {
+    let stdin = [None];
+    let files = input_files
+        .iter()
+        .map(|file| Some(file.as_str()))
+        .collect::<Vec<_>>();
+    let files = if files.is_empty() {
+        &stdin[..]
+    } else {
+        &files[..]
+    };
+
+    let mut inputs = vec![];
+    for file in files {
+        inputs.push((file.unwrap_or("<stdin>").to_string(), read_input(*file)?));
+    }
+    print!(
+        "{}",
+        effort_report_from_inputs(config, &inputs, depth, format)?
+    );
+    Ok(())
+}
+
+/// Render the report for `(file name, contents)` pairs.
+fn effort_report_from_inputs(
+    config: &Config,
+    inputs: &[(String, String)],
+    depth: Option<usize>,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut rows = vec![];
+    for (file, input) in inputs {
+        rows.extend(effort_rows(config, input, file)?);
+    }
+    Ok(EffortReport::new(rows, depth).render(format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effort_report_from_inputs() {
+        let inputs = [
+            (
+                "work.org".to_string(),
+                "* Report\n:PROPERTIES:\n:EFFORT: 1h\n:END:\nCLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 09:30] =>  0:30\n".to_string(),
+            ),
+            (
+                "home.org".to_string(),
+                "* Garden\n:PROPERTIES:\n:EFFORT: 30min\n:END:\n".to_string(),
+            ),
+        ];
+
+        assert_eq!(
+            effort_report_from_inputs(&Config::default(), &inputs, None, OutputFormat::Csv)
+                .unwrap(),
+            "Heading,Estimate,Actual,Difference,%\nReport,60,30,-30,50\nGarden,30,0,-30,0\nTotal,90,30,-60,33\n"
+        );
+
+        let invalid = [(
+            "work.org".to_string(),
+            "* Report\n:PROPERTIES:\n:EFFORT: soon\n:END:\n".to_string(),
+        )];
+        assert!(
+            effort_report_from_inputs(&Config::default(), &invalid, None, OutputFormat::Table)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Export a file, or one of its subtrees, to another format.
+
+use std::fs;
+use std::io::{self, Write};
+
+use crate::utils::fs::{expand_home, read_input};
+use anyhow::{anyhow, Context, Result};
+use orgtools::config::Config;
+use orgtools::document::{parse_document, Document};
+use orgtools::html::{to_html, HtmlOptions, DEFAULT_TEMPLATE};
+use orgtools::markdown::to_markdown;
+use orgtools::org::Org;
+use orgtools::outline::relevel;
+
+/// The document of the input, or of the subtree with the headline
+/// `subtree` with its levels starting at 1.
+fn document_from_input(config: &Config, input: &str, subtree: Option<&str>) -> Result<Document> {
+    let Some(search) = subtree else {
+        return Ok(parse_document(config, input));
+    };
+    let org_file = Org::from_config(config.clone()).load(input);
+    let section = org_file
+        .find_section(search)
+        .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+    Ok(parse_document(config, &relevel(section.text(), 1)?))
+}
+
+fn write_export(output_file: Option<&str>, output: &str) -> Result<()> {
+    match output_file {
+        Some(output_file) => fs::write(output_file, output)?,
+        None => io::stdout().write_all(output.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Write the input as Markdown to `output_file`, or stdout.
+pub fn export_markdown(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    subtree: Option<&str>,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let document = document_from_input(config, &input, subtree)?;
+    write_export(output_file, &to_markdown(&document))
+}
+
+/// Write the input as an HTML page to `output_file`, or stdout, using the
+/// template file in `options` or the default template.
+pub fn export_html(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    subtree: Option<&str>,
+    options: &HtmlOptions,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let document = document_from_input(config, &input, subtree)?;
+    let template = match &options.template {
+        Some(template) => fs::read_to_string(expand_home(template))
+            .with_context(|| format!("Error reading template {}", template))?,
+        None => DEFAULT_TEMPLATE.to_string(),
+    };
+    let page = to_html(config, &document, options);
+    write_export(output_file, &page.render(&template))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_from_input() {
+        let input = "#+TITLE: Plans\n* Home\n** Kitchen\n*** TODO Paint\n* Garden\n";
+        let document = document_from_input(&Config::default(), input, Some("Kitchen")).unwrap();
+        assert_eq!(to_markdown(&document), "# Kitchen\n\n## TODO Paint\n");
+        let document = document_from_input(&Config::default(), input, None).unwrap();
+        assert_eq!(document.headings().count(), 4);
+        assert!(document_from_input(&Config::default(), input, Some("Garage")).is_err());
+    }
+}
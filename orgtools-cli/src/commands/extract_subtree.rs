@@ -0,0 +1,70 @@
+//! Extract a subtree to a standalone file and splice it back.
+
+use std::fs;
+use std::io::{self, Write};
+
+use crate::utils::fs::{read_input, write_output};
+use anyhow::{anyhow, bail, Result};
+use orgtools::config::Config;
+use orgtools::narrow::{self, ExtractHeader};
+use orgtools::org::Org;
+
+/// Write the subtree with the headline `search` to `output_file`, or
+/// stdout, with its levels starting at 1.
+pub fn extract_subtree(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    search: &str,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let output = extract_from_input(config, &input, input_file.unwrap_or(""), search)?;
+    match output_file {
+        Some(output_file) => fs::write(output_file, output)?,
+        None => io::stdout().write_all(output.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn extract_from_input(config: &Config, input: &str, source: &str, search: &str) -> Result<String> {
+    let org_file = Org::from_config(config.clone()).load(input);
+    let section = org_file
+        .find_section(search)
+        .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+    narrow::extract_subtree(&org_file, &section, source)
+}
+
+/// Replace the subtree `extracted_file` was extracted from with its
+/// contents, in `into` or the file it was extracted from.
+pub fn splice_subtree(
+    config: &Config,
+    extracted_file: &str,
+    into: Option<&str>,
+    output_file: Option<&str>,
+) -> Result<()> {
+    let extracted = fs::read_to_string(extracted_file)?;
+    let header = ExtractHeader::parse(&extracted)?;
+    let target = match into {
+        Some(into) => into,
+        None if !header.source.is_empty() => header.source.as_str(),
+        None => bail!("{} was extracted from stdin, use --into", extracted_file),
+    };
+    let input = read_input(Some(target))?;
+    let output = narrow::splice_subtree(config, &input, &extracted)?;
+    write_output(Some(target), output_file, &output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_from_input() {
+        let input = "#+TITLE: Plans\n* Home\n** Kitchen\n*** TODO Paint\n";
+        let output = extract_from_input(&Config::default(), input, "plans.org", "Kitchen").unwrap();
+        assert!(output.starts_with("#+TITLE: Plans\n#+ORGTOOLS_SOURCE: plans.org\n"));
+        assert!(output.ends_with("\n* Kitchen\n** TODO Paint\n"));
+        assert!(extract_from_input(&Config::default(), input, "plans.org", "Garage").is_err());
+    }
+}
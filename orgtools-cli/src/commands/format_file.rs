@@ -0,0 +1,30 @@
+//! Format an org file.
+
+use crate::utils::fs::{read_input, write_output};
+use anyhow::Result;
+use orgtools::config::Config;
+use orgtools::format::format;
+
+/// Format a file, or with `check` only report whether it needs formatting.
+///
+/// Returns `false` if `check` is set and the file isn't formatted.
+pub fn format_file(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    check: bool,
+) -> Result<bool> {
+    let input = read_input(input_file)?;
+    let output = format(config, &input);
+
+    if check {
+        if output != input {
+            eprintln!("{} is not formatted", input_file.unwrap_or("<stdin>"));
+            return Ok(false);
+        }
+    } else {
+        write_output(input_file, output_file, &output)?;
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,34 @@
+//! Check org files for likely mistakes.
+
+use crate::utils::fs::read_input;
+use anyhow::Result;
+use orgtools::config::Config;
+use orgtools::lint::{lint, Level};
+
+/// Print a `file:line:col` diagnostic for every problem in the files, or
+/// in stdin if there are none.
+///
+/// Returns `false` if there were any errors.
+pub fn lint_files(config: &Config, input_files: &[String]) -> Result<bool> {
+    let stdin = [None];
+    let files = input_files
+        .iter()
+        .map(|file| Some(file.as_str()))
+        .collect::<Vec<_>>();
+    let files = if files.is_empty() {
+        &stdin[..]
+    } else {
+        &files[..]
+    };
+
+    let mut ok = true;
+    for file in files {
+        let input = read_input(*file)?;
+        for diagnostic in lint(config, &input)? {
+            println!("{}:{}", file.unwrap_or("<stdin>"), diagnostic);
+            ok &= diagnostic.level < Level::Error;
+        }
+    }
+
+    Ok(ok)
+}
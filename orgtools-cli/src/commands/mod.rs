@@ -1,9 +1,47 @@
 mod add_headline;
+mod archive;
+mod capture;
+mod clock;
+mod clocktable;
+mod edit_subtree;
+mod effort_report;
+mod export_file;
+mod extract_subtree;
+mod format_file;
+mod lint_files;
 mod list_headlines;
 mod print_tree;
 mod prune_done;
+mod refile;
+mod set_state;
+mod show_habits;
+mod sort_subtrees;
+mod split_file;
+mod stats_closed;
+mod stats_flow;
+mod stats_heatmap;
+mod weekly_review;
 
-pub use add_headline::add_headline;
+pub use add_headline::{add_headline, NewHeadline};
+pub use archive::archive;
+pub use capture::capture;
+pub use clock::{clock_in, clock_out, clock_status};
+pub use clocktable::clocktable;
+pub use edit_subtree::edit_subtree;
+pub use effort_report::effort_report;
+pub use export_file::{export_html, export_markdown};
+pub use extract_subtree::{extract_subtree, splice_subtree};
+pub use format_file::format_file;
+pub use lint_files::lint_files;
 pub use list_headlines::list_headlines;
-pub use print_tree::print_tree;
+pub use print_tree::{print_tree, SparseOutput};
 pub use prune_done::prune_done;
+pub use refile::{refile, RefileTarget};
+pub use set_state::set_state;
+pub use show_habits::show_habits;
+pub use sort_subtrees::sort_subtrees;
+pub use split_file::{join_files, split_file};
+pub use stats_closed::{stats_closed, ClosedStats};
+pub use stats_flow::stats_flow;
+pub use stats_heatmap::stats_heatmap;
+pub use weekly_review::weekly_review;
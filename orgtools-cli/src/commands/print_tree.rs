@@ -2,19 +2,34 @@ use std::io;
 
 use crate::utils::fs::read_input;
 use orgtools::config::Config;
+use orgtools::matcher::Matcher;
+use orgtools::narrow::file_keywords;
 use orgtools::org::{Org, OrgFile, Section};
 use orgtools::tree_sitter::{Node, Point};
 
+/// How to print the sections matching a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparseOutput {
+    /// Indented headlines.
+    Text,
+    /// An Org file with the headlines of the ancestors and the entries of the
+    /// matching sections.
+    Org,
+}
+
 pub fn print_tree(
     config: &Config,
     input_file: Option<&str>,
     sexp: bool,
     sections: bool,
+    sparse: Option<(&Matcher, SparseOutput)>,
 ) -> io::Result<()> {
     let input = read_input(input_file)?;
     let org = Org::from_config(config.clone()).load(&input);
     if sexp {
         print_sexp_tree(org.root);
+    } else if let Some((matcher, output)) = sparse {
+        print!("{}", sparse_tree(&org, &input, matcher, output));
     } else if sections {
         print_sections(&org);
     } else {
@@ -83,6 +98,58 @@ fn print_section(section: &Section, indent: usize) {
     }
 }
 
+/// The sections matching `matcher` with the ancestors needed for context.
+///
+/// Matching sections keep their own content but not their children, unless
+/// those match too. Org output starts with the file's `#+` keyword lines,
+/// so settings such as `#+TODO:` still apply.
+fn sparse_tree(org: &OrgFile, input: &str, matcher: &Matcher, output: SparseOutput) -> String {
+    let outline = org.outline();
+    let mut keep = vec![false; outline.len()];
+    let mut matched = vec![false; outline.len()];
+    for (index, item) in outline.iter().enumerate() {
+        if !matcher.matches(&item.section.entry(), &item.tags, item.level()) {
+            continue;
+        }
+        matched[index] = true;
+        keep[index] = true;
+        let mut level = item.level();
+        for ancestor in (0..index).rev() {
+            if outline[ancestor].level() < level {
+                keep[ancestor] = true;
+                level = outline[ancestor].level();
+            }
+        }
+    }
+
+    let mut result = String::new();
+    if output == SparseOutput::Org && keep.contains(&true) {
+        for keyword in file_keywords(input) {
+            result.push_str(keyword);
+            result.push('\n');
+        }
+    }
+    for (index, item) in outline.iter().enumerate().filter(|(index, _)| keep[*index]) {
+        let section = &item.section;
+        match output {
+            SparseOutput::Text => {
+                result.push_str(&"  ".repeat(item.level() - 1));
+                result.push_str(section.headline_text_full().unwrap_or("").trim_end());
+            }
+            SparseOutput::Org => {
+                let text = &input[section.start_byte()..section.content_end_byte()];
+                if matched[index] {
+                    result.push_str(text.trim_end_matches('\n'));
+                } else {
+                    result.push_str(text.lines().next().unwrap_or(""));
+                }
+            }
+        }
+        result.push('\n');
+    }
+    result
+}
+
 fn format_point(point: Point) -> String {
     format!("[{}, {}]", point.row, point.column)
 }
@@ -104,3 +171,48 @@ fn print_manual_tree(node: Node, source_code: &str, indent: usize) {
         print_manual_tree(child, source_code, indent + 2);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#+TITLE: Projects
+#+SEQ_TODO: TODO | DONE
+Some notes on projects.
+#+FILETAGS: :home:
+* Website :work:
+** DONE Pick a host
+** Design
+*** TODO Sketch pages
+Rough ideas.
+*** Notes
+* Garden
+** TODO Dig beds :weekend:
+Before it rains.
+** Compost
+";
+
+    fn sparse(query: &str, output: SparseOutput) -> String {
+        let config = Config::default();
+        let org = Org::from_config(config).load(INPUT);
+        sparse_tree(&org, INPUT, &query.parse().unwrap(), output)
+    }
+
+    #[test]
+    fn test_sparse_tree_text() {
+        assert_eq!(
+            sparse("TODO=\"TODO\"", SparseOutput::Text),
+            "Website\n  Design\n    TODO Sketch pages\nGarden\n  TODO Dig beds\n"
+        );
+        assert_eq!(sparse("someday", SparseOutput::Text), "");
+    }
+
+    #[test]
+    fn test_sparse_tree_org() {
+        assert_eq!(
+            sparse("work+TODO=\"TODO\"|weekend", SparseOutput::Org),
+            "#+TITLE: Projects\n#+SEQ_TODO: TODO | DONE\n#+FILETAGS: :home:\n* Website :work:\n** Design\n*** TODO Sketch pages\nRough ideas.\n* Garden\n** TODO Dig beds :weekend:\nBefore it rains.\n"
+        );
+        assert_eq!(sparse("someday", SparseOutput::Org), "");
+    }
+}
@@ -1,55 +1,49 @@
 //! Remove completed tasks from an org file.
 
 use crate::utils::fs::{read_input, write_output};
+use anyhow::Result;
+use chrono::Local;
 use orgtools::config::Config;
-use orgtools::org::{Keyword, Org, OutputBuilder, Section};
-use std::io;
+use orgtools::prune::prune;
 
+/// Remove finished tasks according to the configured prune policy.
+///
+/// With `dry_run` the report of what would be removed is printed instead.
 pub fn prune_done(
     config: &Config,
     input_file: Option<&str>,
     output_file: Option<&str>,
-) -> io::Result<()> {
+    dry_run: bool,
+) -> Result<()> {
     let input = read_input(input_file)?;
-    let output = prune_done_from_input(config, &input);
+    let pruned = prune(config, &input, Local::now().date_naive())?;
 
-    write_output(input_file, output_file, &output)?;
-
-    Ok(())
-}
-
-fn prune_done_from_input(config: &Config, input: &str) -> String {
-    let org = Org::from_config(config.clone()).load(input);
-    let mut output = org.output_builder();
-    for section in org.subsections() {
-        prune_done_from_section(&section, &mut output);
-    }
-    output.append_to_end_of_input()
-}
-
-fn prune_done_from_section(section: &Section, output: &mut OutputBuilder) {
-    if let Keyword::Finished(_) = section.keyword() {
-        output.append_to(section.start_byte());
-        output.skip_to(section.end_byte());
-    } else {
-        for subsection in section.subsections() {
-            prune_done_from_section(&subsection, output);
+    if dry_run {
+        for item in &pruned.report {
+            println!("{}", item);
         }
+    } else {
+        write_output(input_file, output_file, &pruned.output)?;
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use chrono::NaiveDate;
 
     fn assert_prune_done(input: &str, expected_output: &str) {
         let config = Config {
             keywords_finished: vec!["DONE".to_string(), "CANCELLED".to_string()],
             keywords_unfinished: vec!["TODO".to_string()],
+            ..Config::default()
         };
-        let result = prune_done_from_input(&config, input);
-        assert_eq!(result, expected_output);
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let result = prune(&config, input, today).unwrap();
+        assert_eq!(result.output, expected_output);
     }
     #[test]
     fn test_prune_done() {
@@ -59,6 +53,14 @@ mod tests {
         assert_prune_done(input, expected_output);
     }
 
+    #[test]
+    fn test_prune_done_keeps_open_sub_tasks() {
+        let input = "* DONE Task 1\n** TODO Subtask 1\n* DONE Task 2\n";
+        let expected_output = "* DONE Task 1\n** TODO Subtask 1\n";
+
+        assert_prune_done(input, expected_output);
+    }
+
     #[test]
     fn test_prune_done_sub_tasks() {
         let input = "* TODO Task 1\n** DONE Subtask 1\n*** Subtask 2\n* CANCELLED Task 2\n* Task 3";
@@ -0,0 +1,183 @@
+//! Move a subtree under another heading, in the same file or another one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs::{expand_home, read_input, write_atomically, write_output};
+use anyhow::{anyhow, bail, Result};
+use orgtools::config::Config;
+use orgtools::org::{Org, Position};
+use orgtools::subtree::{add_subtree, cut_subtree, Target};
+
+/// Where to refile a subtree to, written as `heading`, `file::heading` or `file::`.
+#[derive(Debug, PartialEq)]
+pub struct RefileTarget {
+    /// The target file, or `None` for the input file.
+    pub file: Option<String>,
+    /// The heading to file under, or `None` for the top level.
+    pub heading: Option<String>,
+}
+
+impl RefileTarget {
+    pub fn parse(target: &str) -> RefileTarget {
+        let (file, heading) = match target.split_once("::") {
+            Some((file, heading)) => (Some(file.trim()), heading.trim()),
+            None => (None, target.trim()),
+        };
+        RefileTarget {
+            file: file.filter(|file| !file.is_empty()).map(str::to_string),
+            heading: (!heading.is_empty()).then(|| heading.to_string()),
+        }
+    }
+}
+
+/// Refile the first section matching `search` to `target`.
+///
+/// Target files are relative to the workspace, or to the input file's
+/// directory if there is no workspace. Both files are written together.
+pub fn refile(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    search: &str,
+    target: &RefileTarget,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let Some(target_file) = &target.file else {
+        let output = refile_in_input(config, &input, search, target.heading.as_deref())?;
+        write_output(input_file, output_file, &output)?;
+        return Ok(());
+    };
+
+    let Some(input_file) = input_file else {
+        bail!("Refiling to another file needs an input file");
+    };
+    let source = PathBuf::from(output_file.unwrap_or(input_file));
+    let target_path = target_path(config, Path::new(input_file), target_file);
+    if same_file(Path::new(input_file), &target_path) {
+        let output = refile_in_input(config, &input, search, target.heading.as_deref())?;
+        write_output(Some(input_file), output_file, &output)?;
+        return Ok(());
+    }
+
+    let target_input = if target_path.exists() {
+        fs::read_to_string(&target_path)?
+    } else {
+        String::new()
+    };
+    let (output, subtree) = cut_subtree(config, &input, search)?;
+    let target_output = add_subtree(
+        config,
+        &target_input,
+        &subtree,
+        heading_target(target.heading.as_deref()),
+    )?;
+
+    write_atomically(&[(source, output), (target_path, target_output)])?;
+
+    Ok(())
+}
+
+fn target_path(config: &Config, input_file: &Path, target_file: &str) -> PathBuf {
+    let directory = match &config.workspace {
+        Some(workspace) => expand_home(workspace),
+        None => input_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    directory.join(expand_home(target_file))
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn refile_in_input(
+    config: &Config,
+    input: &str,
+    search: &str,
+    heading: Option<&str>,
+) -> Result<String> {
+    if let Some(heading) = heading {
+        let org = Org::from_config(config.clone()).load(input);
+        let source = org
+            .find_section(search)
+            .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+        let target = org
+            .find_section(heading)
+            .ok_or_else(|| anyhow!("Could not find section with headline: {}", heading))?;
+        if (source.start_byte()..source.end_byte()).contains(&target.start_byte()) {
+            bail!("Cannot refile a subtree under itself");
+        }
+    }
+    let (output, subtree) = cut_subtree(config, input, search)?;
+    add_subtree(config, &output, &subtree, heading_target(heading))
+}
+
+/// The target for a subtree refiled under `heading`, or to the top level.
+fn heading_target(heading: Option<&str>) -> Target {
+    match heading {
+        Some(heading) => Target::Section(Position::Under, heading),
+        None => Target::End,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            RefileTarget::parse("Projects"),
+            RefileTarget {
+                file: None,
+                heading: Some("Projects".to_string()),
+            }
+        );
+        assert_eq!(
+            RefileTarget::parse("work.org::Projects"),
+            RefileTarget {
+                file: Some("work.org".to_string()),
+                heading: Some("Projects".to_string()),
+            }
+        );
+        assert_eq!(
+            RefileTarget::parse("work.org::"),
+            RefileTarget {
+                file: Some("work.org".to_string()),
+                heading: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_refile_in_input() {
+        let config = Config::default();
+        let input = "* Inbox\n** TODO Paint fence\n*** Buy paint\n* Projects\n** House\n";
+        let output = refile_in_input(&config, input, "Paint fence", Some("House")).unwrap();
+        assert_eq!(
+            output,
+            "* Inbox\n* Projects\n** House\n*** TODO Paint fence\n**** Buy paint\n"
+        );
+    }
+
+    #[test]
+    fn test_refile_to_top_level() {
+        let config = Config::default();
+        let input = "* Inbox\n** TODO Call Sam\n* Projects\n";
+        let output = refile_in_input(&config, input, "Call Sam", None).unwrap();
+        assert_eq!(output, "* Inbox\n* Projects\n* TODO Call Sam\n");
+    }
+
+    #[test]
+    fn test_refile_under_itself() {
+        let config = Config::default();
+        let input = "* Inbox\n** TODO Paint fence\n*** Buy paint\n";
+        assert!(refile_in_input(&config, input, "Paint fence", Some("Buy paint")).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Change the TODO state of a task.
+
+use crate::utils::fs::{read_input, write_output};
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDateTime};
+use orgtools::config::Config;
+use orgtools::org::Org;
+use orgtools::state::change_state;
+
+pub fn set_state(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    search: &str,
+    keyword: &str,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let output = set_state_in_input(config, &input, search, keyword, Local::now().naive_local())?;
+
+    write_output(input_file, output_file, &output)?;
+
+    Ok(())
+}
+
+fn set_state_in_input(
+    config: &Config,
+    input: &str,
+    search: &str,
+    keyword: &str,
+    now: NaiveDateTime,
+) -> Result<String> {
+    let org_file = Org::from_config(config.clone()).load(input);
+    let section = org_file
+        .find_section(search)
+        .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+
+    let mut entry = section.entry();
+    change_state(&mut entry, config, Some(keyword), now)?;
+
+    let mut output = org_file.output_builder();
+    output.append_up_to_section(&section);
+    output.insert_text(&entry.to_string());
+    output.skip_to(section.content_end_byte());
+    Ok(output.append_to_end_of_input())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_set_state_done() {
+        let input = "* Project\n** TODO Task\nNotes\n*** TODO Subtask\n* Other";
+        let output = set_state_in_input(&Config::default(), input, "Task", "DONE", now()).unwrap();
+        assert_eq!(
+            output,
+            "* Project\n** DONE Task\nCLOSED: [2026-10-17 Sat 10:00]\nNotes\n*** TODO Subtask\n* Other"
+        );
+    }
+
+    #[test]
+    fn test_set_state_missing_section() {
+        let result = set_state_in_input(&Config::default(), "* Task", "Other", "DONE", now());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Show the consistency graphs and streaks of habits.
+
+use crate::utils::fs::read_input;
+use anyhow::Result;
+use chrono::NaiveDate;
+use orgtools::config::Config;
+use orgtools::habits::{habits, render_habits};
+use orgtools::report::OutputFormat;
+
+/// Print the habits in the files, or in stdin if there are none, with
+/// their graphs covering `days` before and after today.
+pub fn show_habits(
+    config: &Config,
+    input_files: &[String],
+    today: NaiveDate,
+    days: (i64, i64),
+    format: OutputFormat,
+) -> Result<()> {
+    let stdin = [None];
+    let files = input_files
+        .iter()
+        .map(|file| Some(file.as_str()))
+        .collect::<Vec<_>>();
+    let files = if files.is_empty() {
+        &stdin[..]
+    } else {
+        &files[..]
+    };
+
+    let mut inputs = vec![];
+    for file in files {
+        inputs.push((file.unwrap_or("<stdin>").to_string(), read_input(*file)?));
+    }
+    print!(
+        "{}",
+        habits_from_inputs(config, &inputs, today, days, format)?
+    );
+    Ok(())
+}
+
+/// Render the habits in `(file name, contents)` pairs.
+fn habits_from_inputs(
+    config: &Config,
+    inputs: &[(String, String)],
+    today: NaiveDate,
+    days: (i64, i64),
+    format: OutputFormat,
+) -> Result<String> {
+    let habits = inputs
+        .iter()
+        .flat_map(|(file, input)| habits(config, input, file))
+        .collect::<Vec<_>>();
+    render_habits(&habits, today, days, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_habits_from_inputs() {
+        let inputs = [
+            (
+                "health.org".to_string(),
+                "* TODO Stretch\nSCHEDULED: <2026-10-19 Mon .+1d>\n:PROPERTIES:\n:STYLE: habit\n:LAST_REPEAT: [2026-10-18 Sun 07:00]\n:END:\n".to_string(),
+            ),
+            (
+                "home.org".to_string(),
+                "* TODO Vacuum\nSCHEDULED: <2026-10-19 Mon .+1w>\n".to_string(),
+            ),
+        ];
+
+        assert_eq!(
+            habits_from_inputs(
+                &Config::default(),
+                &inputs,
+                "2026-10-18".parse().unwrap(),
+                (21, 7),
+                OutputFormat::Csv
+            )
+            .unwrap(),
+            "file,heading,completions,current_streak,longest_streak,missed,last_done,next_due\nhealth.org,Stretch,1,1,1,0,2026-10-18,2026-10-19\n"
+        );
+    }
+}
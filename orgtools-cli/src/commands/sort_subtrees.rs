@@ -0,0 +1,66 @@
+//! Sort the children of a heading.
+
+use crate::utils::fs::{read_input, write_output};
+use anyhow::{anyhow, Result};
+use orgtools::config::Config;
+use orgtools::org::Org;
+use orgtools::sort::SortKey;
+
+/// Sort the children of the first section matching `parent`, or the top
+/// level sections if `parent` is empty.
+pub fn sort_subtrees(
+    config: &Config,
+    input_file: Option<&str>,
+    output_file: Option<&str>,
+    parent: &str,
+    key: &SortKey,
+    reverse: bool,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let output = sort_subtrees_in_input(config, &input, parent, key, reverse)?;
+
+    write_output(input_file, output_file, &output)?;
+
+    Ok(())
+}
+
+fn sort_subtrees_in_input(
+    config: &Config,
+    input: &str,
+    parent: &str,
+    key: &SortKey,
+    reverse: bool,
+) -> Result<String> {
+    let org_file = Org::from_config(config.clone()).load(input);
+    let parent = match parent {
+        "" => None,
+        search => Some(
+            org_file
+                .find_section(search)
+                .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?,
+        ),
+    };
+    Ok(orgtools::sort::sort_subtrees(
+        config,
+        &org_file,
+        parent.as_ref(),
+        key,
+        reverse,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_subtrees_in_input() {
+        let config = Config::default();
+        let input = "* Projects\n** [#B] Shed\n** [#A] House\n* Areas\n";
+        let output =
+            sort_subtrees_in_input(&config, input, "Projects", &SortKey::Priority, false).unwrap();
+        assert_eq!(output, "* Projects\n** [#A] House\n** [#B] Shed\n* Areas\n");
+        let output = sort_subtrees_in_input(&config, input, "", &SortKey::Alpha, false).unwrap();
+        assert_eq!(output, "* Areas\n* Projects\n** [#B] Shed\n** [#A] House\n");
+    }
+}
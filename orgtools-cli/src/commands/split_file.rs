@@ -0,0 +1,81 @@
+//! Split a file by top-level heading, and join files into one outline.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::utils::fs::{read_input, write_atomically};
+use anyhow::{bail, Result};
+use orgtools::config::Config;
+use orgtools::split::{join, split, SplitBy};
+
+/// Write a file per section of the input to `out_dir`, refusing to
+/// overwrite existing files.
+pub fn split_file(
+    config: &Config,
+    input_file: Option<&str>,
+    by: SplitBy,
+    out_dir: &str,
+) -> Result<()> {
+    let input = read_input(input_file)?;
+    let mut files = vec![];
+    for (name, contents) in split(config, &input, by)? {
+        let path = Path::new(out_dir).join(name);
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+        files.push((path, contents));
+    }
+    write_atomically(&files)?;
+    Ok(())
+}
+
+/// Join the files into one outline in `output_file`, or stdout.
+pub fn join_files(
+    config: &Config,
+    input_files: &[String],
+    output_file: Option<&str>,
+) -> Result<()> {
+    let mut files = vec![];
+    for file in input_files {
+        files.push((file.clone(), fs::read_to_string(file)?));
+    }
+    let output = join(config, &files)?;
+    match output_file {
+        Some(output_file) => fs::write(output_file, output)?,
+        None => io::stdout().write_all(output.as_bytes())?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_file_refuses_to_overwrite() {
+        // Given
+        let dir = std::env::temp_dir().join(format!("orgtools-split-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("notes.org");
+        fs::write(&input, "* Rust\n* Cooking\n").unwrap();
+        let existing = dir.join("cooking.org");
+        fs::write(&existing, "* Old\n").unwrap();
+
+        // When
+        let result = split_file(
+            &Config::default(),
+            input.to_str(),
+            SplitBy::Level1,
+            dir.to_str().unwrap(),
+        );
+
+        // Then
+        let written = dir.join("rust.org").exists();
+        let contents = fs::read_to_string(&existing).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+        assert!(!written);
+        assert_eq!(contents, "* Old\n");
+    }
+}
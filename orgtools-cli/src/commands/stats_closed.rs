@@ -0,0 +1,81 @@
+//! Count the tasks closed in each day, week, month or year.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::utils::fs::{expand_home, org_files};
+use anyhow::Result;
+use chrono::NaiveDate;
+use orgtools::config::Config;
+use orgtools::matcher::Matcher;
+use orgtools::report::OutputFormat;
+use orgtools::stats::{closed_dates, count_by_period, render_counts, Period};
+
+/// Which closed tasks to count and how.
+#[derive(Debug)]
+pub struct ClosedStats {
+    pub period: Period,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub matcher: Option<Matcher>,
+    pub format: OutputFormat,
+}
+
+/// Print the number of tasks closed in each period in the org files under
+/// `root`, or the workspace, or the current directory.
+pub fn stats_closed(config: &Config, root: Option<&str>, stats: &ClosedStats) -> Result<()> {
+    let mut inputs = vec![];
+    for path in org_files(&root_directory(config, root)?)? {
+        inputs.push(fs::read_to_string(path)?);
+    }
+    print!("{}", stats_closed_from_inputs(config, &inputs, stats));
+    Ok(())
+}
+
+/// The directory the stats commands look for org files in.
+pub(crate) fn root_directory(config: &Config, root: Option<&str>) -> Result<PathBuf> {
+    Ok(match root.or(config.workspace.as_deref()) {
+        Some(root) => expand_home(root),
+        None => env::current_dir()?,
+    })
+}
+
+fn stats_closed_from_inputs(config: &Config, inputs: &[String], stats: &ClosedStats) -> String {
+    let dates = inputs
+        .iter()
+        .flat_map(|input| closed_dates(config, input, stats.matcher.as_ref()))
+        .collect::<Vec<_>>();
+    let counts = count_by_period(&dates, stats.period, stats.from, stats.to);
+    render_counts(&counts, stats.format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_closed_from_inputs() {
+        let inputs = [
+            "* DONE One :work:\nCLOSED: [2026-10-12 Mon 10:00]\n".to_string(),
+            "* DONE Two\nCLOSED: [2026-10-14 Wed]\n* DONE Three :work:\nCLOSED: [2026-10-14 Wed]\n"
+                .to_string(),
+        ];
+        let stats = ClosedStats {
+            period: Period::Day,
+            from: None,
+            to: None,
+            matcher: Some("work".parse().unwrap()),
+            format: OutputFormat::Table,
+        };
+
+        assert_eq!(
+            stats_closed_from_inputs(&Config::default(), &inputs, &stats),
+            "2026-10-12    1  =\n2026-10-13    0  \n2026-10-14    1  =\n"
+        );
+        assert_eq!(
+            stats_closed_from_inputs(&Config::default(), &[], &stats),
+            ""
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Report lead time, time in state, throughput and stale work.
+
+use std::fs;
+
+use crate::commands::stats_closed::root_directory;
+use crate::utils::fs::org_files;
+use anyhow::Result;
+use orgtools::config::Config;
+use orgtools::flow::{flow_report, task_flows, FlowOptions};
+use orgtools::matcher::Matcher;
+use orgtools::report::OutputFormat;
+
+/// Print a flow report for the org files under `root`, or the workspace,
+/// or the current directory.
+pub fn stats_flow(
+    config: &Config,
+    root: Option<&str>,
+    matcher: Option<&Matcher>,
+    options: &FlowOptions,
+    format: OutputFormat,
+) -> Result<()> {
+    let root = root_directory(config, root)?;
+    let mut inputs = vec![];
+    for path in org_files(&root)? {
+        let name = path
+            .strip_prefix(&root)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        inputs.push((name, fs::read_to_string(&path)?));
+    }
+    print!(
+        "{}",
+        stats_flow_from_inputs(config, &inputs, matcher, options, format)
+    );
+    Ok(())
+}
+
+/// Render the report for `(file name, contents)` pairs.
+fn stats_flow_from_inputs(
+    config: &Config,
+    inputs: &[(String, String)],
+    matcher: Option<&Matcher>,
+    options: &FlowOptions,
+    format: OutputFormat,
+) -> String {
+    let flows = inputs
+        .iter()
+        .flat_map(|(file, input)| task_flows(config, input, file, matcher))
+        .collect::<Vec<_>>();
+    flow_report(&flows, options).render(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use orgtools::flow::FlowGroup;
+    use orgtools::stats::Period;
+
+    #[test]
+    fn test_stats_flow_from_inputs() {
+        let inputs = [
+            (
+                "work.org".to_string(),
+                "* DONE Report\nCLOSED: [2026-10-11 Sun 09:00]\n:PROPERTIES:\n:CREATED: [2026-10-01 Thu 09:00]\n:END:\n".to_string(),
+            ),
+            (
+                "home.org".to_string(),
+                "* DONE Taxes\nCLOSED: [2026-10-12 Mon 09:00]\n:PROPERTIES:\n:CREATED: [2026-10-10 Sat 09:00]\n:END:\n".to_string(),
+            ),
+        ];
+        let options = FlowOptions {
+            by: FlowGroup::File,
+            period: Period::Month,
+            from: None,
+            to: None,
+            stale_days: 30,
+            now: NaiveDate::from_ymd_opt(2026, 10, 18)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+        };
+
+        let output = stats_flow_from_inputs(
+            &Config::default(),
+            &inputs,
+            None,
+            &options,
+            OutputFormat::Csv,
+        );
+        assert!(output.starts_with(
+            "File,Tasks,p50,p85,p95,Max\nhome.org,1,2.0,2.0,2.0,2.0\nwork.org,1,10.0,10.0,10.0,10.0\n"
+        ));
+        assert!(output.contains("period,count\n2026-10-01,2\n"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Show the tasks closed each day as a calendar heatmap.
+
+use std::fs;
+
+use crate::commands::stats_closed::root_directory;
+use crate::utils::fs::org_files;
+use anyhow::Result;
+use chrono::NaiveDate;
+use orgtools::config::Config;
+use orgtools::heatmap::{Heatmap, HeatmapOptions};
+use orgtools::matcher::Matcher;
+use orgtools::stats::closed_dates;
+
+/// Print a heatmap of the tasks closed from the first to the last day of
+/// `range` in the org files under `root`, or the workspace, or the current
+/// directory.
+pub fn stats_heatmap(
+    config: &Config,
+    root: Option<&str>,
+    matcher: Option<&Matcher>,
+    range: (NaiveDate, NaiveDate),
+    options: &HeatmapOptions,
+    svg: bool,
+) -> Result<()> {
+    let mut inputs = vec![];
+    for path in org_files(&root_directory(config, root)?)? {
+        inputs.push(fs::read_to_string(path)?);
+    }
+    let heatmap = heatmap_from_inputs(config, &inputs, matcher, range, options)?;
+    if svg {
+        print!("{}", heatmap.to_svg());
+    } else {
+        print!("{}", heatmap.to_terminal());
+    }
+    Ok(())
+}
+
+fn heatmap_from_inputs(
+    config: &Config,
+    inputs: &[String],
+    matcher: Option<&Matcher>,
+    (first, last): (NaiveDate, NaiveDate),
+    options: &HeatmapOptions,
+) -> Result<Heatmap> {
+    let dates = inputs
+        .iter()
+        .flat_map(|input| closed_dates(config, input, matcher))
+        .collect::<Vec<_>>();
+    Heatmap::new(&dates, first, last, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_from_inputs() {
+        let inputs = [
+            "* DONE One :work:\nCLOSED: [2026-10-12 Mon 10:00]\n".to_string(),
+            "* DONE Two\nCLOSED: [2026-10-14 Wed]\n".to_string(),
+        ];
+        let range = ("2026-10-12".parse().unwrap(), "2026-10-18".parse().unwrap());
+        let matcher = "work".parse().unwrap();
+
+        let svg = heatmap_from_inputs(
+            &Config::default(),
+            &inputs,
+            Some(&matcher),
+            range,
+            &HeatmapOptions::default(),
+        )
+        .unwrap()
+        .to_svg();
+
+        assert!(svg.contains("<title>2026-10-12: 1 task</title>"));
+        assert!(svg.contains("<title>2026-10-14: 0 tasks</title>"));
+    }
+}
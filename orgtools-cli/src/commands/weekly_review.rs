@@ -0,0 +1,81 @@
+//! List next actions, stuck projects and waiting items for a weekly review.
+
+use std::fs;
+
+use crate::commands::stats_closed::root_directory;
+use crate::utils::fs::org_files;
+use anyhow::Result;
+use chrono::NaiveDate;
+use orgtools::config::Config;
+use orgtools::report::OutputFormat;
+use orgtools::review::{review, Review};
+
+/// Print the review of the org files under `root`, or the workspace, or the
+/// current directory.
+pub fn weekly_review(
+    config: &Config,
+    root: Option<&str>,
+    today: NaiveDate,
+    format: OutputFormat,
+) -> Result<()> {
+    let root = root_directory(config, root)?;
+    let mut inputs = vec![];
+    for path in org_files(&root)? {
+        let name = path
+            .strip_prefix(&root)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        inputs.push((name, fs::read_to_string(&path)?));
+    }
+    print!("{}", review_from_inputs(config, &inputs, today, format)?);
+    Ok(())
+}
+
+/// Render the review of `(file name, contents)` pairs.
+fn review_from_inputs(
+    config: &Config,
+    inputs: &[(String, String)],
+    today: NaiveDate,
+    format: OutputFormat,
+) -> Result<String> {
+    let mut combined = Review::default();
+    for (file, input) in inputs {
+        combined.extend(review(config, input, file, today));
+    }
+    combined.render(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_from_inputs() {
+        let inputs = [
+            (
+                "work.org".to_string(),
+                "* Report\n** DONE Draft\n** TODO Send\n".to_string(),
+            ),
+            (
+                "home.org".to_string(),
+                "* Move house\n** DONE Pack\n** BLOCKED Van hire\n:PROPERTIES:\n:DELEGATED_TO: Sam\n:END:\n".to_string(),
+            ),
+        ];
+
+        assert_eq!(
+            review_from_inputs(
+                &Config::default(),
+                &inputs,
+                "2026-10-18".parse().unwrap(),
+                OutputFormat::Csv
+            )
+            .unwrap(),
+            "report,file,heading,keyword,delegated_to,since
+next action,work.org,Report / Send,TODO,,
+stuck project,home.org,Move house,,,
+waiting,home.org,Move house / Van hire,BLOCKED,Sam,
+"
+        );
+    }
+}
@@ -4,8 +4,15 @@ mod commands;
 mod utils;
 
 use crate::cli::Commands;
+use crate::commands::{ClosedStats, NewHeadline, RefileTarget, SparseOutput};
 use crate::utils::set_up_logging;
+use chrono::Local;
+use orgtools::clocktable::ClockTableOptions;
+use orgtools::flow::FlowOptions;
+use orgtools::heatmap::year_ending;
 use orgtools::org::Position;
+use orgtools::subtree::Target;
+use std::process;
 use tracing::debug;
 
 use crate::cli::cli;
@@ -15,22 +22,57 @@ fn main() {
 
     let cli = cli();
     debug!("{:?}", cli);
-    let config = cli.config();
+    let config = cli.config().expect("Error loading config");
     match &cli.command {
         Commands::Prune {
             output_file,
             input_file,
+            older_than,
+            open_descendants,
+            keep_tags,
+            dry_run,
         } => {
-            commands::prune_done(&config, input_file.as_deref(), output_file.as_deref())
-                .expect("prune_done failed");
+            let mut config = config.clone();
+            if older_than.is_some() {
+                config.prune.older_than_days = *older_than;
+            }
+            if let Some(open_descendants) = open_descendants {
+                config.prune.open_descendants = *open_descendants;
+            }
+            if let Some(keep_tags) = keep_tags {
+                config.prune.keep_tags = keep_tags.clone();
+            }
+            commands::prune_done(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                *dry_run,
+            )
+            .expect("prune_done failed");
+        }
+        Commands::Archive { input_file } => {
+            commands::archive(&config, input_file).expect("archive failed");
         }
         Commands::Tree {
             input_file,
             sexp,
             sections,
+            matcher,
+            org,
         } => {
-            commands::print_tree(&config, input_file.as_deref(), *sexp, *sections)
-                .expect("print_tree failed");
+            let output = if *org {
+                SparseOutput::Org
+            } else {
+                SparseOutput::Text
+            };
+            commands::print_tree(
+                &config,
+                input_file.as_deref(),
+                *sexp,
+                *sections,
+                matcher.as_ref().map(|matcher| (matcher, output)),
+            )
+            .expect("print_tree failed");
         }
         Commands::List { input_file } => {
             commands::list_headlines(&config, input_file.as_deref())
@@ -40,25 +82,355 @@ fn main() {
             input_file,
             output_file,
             headline,
+            keyword,
+            priority,
+            tags,
+            scheduled,
+            deadline,
+            properties,
+            body,
             under,
             after,
+            datetree,
         } => {
-            let (position, search) = if let Some(under) = under {
-                (Position::Under, under)
+            let target = if let Some(under) = under {
+                Target::Section(Position::Under, under)
             } else if let Some(after) = after {
-                (Position::After, after)
+                Target::Section(Position::After, after)
+            } else if let Some(kind) = datetree {
+                Target::Datetree {
+                    kind: *kind,
+                    date: Local::now().date_naive(),
+                    path: &[],
+                }
             } else {
-                panic!("Either under or after must be provided")
+                Target::End
+            };
+            let headline = NewHeadline {
+                title: headline.clone(),
+                keyword: keyword.clone(),
+                priority: *priority,
+                tags: tags.clone(),
+                scheduled: scheduled.clone(),
+                deadline: deadline.clone(),
+                properties: properties.clone(),
+                body: body.clone(),
             };
             commands::add_headline(
                 &config,
                 input_file.as_deref(),
                 output_file.as_deref(),
                 headline,
-                position,
-                search,
+                target,
             )
             .expect("add_headline failed");
         }
+        Commands::State {
+            input_file,
+            output_file,
+            search,
+            keyword,
+        } => {
+            let keyword = keyword
+                .as_deref()
+                .or(config.keywords_finished.first().map(String::as_str))
+                .expect("No finished keywords configured");
+            commands::set_state(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                search,
+                keyword,
+            )
+            .expect("set_state failed");
+        }
+        Commands::EditSubtree {
+            input_file,
+            output_file,
+            search,
+            edit,
+        } => {
+            commands::edit_subtree(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                search,
+                *edit,
+            )
+            .expect("edit_subtree failed");
+        }
+        Commands::Fmt {
+            input_file,
+            output_file,
+            check,
+        } => {
+            let formatted = commands::format_file(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                *check,
+            )
+            .expect("fmt failed");
+            if !formatted {
+                process::exit(1);
+            }
+        }
+        Commands::Lint { input_files } => {
+            if !commands::lint_files(&config, input_files).expect("lint failed") {
+                process::exit(1);
+            }
+        }
+        Commands::ClockIn { input_file, search } => {
+            commands::clock_in(&config, input_file, search).expect("clock in failed");
+        }
+        Commands::ClockOut { input_file } => {
+            commands::clock_out(&config, input_file.as_deref()).expect("clock out failed");
+        }
+        Commands::ClockStatus { input_file } => {
+            commands::clock_status(&config, input_file.as_deref()).expect("clock status failed");
+        }
+        Commands::Clocktable {
+            input_files,
+            from,
+            to,
+            by,
+            depth,
+            format,
+            update,
+        } => {
+            let today = Local::now().date_naive();
+            let options = ClockTableOptions {
+                from: *from,
+                to: *to,
+                by: *by,
+                depth: *depth,
+            };
+            commands::clocktable(&config, input_files, &options, *format, *update, today)
+                .expect("clocktable failed");
+        }
+        Commands::Effort {
+            input_files,
+            depth,
+            format,
+        } => {
+            commands::effort_report(&config, input_files, *depth, *format).expect("effort failed");
+        }
+        Commands::Habits {
+            input_files,
+            days_before,
+            days_after,
+            format,
+        } => {
+            commands::show_habits(
+                &config,
+                input_files,
+                Local::now().date_naive(),
+                (*days_before, *days_after),
+                *format,
+            )
+            .expect("habits failed");
+        }
+        Commands::StatsClosed {
+            by,
+            root,
+            from,
+            to,
+            matcher,
+            format,
+        } => {
+            let stats = ClosedStats {
+                period: *by,
+                from: *from,
+                to: *to,
+                matcher: matcher.clone(),
+                format: *format,
+            };
+            commands::stats_closed(&config, root.as_deref(), &stats).expect("stats failed");
+        }
+        Commands::StatsFlow {
+            by,
+            period,
+            stale_days,
+            root,
+            from,
+            to,
+            matcher,
+            format,
+        } => {
+            let options = FlowOptions {
+                by: *by,
+                period: *period,
+                from: *from,
+                to: *to,
+                stale_days: *stale_days,
+                now: Local::now().naive_local(),
+            };
+            commands::stats_flow(
+                &config,
+                root.as_deref(),
+                matcher.as_ref(),
+                &options,
+                *format,
+            )
+            .expect("stats failed");
+        }
+        Commands::StatsHeatmap {
+            week_start,
+            colors,
+            svg,
+            root,
+            from,
+            to,
+            matcher,
+        } => {
+            let mut options = config.heatmap.clone();
+            if let Some(week_start) = week_start {
+                options.week_start = *week_start;
+            }
+            if let Some(colors) = colors {
+                options.colors = colors.clone();
+            }
+            let last = to.unwrap_or_else(|| Local::now().date_naive());
+            let first = from.unwrap_or(year_ending(last).0);
+            commands::stats_heatmap(
+                &config,
+                root.as_deref(),
+                matcher.as_ref(),
+                (first, last),
+                &options,
+                *svg,
+            )
+            .expect("stats failed");
+        }
+        Commands::Review { root, format } => {
+            commands::weekly_review(&config, root.as_deref(), Local::now().date_naive(), *format)
+                .expect("review failed");
+        }
+        Commands::Extract {
+            input_file,
+            output_file,
+            search,
+        } => {
+            commands::extract_subtree(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                search,
+            )
+            .expect("extract failed");
+        }
+        Commands::Splice {
+            extracted_file,
+            into,
+            output_file,
+        } => {
+            commands::splice_subtree(
+                &config,
+                extracted_file,
+                into.as_deref(),
+                output_file.as_deref(),
+            )
+            .expect("splice failed");
+        }
+        Commands::Split {
+            input_file,
+            by,
+            out_dir,
+        } => {
+            commands::split_file(&config, input_file.as_deref(), *by, out_dir)
+                .expect("split failed");
+        }
+        Commands::Join {
+            input_files,
+            output_file,
+        } => {
+            commands::join_files(&config, input_files, output_file.as_deref())
+                .expect("join failed");
+        }
+        Commands::ExportMarkdown {
+            input_file,
+            output_file,
+            subtree,
+        } => {
+            commands::export_markdown(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                subtree.as_deref(),
+            )
+            .expect("export failed");
+        }
+        Commands::ExportHtml {
+            input_file,
+            output_file,
+            subtree,
+            template,
+            toc_depth,
+        } => {
+            let mut options = config.html.clone();
+            if let Some(template) = template {
+                options.template = Some(template.clone());
+            }
+            if let Some(toc_depth) = toc_depth {
+                options.toc_depth = *toc_depth;
+            }
+            commands::export_html(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                subtree.as_deref(),
+                &options,
+            )
+            .expect("export failed");
+        }
+        Commands::Sort {
+            input_file,
+            output_file,
+            parent,
+            by,
+            reverse,
+        } => {
+            commands::sort_subtrees(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                parent,
+                by,
+                *reverse,
+            )
+            .expect("sort failed");
+        }
+        Commands::Refile {
+            input_file,
+            output_file,
+            search,
+            target,
+        } => {
+            commands::refile(
+                &config,
+                input_file.as_deref(),
+                output_file.as_deref(),
+                search,
+                &RefileTarget::parse(target),
+            )
+            .expect("refile failed");
+        }
+        Commands::Capture {
+            template,
+            file,
+            output_file,
+            answers,
+            annotation,
+        } => {
+            commands::capture(
+                &config,
+                template,
+                file.as_deref(),
+                output_file.as_deref(),
+                answers,
+                annotation.as_deref(),
+            )
+            .expect("capture failed");
+        }
     }
 }
@@ -12,10 +12,19 @@ pub fn set_up_logging() {
 
 pub mod fs {
     use std::{
-        fs,
+        env, fs,
         io::{self, Read, Write},
+        path::{Path, PathBuf},
     };
 
+    /// Expand a leading `~` to the home directory.
+    pub fn expand_home(path: &str) -> PathBuf {
+        match (path.strip_prefix("~/"), env::var_os("HOME")) {
+            (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+            _ => PathBuf::from(path),
+        }
+    }
+
     pub fn read_input(input_file: Option<&str>) -> io::Result<String> {
         if let Some(input_file) = input_file {
             fs::read_to_string(input_file)
@@ -39,4 +48,61 @@ pub mod fs {
             io::stdout().write_all(content.as_bytes())
         }
     }
+
+    /// Write several files, each by renaming a temporary file into place.
+    ///
+    /// Every temporary file is written before any is renamed, so a failure
+    /// while writing leaves all the files as they were.
+    pub fn write_atomically(files: &[(PathBuf, String)]) -> io::Result<()> {
+        let mut temporary_paths = vec![];
+        for (path, content) in files {
+            let temporary_path = temporary_path(path);
+            let written = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+                _ => Ok(()),
+            }
+            .and_then(|_| fs::write(&temporary_path, content));
+            if let Err(err) = written {
+                for temporary_path in temporary_paths {
+                    let _ = fs::remove_file(temporary_path);
+                }
+                return Err(err);
+            }
+            temporary_paths.push(temporary_path);
+        }
+        for ((path, _), temporary_path) in files.iter().zip(temporary_paths) {
+            fs::rename(temporary_path, path)?;
+        }
+        Ok(())
+    }
+
+    /// Find the `.org` files under a directory, skipping hidden files and
+    /// directories.
+    pub fn org_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                files.extend(org_files(&path)?);
+            } else if path.extension().is_some_and(|extension| extension == "org") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn temporary_path(path: &Path) -> PathBuf {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        path.with_file_name(format!(".{}.tmp", name))
+    }
 }
@@ -0,0 +1,165 @@
+//! Archiving entries: where they go and what is recorded about where they came from.
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::org::Org;
+use crate::subtree::{add_subtree, Target};
+use crate::timestamp::Timestamp;
+
+/// The archive location used when neither the file nor the entry sets one.
+pub const DEFAULT_ARCHIVE_LOCATION: &str = "%s_archive::";
+
+/// Where archived entries are moved to, parsed from `file::heading`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArchiveLocation {
+    /// The archive file, or `None` for the file the entry is in.
+    pub file: Option<String>,
+    /// The top level heading entries are moved under, or `None` for the top level.
+    pub heading: Option<String>,
+}
+
+impl ArchiveLocation {
+    /// Parse a location, replacing `%s` with the name of the source file.
+    ///
+    /// The heading may be written with its stars, as in `::* Archived`.
+    pub fn parse(location: &str, source_file_name: &str) -> ArchiveLocation {
+        let (file, heading) = location.split_once("::").unwrap_or((location, ""));
+        let file = file.trim().replace("%s", source_file_name);
+        let heading = heading.trim().trim_start_matches('*').trim();
+        ArchiveLocation {
+            file: (!file.is_empty()).then_some(file),
+            heading: (!heading.is_empty()).then(|| heading.to_string()),
+        }
+    }
+}
+
+/// The value of the first `#+KEY:` line in the preamble of a file, ignoring case.
+pub fn file_keyword<'a>(input: &'a str, key: &str) -> Option<&'a str> {
+    input
+        .lines()
+        .take_while(|line| !line.starts_with('*'))
+        .find_map(|line| {
+            let rest = line.trim_start().strip_prefix("#+")?;
+            let (name, value) = rest.split_once(':')?;
+            name.eq_ignore_ascii_case(key).then(|| value.trim())
+        })
+}
+
+/// Where an entry came from, recorded in its properties when it is archived.
+#[derive(Debug, Clone)]
+pub struct ArchiveInfo {
+    pub time: NaiveDateTime,
+    pub file: String,
+    /// The headlines of the entry's ancestors, from the top level down.
+    pub outline_path: Vec<String>,
+    pub category: String,
+}
+
+/// Record the `ARCHIVE_*` properties on an entry.
+pub fn add_archive_properties(entry: &mut Entry, info: &ArchiveInfo) {
+    let todo = entry.keyword().unwrap_or_default().to_string();
+    entry.set_property(
+        "ARCHIVE_TIME",
+        &Timestamp::inactive_now(info.time).to_string(),
+    );
+    entry.set_property("ARCHIVE_FILE", &info.file);
+    entry.set_property("ARCHIVE_OLPATH", &info.outline_path.join("/"));
+    entry.set_property("ARCHIVE_CATEGORY", &info.category);
+    entry.set_property("ARCHIVE_TODO", &todo);
+}
+
+/// Add an archived subtree at level 1 to the end of an archive file or
+/// under its heading.
+///
+/// The heading is added at the end of the file if it is missing.
+pub fn add_to_archive(
+    config: &Config,
+    input: &str,
+    heading: Option<&str>,
+    subtree: &str,
+) -> Result<String> {
+    let Some(heading) = heading else {
+        return add_subtree(config, input, subtree, Target::End);
+    };
+    let path = [heading.to_string()];
+    let mut input = input.to_string();
+    if Org::from_config(config.clone())
+        .load(&input)
+        .find_outline_path(&path)
+        .is_none()
+    {
+        input = add_subtree(config, &input, &format!("* {}\n", heading), Target::End)?;
+    }
+    add_subtree(config, &input, subtree, Target::OutlinePath(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_parse_default_location() {
+        let location = ArchiveLocation::parse(DEFAULT_ARCHIVE_LOCATION, "notes.org");
+        assert_eq!(location.file.as_deref(), Some("notes.org_archive"));
+        assert_eq!(location.heading, None);
+    }
+
+    #[test]
+    fn test_parse_heading_location() {
+        let location = ArchiveLocation::parse("::* Archived Tasks", "notes.org");
+        assert_eq!(location.file, None);
+        assert_eq!(location.heading.as_deref(), Some("Archived Tasks"));
+
+        let location = ArchiveLocation::parse("archive/%s::Old", "notes.org");
+        assert_eq!(location.file.as_deref(), Some("archive/notes.org"));
+        assert_eq!(location.heading.as_deref(), Some("Old"));
+    }
+
+    #[test]
+    fn test_file_keyword() {
+        let input = "#+TITLE: Notes\n#+archive: old.org::\n* Heading\n#+ARCHIVE: ignored\n";
+        assert_eq!(file_keyword(input, "ARCHIVE"), Some("old.org::"));
+        assert_eq!(file_keyword(input, "CATEGORY"), None);
+    }
+
+    #[test]
+    fn test_add_archive_properties() {
+        // Given
+        let config = Config::default();
+        let mut entry = Entry::parse("** DONE Task\nBody\n", &config).unwrap();
+        let info = ArchiveInfo {
+            time: NaiveDate::from_ymd_opt(2026, 10, 17)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap(),
+            file: "/home/me/notes.org".to_string(),
+            outline_path: vec!["Projects".to_string(), "House".to_string()],
+            category: "notes".to_string(),
+        };
+
+        // When
+        add_archive_properties(&mut entry, &info);
+
+        // Then
+        assert_eq!(
+            entry.to_string(),
+            "** DONE Task\n:PROPERTIES:\n:ARCHIVE_TIME: [2026-10-17 Sat 10:00]\n:ARCHIVE_FILE: /home/me/notes.org\n:ARCHIVE_OLPATH: Projects/House\n:ARCHIVE_CATEGORY: notes\n:ARCHIVE_TODO: DONE\n:END:\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_add_to_archive_heading() {
+        let config = Config::default();
+        let output =
+            add_to_archive(&config, "* Notes\n", Some("Archive"), "* DONE Task\n").unwrap();
+        assert_eq!(output, "* Notes\n* Archive\n** DONE Task\n");
+        let output = add_to_archive(&config, &output, Some("Archive"), "* DONE Other\n").unwrap();
+        assert_eq!(output, "* Notes\n* Archive\n** DONE Task\n** DONE Other\n");
+        let output = add_to_archive(&config, "", None, "* DONE Task\n").unwrap();
+        assert_eq!(output, "* DONE Task\n");
+    }
+}
@@ -0,0 +1,210 @@
+//! Capture templates for adding entries from a template.
+//!
+//! Templates are expanded with the following placeholders:
+//!
+//! - `%t` / `%T`: active timestamp with the date / date and time
+//! - `%u` / `%U`: inactive timestamp with the date / date and time
+//! - `%^{Prompt}`: ask for a value, `%^{Prompt|default}` provides a default
+//! - `%i`: the initial content, usually read from stdin
+//! - `%a`: the annotation, usually a link to where the capture started
+//! - `%(command)`: the output of a shell command
+//! - `%%`: a literal `%`
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::datetree::DatetreeKind;
+use crate::timestamp::Timestamp;
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct CaptureTemplate {
+    /// The template text, starting with a level 1 headline.
+    pub template: String,
+    /// The file the entry is added to.
+    pub file: Option<String>,
+    /// The outline path of the heading the entry is added under.
+    ///
+    /// The entry is added at the end of the file when this is empty.
+    #[serde(default)]
+    pub olp: Vec<String>,
+    /// File the entry in a date tree for the capture date, under `olp` if set.
+    pub datetree: Option<DatetreeKind>,
+}
+
+/// The environment a template is expanded in.
+pub trait CaptureEnv {
+    fn now(&self) -> NaiveDateTime;
+
+    /// The initial content for `%i`.
+    fn initial_content(&mut self) -> Result<String>;
+
+    /// The annotation for `%a`.
+    fn annotation(&self) -> Option<String>;
+
+    /// Ask for a value for `%^{prompt}`.
+    fn prompt(&mut self, prompt: &str, default: Option<&str>) -> Result<String>;
+
+    /// Run a shell command for `%(command)`.
+    fn shell(&mut self, command: &str) -> Result<String>;
+}
+
+/// Expand the placeholders in a template.
+///
+/// Prompts with the same text are only asked once.
+pub fn expand_template(template: &str, env: &mut dyn CaptureEnv) -> Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut answers: HashMap<String, String> = HashMap::new();
+    let mut rest = template;
+
+    while let Some(index) = rest.find('%') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let now = env.now();
+        let mut chars = rest.chars();
+        match chars.next() {
+            Some('t') => push_timestamp(&mut output, true, now, false),
+            Some('T') => push_timestamp(&mut output, true, now, true),
+            Some('u') => push_timestamp(&mut output, false, now, false),
+            Some('U') => push_timestamp(&mut output, false, now, true),
+            Some('i') => output.push_str(env.initial_content()?.trim_end()),
+            Some('a') => output.push_str(&env.annotation().unwrap_or_default()),
+            Some('%') => output.push('%'),
+            Some('^') if chars.as_str().starts_with('{') => {
+                let (spec, remaining) = take_delimited(&chars.as_str()[1..], '}')?;
+                let (prompt, default) = match spec.split_once('|') {
+                    Some((prompt, default)) => (prompt, Some(default)),
+                    None => (spec, None),
+                };
+                let answer = match answers.get(prompt) {
+                    Some(answer) => answer.clone(),
+                    None => {
+                        let answer = env.prompt(prompt, default)?;
+                        answers.insert(prompt.to_string(), answer.clone());
+                        answer
+                    }
+                };
+                output.push_str(&answer);
+                rest = remaining;
+                continue;
+            }
+            Some('(') => {
+                let (command, remaining) = take_delimited(chars.as_str(), ')')?;
+                output.push_str(env.shell(command)?.trim_end());
+                rest = remaining;
+                continue;
+            }
+            Some(c) => bail!("Unknown placeholder in capture template: %{}", c),
+            None => bail!("Capture template ends with %"),
+        }
+        rest = chars.as_str();
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+fn push_timestamp(output: &mut String, active: bool, now: NaiveDateTime, with_time: bool) {
+    let mut timestamp = Timestamp::inactive_now(now);
+    timestamp.active = active;
+    if !with_time {
+        timestamp.time = None;
+    }
+    output.push_str(&timestamp.to_string());
+}
+
+/// Split off text up to a closing delimiter, allowing nested pairs.
+fn take_delimited(input: &str, close: char) -> Result<(&str, &str)> {
+    let open = match close {
+        ')' => '(',
+        _ => '{',
+    };
+    let mut depth = 0;
+    for (index, c) in input.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Ok((&input[..index], &input[index + 1..]));
+            }
+            depth -= 1;
+        }
+    }
+    Err(anyhow!("Missing {:?} in capture template", close))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    struct TestEnv {
+        prompts: Vec<String>,
+    }
+
+    impl CaptureEnv for TestEnv {
+        fn now(&self) -> NaiveDateTime {
+            NaiveDate::from_ymd_opt(2026, 10, 17)
+                .unwrap()
+                .and_hms_opt(9, 30, 15)
+                .unwrap()
+        }
+
+        fn initial_content(&mut self) -> Result<String> {
+            Ok("Pasted text\n".to_string())
+        }
+
+        fn annotation(&self) -> Option<String> {
+            Some("[[file:notes.org]]".to_string())
+        }
+
+        fn prompt(&mut self, prompt: &str, default: Option<&str>) -> Result<String> {
+            self.prompts.push(prompt.to_string());
+            Ok(default.unwrap_or("answer").to_string())
+        }
+
+        fn shell(&mut self, command: &str) -> Result<String> {
+            Ok(format!("ran {}\n", command))
+        }
+    }
+
+    fn expand(template: &str) -> (String, Vec<String>) {
+        let mut env = TestEnv { prompts: vec![] };
+        let output = expand_template(template, &mut env).unwrap();
+        (output, env.prompts)
+    }
+
+    #[test]
+    fn test_expand_timestamps() {
+        let (output, _) = expand("%t %T %u %U");
+        assert_eq!(
+            output,
+            "<2026-10-17 Sat> <2026-10-17 Sat 09:30> [2026-10-17 Sat] [2026-10-17 Sat 09:30]"
+        );
+    }
+
+    #[test]
+    fn test_expand_prompts() {
+        let (output, prompts) = expand("* %^{Who} and %^{Who} in %^{Room|Lobby}");
+        assert_eq!(output, "* answer and answer in Lobby");
+        assert_eq!(prompts, vec!["Who".to_string(), "Room".to_string()]);
+    }
+
+    #[test]
+    fn test_expand_input_annotation_and_shell() {
+        let (output, _) = expand("* Note\n%i\n%a\n%(echo (hi))\n100%%");
+        assert_eq!(
+            output,
+            "* Note\nPasted text\n[[file:notes.org]]\nran echo (hi)\n100%"
+        );
+    }
+
+    #[test]
+    fn test_expand_unknown_placeholder() {
+        let mut env = TestEnv { prompts: vec![] };
+        assert!(expand_template("%z", &mut env).is_err());
+        assert!(expand_template("%^{Who", &mut env).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! `CLOCK:` lines recording time spent on an entry.
+
+use std::fmt;
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::org::Org;
+use crate::timestamp::Timestamp;
+
+/// A clock line such as `CLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 10:30] =>  1:30`.
+///
+/// A clock without an end is still running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    pub start: NaiveDateTime,
+    pub end: Option<NaiveDateTime>,
+}
+
+impl Clock {
+    /// Parse a clock line, ignoring any `=>` duration since it is derived
+    /// from the timestamps.
+    pub fn parse(line: &str) -> Option<Clock> {
+        let rest = line.trim().strip_prefix("CLOCK:")?.trim_start();
+        let (start, consumed) = Timestamp::parse_prefix(rest).ok()?;
+        start.time?;
+        let rest = rest[consumed..].trim_start();
+        let end = match rest.strip_prefix("--") {
+            Some(rest) => {
+                let (end, _) = Timestamp::parse_prefix(rest).ok()?;
+                end.time?;
+                Some(end.datetime())
+            }
+            None => None,
+        };
+        Some(Clock {
+            start: start.datetime(),
+            end,
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.end.is_none()
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.end.map(|end| end - self.start)
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CLOCK: {}", Timestamp::inactive_now(self.start))?;
+        if let (Some(end), Some(duration)) = (self.end, self.duration()) {
+            write!(
+                f,
+                "--{} => {:>5}",
+                Timestamp::inactive_now(end),
+                format_minutes(duration.num_minutes())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A clock and the heading it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingClock {
+    /// The headlines from the top level down to the clock's heading.
+    pub outline_path: Vec<String>,
+    /// The heading's tags, including inherited ones.
+    pub tags: Vec<String>,
+    pub clock: Clock,
+}
+
+/// Collect the clocks of every heading in a file, whether they are in a
+/// `:LOGBOOK:` drawer or not.
+pub fn heading_clocks(config: &Config, input: &str) -> Vec<HeadingClock> {
+    let org = Org::from_config(config.clone()).load(input);
+    let mut clocks = vec![];
+    for item in org.outline() {
+        let section = &item.section;
+        for line in input[section.start_byte()..section.content_end_byte()].lines() {
+            if let Some(clock) = Clock::parse(line) {
+                clocks.push(HeadingClock {
+                    outline_path: item.outline_path.clone(),
+                    tags: item.tags.clone(),
+                    clock,
+                });
+            }
+        }
+    }
+    clocks
+}
+
+/// Start a clock on an entry.
+pub fn clock_in(entry: &mut Entry, now: NaiveDateTime) {
+    let clock = Clock {
+        start: now,
+        end: None,
+    };
+    entry.add_logbook_item(&clock.to_string());
+}
+
+/// Stop every running clock in a file, returning the new text, or `None`
+/// if no clock was running.
+pub fn close_running_clocks(input: &str, now: NaiveDateTime) -> Option<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut closed = false;
+    for line in input.split_inclusive('\n') {
+        match Clock::parse(line) {
+            Some(clock) if clock.is_running() => {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                let clock = Clock {
+                    end: Some(now.max(clock.start)),
+                    ..clock
+                };
+                output.push_str(&format!("{}{}", indent, clock));
+                if line.ends_with('\n') {
+                    output.push('\n');
+                }
+                closed = true;
+            }
+            _ => output.push_str(line),
+        }
+    }
+    closed.then_some(output)
+}
+
+/// Format minutes as `H:MM`, the way Org shows clocked time.
+pub fn format_minutes(minutes: i64) -> String {
+    let sign = if minutes < 0 { "-" } else { "" };
+    let minutes = minutes.abs();
+    format!("{}{}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        let clock =
+            Clock::parse("  CLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 10:30] =>  1:30")
+                .unwrap();
+        assert_eq!(clock.start, at(9, 0));
+        assert_eq!(clock.end, Some(at(10, 30)));
+        assert_eq!(clock.duration(), Some(Duration::minutes(90)));
+
+        let running = Clock::parse("CLOCK: [2026-10-17 Sat 09:00]").unwrap();
+        assert!(running.is_running());
+        assert!(Clock::parse("CLOCK: [2026-10-17 Sat]").is_none());
+        assert!(Clock::parse("- Note").is_none());
+    }
+
+    #[test]
+    fn test_clock_in_and_out() {
+        // Given
+        let config = Config::default();
+        let input = "* Work :work:\n** Report\n:LOGBOOK:\n- Note\n:END:\n";
+        let org_file = Org::from_config(config.clone()).load(input);
+        let section = org_file.find_section("Report").unwrap();
+        let mut entry = section.entry();
+
+        // When
+        clock_in(&mut entry, at(9, 0));
+        let clocked_in = format!("* Work :work:\n{}", entry);
+        let clocked_out = close_running_clocks(&clocked_in, at(10, 30)).unwrap();
+
+        // Then
+        assert_eq!(
+            clocked_in,
+            "* Work :work:\n** Report\n:LOGBOOK:\nCLOCK: [2026-10-17 Sat 09:00]\n- Note\n:END:\n"
+        );
+        assert_eq!(
+            heading_clocks(&config, &clocked_in),
+            vec![HeadingClock {
+                outline_path: vec!["Work".to_string(), "Report".to_string()],
+                tags: vec!["work".to_string()],
+                clock: Clock {
+                    start: at(9, 0),
+                    end: None
+                },
+            }]
+        );
+        assert_eq!(
+            clocked_out,
+            "* Work :work:\n** Report\n:LOGBOOK:\nCLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 10:30] =>  1:30\n- Note\n:END:\n"
+        );
+        assert_eq!(close_running_clocks(&clocked_out, at(11, 0)), None);
+    }
+
+    #[test]
+    fn test_render_clock() {
+        let clock = Clock {
+            start: at(9, 0),
+            end: Some(at(10, 30)),
+        };
+        assert_eq!(
+            clock.to_string(),
+            "CLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 10:30] =>  1:30"
+        );
+        assert_eq!(format_minutes(605), "10:05");
+    }
+}
@@ -0,0 +1,450 @@
+//! Clock table reports of the time clocked in `CLOCK:` lines.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+
+use crate::clock::{format_minutes, heading_clocks};
+use crate::config::Config;
+use crate::report::{OutputFormat, Table};
+use crate::timestamp::Timestamp;
+
+/// A finished clock and the heading it was clocked against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockRecord {
+    pub file: String,
+    /// The headlines from the top level down to the clocked heading.
+    pub outline_path: Vec<String>,
+    /// The heading's tags, including inherited ones.
+    pub tags: Vec<String>,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+/// Collect the finished clocks of every heading in a file.
+pub fn clock_records(config: &Config, input: &str, file: &str) -> Vec<ClockRecord> {
+    heading_clocks(config, input)
+        .into_iter()
+        .filter_map(|heading_clock| {
+            Some(ClockRecord {
+                file: file.to_string(),
+                start: heading_clock.clock.start,
+                end: heading_clock.clock.end?,
+                outline_path: heading_clock.outline_path,
+                tags: heading_clock.tags,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Heading,
+    Tag,
+    Day,
+    File,
+}
+
+impl FromStr for GroupBy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "heading" => Ok(GroupBy::Heading),
+            "tag" => Ok(GroupBy::Tag),
+            "day" => Ok(GroupBy::Day),
+            "file" => Ok(GroupBy::File),
+            _ => bail!(
+                "Invalid grouping, expected heading, tag, day or file: {}",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClockTableOptions {
+    /// Only count time from this point on.
+    pub from: Option<NaiveDateTime>,
+    /// Only count time before this point.
+    pub to: Option<NaiveDateTime>,
+    pub by: GroupBy,
+    /// Count the time of deeper headings against their ancestor at this level.
+    pub depth: Option<usize>,
+}
+
+impl Default for ClockTableOptions {
+    fn default() -> Self {
+        ClockTableOptions {
+            from: None,
+            to: None,
+            by: GroupBy::Heading,
+            depth: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClockRow {
+    pub group: String,
+    pub minutes: i64,
+    /// The share of the total time.
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClockTable {
+    pub rows: Vec<ClockRow>,
+    pub total_minutes: i64,
+}
+
+/// Sum the clocked time in the range into groups.
+///
+/// Headings and files are listed in the order they are first seen, days
+/// and tags in sorted order. With tags, time clocked against a heading
+/// with several tags counts towards each of them, and time without tags is
+/// grouped under `(none)`.
+pub fn clock_table(records: &[ClockRecord], options: &ClockTableOptions) -> ClockTable {
+    let mut groups: Vec<(String, i64)> = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut add = |group: String, minutes: i64| {
+        let i = *index.entry(group.clone()).or_insert_with(|| {
+            groups.push((group, 0));
+            groups.len() - 1
+        });
+        groups[i].1 += minutes;
+    };
+
+    let mut total_minutes = 0;
+    for record in records {
+        let start = options
+            .from
+            .map_or(record.start, |from| from.max(record.start));
+        let end = options.to.map_or(record.end, |to| to.min(record.end));
+        if end <= start {
+            continue;
+        }
+        let minutes = (end - start).num_minutes();
+        total_minutes += minutes;
+        match options.by {
+            GroupBy::Heading => {
+                let depth = options.depth.unwrap_or(usize::MAX);
+                let path = &record.outline_path[..record.outline_path.len().min(depth)];
+                add(path.join(" / "), minutes);
+            }
+            GroupBy::File => add(record.file.clone(), minutes),
+            GroupBy::Tag if record.tags.is_empty() => add("(none)".to_string(), minutes),
+            GroupBy::Tag => {
+                for tag in &record.tags {
+                    add(tag.clone(), minutes);
+                }
+            }
+            GroupBy::Day => {
+                let mut day_start = start;
+                while day_start < end {
+                    let midnight = (day_start.date() + Duration::days(1)).and_time(NaiveTime::MIN);
+                    let day_end = midnight.min(end);
+                    add(
+                        day_start.date().format("%Y-%m-%d %a").to_string(),
+                        (day_end - day_start).num_minutes(),
+                    );
+                    day_start = day_end;
+                }
+            }
+        }
+    }
+
+    if matches!(options.by, GroupBy::Day | GroupBy::Tag) {
+        groups.sort();
+    }
+    let rows = groups
+        .into_iter()
+        .map(|(group, minutes)| ClockRow {
+            group,
+            minutes,
+            percent: match total_minutes {
+                0 => 0.0,
+                total => minutes as f64 * 100.0 / total as f64,
+            },
+        })
+        .collect();
+    ClockTable {
+        rows,
+        total_minutes,
+    }
+}
+
+impl ClockTable {
+    /// Render as an aligned Org table with a total row.
+    pub fn to_org_table(&self, group_name: &str) -> String {
+        let mut table = Table::new(&[group_name, "Time", "%"]);
+        for row in &self.rows {
+            table.rows.push(vec![
+                row.group.clone(),
+                format_minutes(row.minutes),
+                format!("{:.1}", row.percent),
+            ]);
+        }
+        table.total = Some(vec![
+            "Total".to_string(),
+            format_minutes(self.total_minutes),
+            format!("{:.1}", self.total_percent()),
+        ]);
+        table.to_org()
+    }
+
+    /// Render as CSV with a header and a total row.
+    pub fn to_csv(&self, group_name: &str) -> String {
+        let mut table = Table::new(&[group_name, "minutes", "time", "percent"]);
+        for row in &self.rows {
+            table.rows.push(vec![
+                row.group.clone(),
+                row.minutes.to_string(),
+                format_minutes(row.minutes),
+                format!("{:.1}", row.percent),
+            ]);
+        }
+        table.total = Some(vec![
+            "Total".to_string(),
+            self.total_minutes.to_string(),
+            format_minutes(self.total_minutes),
+            format!("{:.1}", self.total_percent()),
+        ]);
+        table.to_csv()
+    }
+
+    fn total_percent(&self) -> f64 {
+        if self.total_minutes > 0 {
+            100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("clock table serializes to JSON")
+    }
+
+    pub fn render(&self, format: OutputFormat, group_name: &str) -> String {
+        match format {
+            OutputFormat::Table => self.to_org_table(group_name),
+            OutputFormat::Csv => self.to_csv(group_name),
+            OutputFormat::Json => self.to_json() + "\n",
+        }
+    }
+}
+
+/// Parse the start or end of a report range, such as `2026-10-01`, `today`
+/// or `<2026-10-01 Thu 09:00>`.
+///
+/// An end date without a time includes the whole of that day.
+pub fn parse_range_bound(input: &str, today: NaiveDate, is_end: bool) -> Result<NaiveDateTime> {
+    let timestamp = Timestamp::parse_user_input(input.trim_matches('"'), false, today)?;
+    Ok(match timestamp.time {
+        None if is_end => (timestamp.date + Duration::days(1)).and_time(NaiveTime::MIN),
+        _ => timestamp.datetime(),
+    })
+}
+
+/// Replace the contents of every `#+BEGIN: clocktable` dynamic block with
+/// a table of the time clocked in the file.
+///
+/// Blocks take the parameters `:by`, `:depth` (or `:maxlevel`), `:from`
+/// (or `:tstart`) and `:to` (or `:tend`).
+pub fn update_clocktable_blocks(
+    config: &Config,
+    input: &str,
+    file: &str,
+    today: NaiveDate,
+) -> Result<String> {
+    let records = clock_records(config, input, file);
+    let mut output = String::with_capacity(input.len());
+    let mut lines = input.split_inclusive('\n');
+
+    while let Some(line) = lines.next() {
+        output.push_str(line);
+        let Some(params) = clocktable_params(line) else {
+            continue;
+        };
+        let (options, group_name) = block_options(params, today)?;
+
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line.trim().eq_ignore_ascii_case("#+END:") {
+                if !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str(&clock_table(&records, &options).to_org_table(group_name));
+                output.push_str(line);
+                closed = true;
+                break;
+            }
+        }
+        if !closed {
+            bail!("Clock table block is missing #+END: {}", line.trim());
+        }
+    }
+    Ok(output)
+}
+
+fn clocktable_params(line: &str) -> Option<&str> {
+    let rest = line.trim();
+    let rest = rest
+        .get(..8)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("#+BEGIN:"))
+        .map(|_| rest[8..].trim_start())?;
+    let name = rest.split_whitespace().next()?;
+    name.eq_ignore_ascii_case("clocktable")
+        .then(|| rest[name.len()..].trim())
+}
+
+fn block_options(params: &str, today: NaiveDate) -> Result<(ClockTableOptions, &'static str)> {
+    let mut options = ClockTableOptions::default();
+    let mut words = params.split_whitespace();
+    while let Some(key) = words.next() {
+        let Some(mut value) = words.next().map(str::to_string) else {
+            bail!("Missing value for clock table parameter {}", key);
+        };
+        // Quoted timestamps contain spaces.
+        while value.starts_with('"') && (value.len() == 1 || !value.ends_with('"')) {
+            match words.next() {
+                Some(word) => value = format!("{} {}", value, word),
+                None => break,
+            }
+        }
+        match key {
+            ":by" => options.by = value.parse()?,
+            ":depth" | ":maxlevel" => options.depth = Some(value.parse()?),
+            ":from" | ":tstart" => options.from = Some(parse_range_bound(&value, today, false)?),
+            ":to" | ":tend" => options.to = Some(parse_range_bound(&value, today, true)?),
+            _ => {}
+        }
+    }
+    let group_name = group_name(options.by);
+    Ok((options, group_name))
+}
+
+/// The heading of the group column for a grouping.
+pub fn group_name(by: GroupBy) -> &'static str {
+    match by {
+        GroupBy::Heading => "Heading",
+        GroupBy::Tag => "Tag",
+        GroupBy::Day => "Day",
+        GroupBy::File => "File",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "* Work :work:
+** Report
+:LOGBOOK:
+CLOCK: [2026-10-16 Fri 23:00]--[2026-10-17 Sat 01:00] =>  2:00
+CLOCK: [2026-10-17 Sat 09:00]
+:END:
+*** Draft :writing:
+CLOCK: [2026-10-17 Sat 10:00]--[2026-10-17 Sat 11:00] =>  1:00
+* Home
+:LOGBOOK:
+CLOCK: [2026-10-18 Sun 09:00]--[2026-10-18 Sun 09:30] =>  0:30
+:END:
+";
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn table(options: ClockTableOptions) -> Vec<(String, i64)> {
+        let records = clock_records(&Config::default(), INPUT, "notes.org");
+        clock_table(&records, &options)
+            .rows
+            .into_iter()
+            .map(|row| (row.group, row.minutes))
+            .collect()
+    }
+
+    fn rows(rows: &[(&str, i64)]) -> Vec<(String, i64)> {
+        rows.iter()
+            .map(|(group, minutes)| (group.to_string(), *minutes))
+            .collect()
+    }
+
+    #[test]
+    fn test_clock_table_by_heading() {
+        assert_eq!(
+            table(ClockTableOptions::default()),
+            rows(&[
+                ("Work / Report", 120),
+                ("Work / Report / Draft", 60),
+                ("Home", 30)
+            ])
+        );
+        let options = ClockTableOptions {
+            depth: Some(1),
+            ..ClockTableOptions::default()
+        };
+        assert_eq!(table(options), rows(&[("Work", 180), ("Home", 30)]));
+    }
+
+    #[test]
+    fn test_clock_table_by_day_and_tag_in_range() {
+        let options = ClockTableOptions {
+            from: Some(parse_range_bound("2026-10-17", today(), false).unwrap()),
+            to: Some(parse_range_bound("2026-10-17", today(), true).unwrap()),
+            by: GroupBy::Day,
+            depth: None,
+        };
+        assert_eq!(table(options), rows(&[("2026-10-17 Sat", 120)]));
+
+        let options = ClockTableOptions {
+            by: GroupBy::Tag,
+            ..ClockTableOptions::default()
+        };
+        assert_eq!(
+            table(options),
+            rows(&[("(none)", 30), ("work", 180), ("writing", 60)])
+        );
+    }
+
+    #[test]
+    fn test_render_clock_table() {
+        let records = clock_records(&Config::default(), INPUT, "notes.org");
+        let options = ClockTableOptions {
+            by: GroupBy::File,
+            ..ClockTableOptions::default()
+        };
+        let table = clock_table(&records, &options);
+        assert_eq!(
+            table.to_org_table("File"),
+            "| File      | Time |     % |\n|-----------+------+-------|\n| notes.org | 3:30 | 100.0 |\n|-----------+------+-------|\n| Total     | 3:30 | 100.0 |\n"
+        );
+        assert_eq!(
+            table.to_csv("File"),
+            "File,minutes,time,percent\nnotes.org,210,3:30,100.0\nTotal,210,3:30,100.0\n"
+        );
+        assert!(table.to_json().contains("\"total_minutes\": 210"));
+    }
+
+    #[test]
+    fn test_update_clocktable_blocks() {
+        // Given
+        let input = format!(
+            "{}* Report\n#+BEGIN: clocktable :by heading :maxlevel 1 :tstart \"<2026-10-18 Sun>\"\nold\n#+END:\n",
+            INPUT
+        );
+
+        // When
+        let output =
+            update_clocktable_blocks(&Config::default(), &input, "notes.org", today()).unwrap();
+
+        // Then
+        assert!(output.ends_with(
+            "#+BEGIN: clocktable :by heading :maxlevel 1 :tstart \"<2026-10-18 Sun>\"\n| Heading | Time |     % |\n|---------+------+-------|\n| Home    | 0:30 | 100.0 |\n|---------+------+-------|\n| Total   | 0:30 | 100.0 |\n#+END:\n"
+        ));
+    }
+}
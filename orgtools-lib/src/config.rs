@@ -1,7 +1,75 @@
-#[derive(Debug, Clone)]
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::capture::CaptureTemplate;
+use crate::effort::DurationUnits;
+use crate::format::FormatOptions;
+use crate::heatmap::HeatmapOptions;
+use crate::html::HtmlOptions;
+use crate::lint::LintOptions;
+use crate::prune::PrunePolicy;
+use crate::review::ReviewOptions;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub keywords_unfinished: Vec<String>,
     pub keywords_finished: Vec<String>,
+    /// Named capture templates, configured as `[capture.<name>]` tables.
+    #[serde(rename = "capture")]
+    pub capture_templates: BTreeMap<String, CaptureTemplate>,
+    /// Record TODO state changes in the `:LOGBOOK:` drawer.
+    pub log_state_changes: bool,
+    /// Record completions of repeating tasks in the `:LOGBOOK:` drawer.
+    pub log_repeat: bool,
+    /// Which finished entries `prune` removes.
+    pub prune: PrunePolicy,
+    /// How `fmt` lays out files.
+    pub format: FormatOptions,
+    /// The levels of the `lint` rules.
+    pub lint: LintOptions,
+    /// The directory other org files, such as refile targets, are relative to.
+    pub workspace: Option<String>,
+    /// The lengths of the units in `:EFFORT:` durations.
+    pub duration_units: DurationUnits,
+    /// The colours and week start of `stats heatmap`.
+    pub heatmap: HeatmapOptions,
+    /// What `review` counts as next actions and waiting items.
+    pub review: ReviewOptions,
+    /// The template and table of contents of `export html`.
+    pub html: HtmlOptions,
+}
+
+impl Config {
+    /// Load a configuration file in TOML format.
+    ///
+    /// Any settings missing from the file take their default values.
+    pub fn load(path: &Path) -> Result<Config> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Error reading config file {}", path.display()))?;
+        Config::from_toml(&content)
+            .with_context(|| format!("Error parsing config file {}", path.display()))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Config> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn is_finished(&self, keyword: &str) -> bool {
+        self.keywords_finished.iter().any(|k| k == keyword)
+    }
+
+    pub fn is_unfinished(&self, keyword: &str) -> bool {
+        self.keywords_unfinished.iter().any(|k| k == keyword)
+    }
+
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_finished(keyword) || self.is_unfinished(keyword)
+    }
 }
 
 impl Default for Config {
@@ -13,6 +81,52 @@ impl Default for Config {
                 "BLOCKED".to_string(),
             ],
             keywords_finished: vec!["DONE".to_string(), "ABANDONED".to_string()],
+            capture_templates: BTreeMap::new(),
+            log_state_changes: false,
+            log_repeat: true,
+            prune: PrunePolicy::default(),
+            format: FormatOptions::default(),
+            lint: LintOptions::default(),
+            workspace: None,
+            duration_units: DurationUnits::default(),
+            heatmap: HeatmapOptions::default(),
+            review: ReviewOptions::default(),
+            html: HtmlOptions::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_defaults() {
+        let config = Config::from_toml("keywords_finished = [\"DONE\"]").unwrap();
+        assert_eq!(config.keywords_finished, vec!["DONE".to_string()]);
+        assert_eq!(
+            config.keywords_unfinished,
+            Config::default().keywords_unfinished
+        );
+        assert!(config.capture_templates.is_empty());
+    }
+
+    #[test]
+    fn test_from_toml_capture_templates() {
+        let config = Config::from_toml(
+            r#"
+[capture.meeting]
+template = "* Meeting with %^{Who} %U"
+file = "meetings.org"
+olp = ["Meetings", "2026"]
+"#,
+        )
+        .unwrap();
+        let template = &config.capture_templates["meeting"];
+        assert_eq!(template.file.as_deref(), Some("meetings.org"));
+        assert_eq!(
+            template.olp,
+            vec!["Meetings".to_string(), "2026".to_string()]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Date trees: entries filed under year, month, week and day headlines.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::org::Org;
+use crate::outline::relevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatetreeKind {
+    /// `* 2026` / `** 2026-10 October` / `*** 2026-10-17 Saturday`
+    Day,
+    /// `* 2026` / `** 2026-W42` / `*** 2026-10-17 Saturday`
+    Week,
+    /// `* 2026` / `** 2026-10 October`
+    Month,
+}
+
+impl FromStr for DatetreeKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "day" => Ok(DatetreeKind::Day),
+            "week" => Ok(DatetreeKind::Week),
+            "month" => Ok(DatetreeKind::Month),
+            _ => bail!("Invalid date tree, expected day, week or month: {}", s),
+        }
+    }
+}
+
+/// The headline titles for a date, from the year down to the entry's parent.
+///
+/// Week trees use the ISO week and ISO year, so the first days of January
+/// can belong to the previous year.
+pub fn datetree_path(kind: DatetreeKind, date: NaiveDate) -> Vec<String> {
+    let day = date.format("%Y-%m-%d %A").to_string();
+    match kind {
+        DatetreeKind::Day => vec![
+            date.format("%Y").to_string(),
+            date.format("%Y-%m %B").to_string(),
+            day,
+        ],
+        DatetreeKind::Week => {
+            let week = date.iso_week();
+            vec![
+                week.year().to_string(),
+                format!("{}-W{:02}", week.year(), week.week()),
+                day,
+            ]
+        }
+        DatetreeKind::Month => vec![
+            date.format("%Y").to_string(),
+            date.format("%Y-%m %B").to_string(),
+        ],
+    }
+}
+
+/// The first day of the year, month, ISO week or day a date tree headline
+/// stands for, to order it among its siblings.
+///
+/// Only titles exactly as [`datetree_path`] writes them count, so other
+/// headlines starting with a number are not taken for dates.
+fn title_date(title: &str) -> Option<NaiveDate> {
+    let first = title.split_whitespace().next()?;
+    let parts = first.split('-').collect::<Vec<_>>();
+    let year = parts.first()?.parse().ok()?;
+    let date = match parts[1..] {
+        [] => NaiveDate::from_ymd_opt(year, 1, 1),
+        [week] if week.starts_with('W') => {
+            NaiveDate::from_isoywd_opt(year, week[1..].parse().ok()?, Weekday::Mon)
+        }
+        [month] => NaiveDate::from_ymd_opt(year, month.parse().ok()?, 1),
+        [month, day] => NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?),
+        _ => None,
+    }?;
+    [DatetreeKind::Day, DatetreeKind::Week]
+        .into_iter()
+        .any(|kind| datetree_path(kind, date).iter().any(|path| path == title))
+        .then_some(date)
+}
+
+/// Add an entry, starting with a level 1 headline, under its date in a
+/// date tree, which is itself under the section at the end of `path` or at
+/// the top level.
+///
+/// Missing year, month, week or day headlines are created in date order
+/// among their siblings.
+pub fn add_to_datetree(
+    config: &Config,
+    input: &str,
+    entry: &str,
+    kind: DatetreeKind,
+    date: NaiveDate,
+    path: &[String],
+) -> Result<String> {
+    let org_file = Org::from_config(config.clone()).load(input);
+    let mut output = org_file.output_builder();
+    let mut parent = if path.is_empty() {
+        None
+    } else {
+        let section = org_file
+            .find_outline_path(path)
+            .ok_or_else(|| anyhow!("Could not find outline path: {}", path.join("/")))?;
+        Some(section)
+    };
+    let titles = datetree_path(kind, date);
+
+    for (depth, title) in titles.iter().enumerate() {
+        let mut siblings = match &parent {
+            Some(section) => section.subsections(),
+            None => org_file.subsections(),
+        };
+        if let Some(index) = siblings
+            .iter()
+            .position(|section| section.headline_text() == Some(title.as_str()))
+        {
+            parent = Some(siblings.swap_remove(index));
+            continue;
+        }
+
+        let level = parent.as_ref().map_or(1, |section| section.stars() + 1);
+        let mut text = String::new();
+        for (offset, title) in titles[depth..].iter().enumerate() {
+            text.push_str(&format!("{} {}\n", "*".repeat(level + offset), title));
+        }
+        text.push_str(&relevel(entry, level + titles.len() - depth)?);
+
+        let title_date = title_date(title);
+        let next = siblings.iter().find(|section| {
+            section
+                .headline_text()
+                .and_then(self::title_date)
+                .is_some_and(|date| Some(date) > title_date)
+        });
+        match (next, &parent) {
+            (Some(next), _) => output.append_up_to_section(next),
+            (None, Some(section)) => output.append_to_end_of_section(section),
+            (None, None) => output.append_to(input.len()),
+        }
+        output.ensure_newline();
+        output.insert_text(&text);
+        return Ok(output.append_to_end_of_input());
+    }
+
+    let day = parent.expect("date tree has at least one level");
+    output.append_to_end_of_section(&day);
+    output.ensure_newline();
+    output.insert_text(&relevel(entry, day.stars() + 1)?);
+    Ok(output.append_to_end_of_input())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datetree_path() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        assert_eq!(
+            datetree_path(DatetreeKind::Day, date),
+            vec!["2026", "2026-10 October", "2026-10-17 Saturday"]
+        );
+        assert_eq!(
+            datetree_path(DatetreeKind::Week, date),
+            vec!["2026", "2026-W42", "2026-10-17 Saturday"]
+        );
+        assert_eq!(
+            datetree_path(DatetreeKind::Month, date),
+            vec!["2026", "2026-10 October"]
+        );
+    }
+
+    #[test]
+    fn test_week_datetree_uses_iso_year() {
+        let date = NaiveDate::from_ymd_opt(2027, 1, 1).unwrap();
+        assert_eq!(
+            datetree_path(DatetreeKind::Week, date),
+            vec!["2026", "2026-W53", "2027-01-01 Friday"]
+        );
+    }
+
+    fn add(input: &str, kind: DatetreeKind, path: &[String]) -> String {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        add_to_datetree(&Config::default(), input, "* Entry\n", kind, date, path).unwrap()
+    }
+
+    #[test]
+    fn test_add_to_empty_datetree() {
+        let result = add("#+TITLE: Journal\n", DatetreeKind::Day, &[]);
+        assert_eq!(
+            result,
+            "#+TITLE: Journal\n* 2026\n** 2026-10 October\n*** 2026-10-17 Saturday\n**** Entry\n"
+        );
+    }
+
+    #[test]
+    fn test_add_to_existing_day() {
+        let input = "* 2026\n** 2026-10 October\n*** 2026-10-17 Saturday\n**** Old\n* 2027\n";
+        let result = add(input, DatetreeKind::Day, &[]);
+        assert_eq!(
+            result,
+            "* 2026\n** 2026-10 October\n*** 2026-10-17 Saturday\n**** Old\n**** Entry\n* 2027\n"
+        );
+    }
+
+    #[test]
+    fn test_add_to_datetree_in_sorted_position() {
+        let input = "* 2026\n** 2026-09 September\n** 2026-11 November\n*** 2026-11-01 Sunday\n";
+        let result = add(input, DatetreeKind::Day, &[]);
+        assert_eq!(
+            result,
+            "* 2026\n** 2026-09 September\n** 2026-10 October\n*** 2026-10-17 Saturday\n**** Entry\n** 2026-11 November\n*** 2026-11-01 Sunday\n"
+        );
+    }
+
+    #[test]
+    fn test_add_to_datetree_orders_by_date() {
+        let input = "* 42 ideas\n* 2027\n";
+        let result = add(input, DatetreeKind::Month, &[]);
+        assert_eq!(
+            result,
+            "* 42 ideas\n* 2026\n** 2026-10 October\n*** Entry\n* 2027\n"
+        );
+        let input = "* 2025\n* 3000 things\n* 2027\n";
+        let result = add(input, DatetreeKind::Month, &[]);
+        assert_eq!(
+            result,
+            "* 2025\n* 3000 things\n* 2026\n** 2026-10 October\n*** Entry\n* 2027\n"
+        );
+        let input = "* 2026\n** 2026-11 plans\n** 2026-12 December\n";
+        let result = add(input, DatetreeKind::Month, &[]);
+        assert_eq!(
+            result,
+            "* 2026\n** 2026-11 plans\n** 2026-10 October\n*** Entry\n** 2026-12 December\n"
+        );
+        let input = "* 2026\n** 2026-W43\n";
+        let result = add(input, DatetreeKind::Week, &[]);
+        assert_eq!(
+            result,
+            "* 2026\n** 2026-W42\n*** 2026-10-17 Saturday\n**** Entry\n** 2026-W43\n"
+        );
+    }
+
+    #[test]
+    fn test_add_to_week_datetree_under_outline_path() {
+        let input = "* Journal\n** 2025\n* Other";
+        let path = vec!["Journal".to_string()];
+        let result = add(input, DatetreeKind::Week, &path);
+        assert_eq!(
+            result,
+            "* Journal\n** 2025\n** 2026\n*** 2026-W42\n**** 2026-10-17 Saturday\n***** Entry\n* Other"
+        );
+    }
+
+    #[test]
+    fn test_add_to_month_datetree() {
+        let input = "* 2026\n** 2026-10 October\n";
+        let result = add(input, DatetreeKind::Month, &[]);
+        assert_eq!(result, "* 2026\n** 2026-10 October\n*** Entry\n");
+    }
+}
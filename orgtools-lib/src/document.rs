@@ -0,0 +1,860 @@
+//! The elements of an Org document, for exporting to other formats.
+//!
+//! [`parse_document`] turns Org text into a tree of blocks, such as headings,
+//! paragraphs, lists and tables, holding inline elements, such as emphasis,
+//! links and timestamps. Headings tagged `:noexport:` and `COMMENT` headings
+//! are left out along with their subtrees, as are drawers, planning lines and
+//! comments.
+
+use crate::config::Config;
+use crate::entry::{parse_property, Headline, Planning};
+use crate::outline::{headline_level, inside_blocks};
+use crate::timestamp::Timestamp;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    /// The `#+KEY: value` lines, with upper case keys.
+    pub keywords: Vec<(String, String)>,
+    pub blocks: Vec<Block>,
+    pub footnotes: Vec<Footnote>,
+}
+
+impl Document {
+    pub fn keyword(&self, key: &str) -> Option<&str> {
+        self.keywords
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.keyword("TITLE")
+    }
+
+    /// The headings in document order.
+    pub fn headings(&self) -> impl Iterator<Item = &Heading> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Heading(heading) => Some(heading),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// A headline. The blocks of its section follow it rather than being
+    /// nested in it.
+    Heading(Heading),
+    Paragraph(Vec<Inline>),
+    List(List),
+    Table(Table),
+    Src {
+        language: Option<String>,
+        code: String,
+    },
+    /// An example block or fixed width lines.
+    Example(String),
+    Quote(Vec<Block>),
+    HorizontalRule,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    pub level: usize,
+    pub keyword: Option<String>,
+    pub priority: Option<char>,
+    pub title: Vec<Inline>,
+    pub tags: Vec<String>,
+    /// The `:CUSTOM_ID:` property.
+    pub custom_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct List {
+    pub ordered: bool,
+    pub items: Vec<ListItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListItem {
+    pub checkbox: Option<Checkbox>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checkbox {
+    Unchecked,
+    Partial,
+    Checked,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub rows: Vec<TableRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableRow {
+    Rule,
+    Cells(Row),
+}
+
+/// The inline elements of each cell of a table row.
+pub type Row = Vec<Vec<Inline>>;
+
+impl Table {
+    /// The rows above the first rule, if there is one below some rows, and
+    /// the other rows, without rules.
+    pub fn header_and_body(&self) -> (Vec<&Row>, Vec<&Row>) {
+        fn cells(rows: &[TableRow]) -> Vec<&Row> {
+            rows.iter()
+                .filter_map(|row| match row {
+                    TableRow::Cells(cells) => Some(cells),
+                    TableRow::Rule => None,
+                })
+                .collect()
+        }
+        match self.rows.iter().position(|row| *row == TableRow::Rule) {
+            Some(rule) if rule > 0 => (cells(&self.rows[..rule]), cells(&self.rows[rule..])),
+            _ => (vec![], cells(&self.rows)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Footnote {
+    pub name: String,
+    pub content: Vec<Inline>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Bold(Vec<Inline>),
+    Italic(Vec<Inline>),
+    Underline(Vec<Inline>),
+    StrikeThrough(Vec<Inline>),
+    Verbatim(String),
+    Code(String),
+    Link {
+        target: String,
+        description: Option<Vec<Inline>>,
+    },
+    FootnoteReference(String),
+    Timestamp(Timestamp),
+    LineBreak,
+}
+
+/// The text of inline elements without any markup.
+pub fn plain_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(s) | Inline::Verbatim(s) | Inline::Code(s) => text.push_str(s),
+            Inline::Bold(inner)
+            | Inline::Italic(inner)
+            | Inline::Underline(inner)
+            | Inline::StrikeThrough(inner) => text.push_str(&plain_text(inner)),
+            Inline::Link {
+                description: Some(description),
+                ..
+            } => text.push_str(&plain_text(description)),
+            Inline::Link { target, .. } => text.push_str(target),
+            Inline::FootnoteReference(_) => {}
+            Inline::Timestamp(timestamp) => text.push_str(&timestamp.to_string()),
+            Inline::LineBreak => text.push(' '),
+        }
+    }
+    text
+}
+
+/// The file extensions of links that are exported as images.
+pub const IMAGE_EXTENSIONS: [&str; 6] = [".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp"];
+
+/// The heading title an internal link points to: `*Title`, or a fuzzy link
+/// without a scheme or path, such as `[[Title]]`.
+pub(crate) fn heading_link_title(target: &str) -> Option<&str> {
+    if let Some(title) = target.strip_prefix('*') {
+        return Some(title);
+    }
+    let lower = target.to_lowercase();
+    let is_fuzzy = !target.contains(':')
+        && !target.starts_with(['#', '/', '.', '~'])
+        && !IMAGE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext));
+    is_fuzzy.then_some(target)
+}
+
+/// Lower case words of `text` joined by `-`, for file names and anchors.
+pub fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parse Org text into a document, recognising the keywords from `config`.
+pub fn parse_document(config: &Config, input: &str) -> Document {
+    let mut document = Document::default();
+    let lines = input.lines().collect::<Vec<_>>();
+    // Lines in blocks are never headlines or file keywords.
+    let inside = inside_blocks(&lines);
+    let mut section = vec![];
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        if inside[index - 1] {
+            section.push(line);
+            continue;
+        }
+        let Some(headline) = Headline::parse(line, config) else {
+            if let Some((key, value)) = keyword_line(line) {
+                if !key.starts_with("BEGIN_") && !key.starts_with("END_") {
+                    document.keywords.push((key, value));
+                    continue;
+                }
+            }
+            section.push(line);
+            continue;
+        };
+
+        let parsed = parse_blocks(&section, &mut document.footnotes);
+        document.blocks.extend(parsed);
+        section.clear();
+
+        let excluded = headline.tags.iter().any(|tag| tag == "noexport")
+            || headline.title == "COMMENT"
+            || headline.title.starts_with("COMMENT ");
+        if excluded {
+            while index < lines.len()
+                && headline_level(lines[index])
+                    .filter(|_| !inside[index])
+                    .is_none_or(|level| level > headline.level)
+            {
+                index += 1;
+            }
+            continue;
+        }
+
+        // The planning line and property drawer directly below the headline.
+        if lines
+            .get(index)
+            .is_some_and(|line| Planning::parse(line).is_some())
+        {
+            index += 1;
+        }
+        let mut custom_id = None;
+        if lines
+            .get(index)
+            .is_some_and(|line| line.trim().eq_ignore_ascii_case(":PROPERTIES:"))
+        {
+            index += 1;
+            while index < lines.len() && !lines[index].trim().eq_ignore_ascii_case(":END:") {
+                if let Some((name, value)) = parse_property(lines[index]) {
+                    if name.eq_ignore_ascii_case("CUSTOM_ID") {
+                        custom_id = Some(value);
+                    }
+                }
+                index += 1;
+            }
+            index += 1;
+        }
+
+        document.blocks.push(Block::Heading(Heading {
+            level: headline.level,
+            keyword: headline.keyword,
+            priority: headline.priority,
+            title: parse_inline(&headline.title),
+            tags: headline.tags,
+            custom_id,
+        }));
+    }
+    let parsed = parse_blocks(&section, &mut document.footnotes);
+    document.blocks.extend(parsed);
+    document
+}
+
+/// A `#+KEY: value` line, with the key in upper case.
+fn keyword_line(line: &str) -> Option<(String, String)> {
+    let rest = line.trim_start().strip_prefix("#+")?;
+    let end = rest.find([':', ' ', '\t']).unwrap_or(rest.len());
+    if end == 0 {
+        return None;
+    }
+    let value = rest[end..].strip_prefix(':').unwrap_or(&rest[end..]);
+    Some((rest[..end].to_uppercase(), value.trim().to_string()))
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_drawer_start(line: &str) -> bool {
+    let line = line.trim();
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// A list bullet at the start of a trimmed line: whether it's ordered, and
+/// the length of the bullet and the space after it.
+fn bullet(line: &str) -> Option<(bool, usize)> {
+    if line == "-" || line == "+" {
+        return Some((false, 1));
+    }
+    if line.starts_with("- ") || line.starts_with("+ ") {
+        return Some((false, 2));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((true, digits + 2));
+    }
+    None
+}
+
+/// Whether a line starts a block other than a paragraph.
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.is_empty()
+        || trimmed.starts_with('|')
+        || trimmed.starts_with("#+")
+        || trimmed == "#"
+        || trimmed.starts_with("# ")
+        || trimmed == ":"
+        || trimmed.starts_with(": ")
+        || is_drawer_start(trimmed)
+        || is_horizontal_rule(trimmed)
+        || (bullet(trimmed).is_some() && !(indent(line) == 0 && trimmed.starts_with('*')))
+        || (indent(line) == 0 && trimmed.starts_with("[fn:"))
+        || Planning::parse(trimmed).is_some()
+}
+
+fn is_horizontal_rule(line: &str) -> bool {
+    line.len() >= 5 && line.chars().all(|c| c == '-')
+}
+
+/// The lines with their common indentation removed.
+fn dedent(lines: &[&str]) -> Vec<String> {
+    let common = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent(line))
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(common..).unwrap_or("").trim_end().to_string())
+        .collect()
+}
+
+/// The index of the `#+END_<name>` line matching a `#+BEGIN_<name>` line.
+fn block_end(lines: &[&str], start: usize, name: &str) -> usize {
+    let end = format!("#+END_{}", name);
+    (start + 1..lines.len())
+        .find(|&index| {
+            lines[index]
+                .trim()
+                .get(..end.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&end))
+        })
+        .unwrap_or(lines.len())
+}
+
+fn parse_blocks(lines: &[&str], footnotes: &mut Vec<Footnote>) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed == "#" || trimmed.starts_with("# ") {
+            index += 1;
+        } else if is_drawer_start(trimmed) {
+            while index < lines.len() && !lines[index].trim().eq_ignore_ascii_case(":END:") {
+                index += 1;
+            }
+            index += 1;
+        } else if let Some((key, value)) = keyword_line(trimmed) {
+            let Some(name) = key.strip_prefix("BEGIN_") else {
+                index += 1;
+                continue;
+            };
+            let end = block_end(lines, index, name);
+            let content = dedent(&lines[index + 1..end.min(lines.len())]);
+            match name {
+                "SRC" => blocks.push(Block::Src {
+                    language: value.split_whitespace().next().map(str::to_string),
+                    code: content.join("\n"),
+                }),
+                "EXAMPLE" => blocks.push(Block::Example(content.join("\n"))),
+                _ => {
+                    let content = content.iter().map(String::as_str).collect::<Vec<_>>();
+                    let inner = parse_blocks(&content, footnotes);
+                    if name == "QUOTE" {
+                        blocks.push(Block::Quote(inner));
+                    } else {
+                        blocks.extend(inner);
+                    }
+                }
+            }
+            index = end + 1;
+        } else if trimmed.starts_with('|') {
+            let mut rows = vec![];
+            while index < lines.len() && lines[index].trim().starts_with('|') {
+                let row = lines[index].trim();
+                if row.starts_with("|-") {
+                    rows.push(TableRow::Rule);
+                } else {
+                    let inner = row.strip_prefix('|').unwrap_or(row);
+                    let inner = inner.strip_suffix('|').unwrap_or(inner);
+                    rows.push(TableRow::Cells(
+                        inner
+                            .split('|')
+                            .map(|cell| parse_inline(cell.trim()))
+                            .collect(),
+                    ));
+                }
+                index += 1;
+            }
+            blocks.push(Block::Table(Table { rows }));
+        } else if trimmed == ":" || trimmed.starts_with(": ") {
+            let mut example = vec![];
+            while index < lines.len() {
+                let trimmed = lines[index].trim();
+                if trimmed == ":" {
+                    example.push("");
+                } else if let Some(text) = trimmed.strip_prefix(": ") {
+                    example.push(text);
+                } else {
+                    break;
+                }
+                index += 1;
+            }
+            blocks.push(Block::Example(example.join("\n")));
+        } else if is_horizontal_rule(trimmed) {
+            blocks.push(Block::HorizontalRule);
+            index += 1;
+        } else if indent(line) == 0 && trimmed.starts_with("[fn:") && trimmed.contains(']') {
+            let close = trimmed.find(']').expect("checked above");
+            let mut text = trimmed[close + 1..].trim().to_string();
+            index += 1;
+            while index < lines.len() && !starts_block(lines[index]) {
+                text.push('\n');
+                text.push_str(lines[index].trim());
+                index += 1;
+            }
+            footnotes.push(Footnote {
+                name: trimmed[4..close].to_string(),
+                content: parse_inline(&text),
+            });
+        } else if Planning::parse(trimmed).is_some() {
+            index += 1;
+        } else if let Some((ordered, _)) = bullet(trimmed) {
+            let (list, next) = parse_list(lines, index, ordered, footnotes);
+            blocks.push(Block::List(list));
+            index = next;
+        } else {
+            let mut paragraph = vec![trimmed];
+            index += 1;
+            while index < lines.len() && !starts_block(lines[index]) {
+                paragraph.push(lines[index].trim());
+                index += 1;
+            }
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join("\n"))));
+        }
+    }
+    blocks
+}
+
+/// Parse the list starting at `start`, returning it and the index of the
+/// line after it.
+fn parse_list(
+    lines: &[&str],
+    start: usize,
+    ordered: bool,
+    footnotes: &mut Vec<Footnote>,
+) -> (List, usize) {
+    let list_indent = indent(lines[start]);
+    let mut items = vec![];
+    let mut index = start;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+        let Some((item_ordered, bullet_len)) =
+            bullet(trimmed.trim_end()).or_else(|| bullet(trimmed))
+        else {
+            break;
+        };
+        if indent(line) != list_indent || item_ordered != ordered {
+            break;
+        }
+
+        let mut first = trimmed.get(bullet_len..).unwrap_or("").trim_end();
+        let mut checkbox = None;
+        for (marker, state) in [
+            ("[ ]", Checkbox::Unchecked),
+            ("[-]", Checkbox::Partial),
+            ("[X]", Checkbox::Checked),
+            ("[x]", Checkbox::Checked),
+        ] {
+            if let Some(rest) = first.strip_prefix(marker) {
+                checkbox = Some(state);
+                first = rest.trim_start();
+            }
+        }
+
+        index += 1;
+        let mut content = vec![];
+        let mut blanks = 0;
+        while index < lines.len() {
+            let line = lines[index];
+            if line.trim().is_empty() {
+                blanks += 1;
+                if blanks == 2 {
+                    break;
+                }
+            } else if indent(line) <= list_indent {
+                break;
+            } else {
+                blanks = 0;
+            }
+            content.push(line);
+            index += 1;
+        }
+        while content.last().is_some_and(|line| line.trim().is_empty()) {
+            content.pop();
+        }
+        let mut item_lines = vec![first.to_string()];
+        item_lines.extend(dedent(&content));
+        let item_lines = item_lines.iter().map(String::as_str).collect::<Vec<_>>();
+        items.push(ListItem {
+            checkbox,
+            blocks: parse_blocks(&item_lines, footnotes),
+        });
+
+        // A blank line between items doesn't end the list.
+        let next = (index..lines.len()).find(|&index| !lines[index].trim().is_empty());
+        match next {
+            Some(next) if next - index < 2 && indent(lines[next]) == list_indent => index = next,
+            _ => break,
+        }
+    }
+    (List { ordered, items }, index)
+}
+
+const EMPHASIS_PRE: &str = " \t\n-({'\"";
+const EMPHASIS_POST: &str = " \t\n-.,:!?;'\")}[";
+
+/// Parse emphasis, links, footnote references and timestamps in text.
+pub fn parse_inline(text: &str) -> Vec<Inline> {
+    let mut inlines = vec![];
+    let mut plain = String::new();
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        let previous = text[..index].chars().next_back();
+        if let Some((inline, len)) = parse_inline_at(rest, previous) {
+            if !plain.is_empty() {
+                inlines.push(Inline::Text(std::mem::take(&mut plain)));
+            }
+            inlines.push(inline);
+            index += len;
+            continue;
+        }
+        let c = rest.chars().next().expect("index is within the text");
+        plain.push(c);
+        index += c.len_utf8();
+    }
+    if !plain.is_empty() {
+        inlines.push(Inline::Text(plain));
+    }
+    inlines
+}
+
+/// The length in bytes of the verbatim or code span at the start of `rest`.
+pub(crate) fn verbatim_len(rest: &str, previous: Option<char>) -> Option<usize> {
+    if !rest.starts_with(['=', '~']) {
+        return None;
+    }
+    match parse_inline_at(rest, previous)? {
+        (Inline::Verbatim(_) | Inline::Code(_), len) => Some(len),
+        _ => None,
+    }
+}
+
+/// The inline element at the start of `rest` and its length in bytes.
+fn parse_inline_at(rest: &str, previous: Option<char>) -> Option<(Inline, usize)> {
+    if let Some(link) = rest.strip_prefix("[[") {
+        let end = link.find("]]")?;
+        let (target, description) = match link[..end].split_once("][") {
+            Some((target, description)) => (target, Some(parse_inline(description))),
+            None => (&link[..end], None),
+        };
+        return Some((
+            Inline::Link {
+                target: target.to_string(),
+                description,
+            },
+            end + 4,
+        ));
+    }
+    if let Some(name) = rest.strip_prefix("[fn:") {
+        let end = name.find(']')?;
+        let name = &name[..end];
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return None;
+        }
+        return Some((Inline::FootnoteReference(name.to_string()), end + 5));
+    }
+    if rest.starts_with("\\\\") && rest[2..].trim_start_matches([' ', '\t']).starts_with('\n') {
+        let len = 2 + rest[2..].find('\n').expect("checked above") + 1;
+        return Some((Inline::LineBreak, len));
+    }
+    if rest.starts_with(['<', '[']) && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        let (timestamp, len) = Timestamp::parse_prefix(rest).ok()?;
+        return Some((Inline::Timestamp(timestamp), len));
+    }
+
+    let marker = rest.chars().next()?;
+    if !"*/_+=~".contains(marker) || previous.is_some_and(|c| !EMPHASIS_PRE.contains(c)) {
+        return None;
+    }
+    let inner = &rest[1..];
+    if inner.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = inner.char_indices().skip(1).find_map(|(index, c)| {
+        if c != marker {
+            return None;
+        }
+        let before = inner[..index].chars().next_back()?;
+        let after = inner[index + c.len_utf8()..].chars().next();
+        (!before.is_whitespace() && after.is_none_or(|after| EMPHASIS_POST.contains(after)))
+            .then_some(index)
+    })?;
+    let contents = &inner[..end];
+    let inline = match marker {
+        '*' => Inline::Bold(parse_inline(contents)),
+        '/' => Inline::Italic(parse_inline(contents)),
+        '_' => Inline::Underline(parse_inline(contents)),
+        '+' => Inline::StrikeThrough(parse_inline(contents)),
+        '=' => Inline::Verbatim(contents.to_string()),
+        _ => Inline::Code(contents.to_string()),
+    };
+    Some((inline, end + 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_parse_inline() {
+        assert_eq!(
+            parse_inline(
+                "A *bold /and italic/* word, =x*y= and [[https://example.com][a /link/]]."
+            ),
+            vec![
+                text("A "),
+                Inline::Bold(vec![
+                    text("bold "),
+                    Inline::Italic(vec![text("and italic")])
+                ]),
+                text(" word, "),
+                Inline::Verbatim("x*y".to_string()),
+                text(" and "),
+                Inline::Link {
+                    target: "https://example.com".to_string(),
+                    description: Some(vec![text("a "), Inline::Italic(vec![text("link")])]),
+                },
+                text("."),
+            ]
+        );
+        assert_eq!(
+            parse_inline("2*3*4 and a/b/c"),
+            vec![text("2*3*4 and a/b/c")]
+        );
+        assert_eq!(
+            parse_inline("Due <2026-10-17 Sat>[fn:1]"),
+            vec![
+                text("Due "),
+                Inline::Timestamp(Timestamp::parse_prefix("<2026-10-17 Sat>").unwrap().0),
+                Inline::FootnoteReference("1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_non_ascii() {
+        assert_eq!(
+            parse_inline("*très* bien, ~naïve~ ou /ça/"),
+            vec![
+                Inline::Bold(vec![text("très")]),
+                text(" bien, "),
+                Inline::Code("naïve".to_string()),
+                text(" ou "),
+                Inline::Italic(vec![text("ça")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_document_blocks_hide_headlines_and_keywords() {
+        // Given
+        let input = "#+TITLE: Guide
+* Example :noexport:
+#+BEGIN_SRC org
+* Not a heading
+#+END_SRC
+* Samples
+#+BEGIN_SRC org
+#+TITLE: Inner
+* Inner heading
+#+END_SRC
+#+begin_example
+#+TITLE: Example
+#+end_example
+";
+
+        // When
+        let document = parse_document(&Config::default(), input);
+
+        // Then
+        assert_eq!(
+            document.keywords,
+            vec![("TITLE".to_string(), "Guide".to_string())]
+        );
+        assert_eq!(document.headings().count(), 1);
+        assert_eq!(
+            document.blocks[1..],
+            [
+                Block::Src {
+                    language: Some("org".to_string()),
+                    code: "#+TITLE: Inner\n* Inner heading".to_string(),
+                },
+                Block::Example("#+TITLE: Example".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_document() {
+        // Given
+        let input = "#+TITLE: Guide
+Intro.
+
+* TODO [#A] Install :setup:
+SCHEDULED: <2026-10-20 Tue>
+:PROPERTIES:
+:CUSTOM_ID: install
+:END:
+- [X] Download
+- [ ] Unpack
+  1. Open the archive
+  2. Extract it
+
+#+BEGIN_SRC sh :results none
+  make install
+#+END_SRC
+* Notes :noexport:
+Private.
+** Sub
+* COMMENT Drafts
+* Usage
+| Option | Meaning |
+|--------+---------|
+| -v     | Verbose |
+#+BEGIN_QUOTE
+Quoted text.
+#+END_QUOTE
+: fixed
+-----
+See the note.[fn:1]
+
+[fn:1] A footnote.
+";
+
+        // When
+        let document = parse_document(&Config::default(), input);
+
+        // Then
+        assert_eq!(document.title(), Some("Guide"));
+        let headings = document.headings().collect::<Vec<_>>();
+        assert_eq!(headings.len(), 2);
+        assert_eq!(
+            headings[0],
+            &Heading {
+                level: 1,
+                keyword: Some("TODO".to_string()),
+                priority: Some('A'),
+                title: vec![text("Install")],
+                tags: vec!["setup".to_string()],
+                custom_id: Some("install".to_string()),
+            }
+        );
+        assert_eq!(
+            document.blocks[2],
+            Block::List(List {
+                ordered: false,
+                items: vec![
+                    ListItem {
+                        checkbox: Some(Checkbox::Checked),
+                        blocks: vec![Block::Paragraph(vec![text("Download")])],
+                    },
+                    ListItem {
+                        checkbox: Some(Checkbox::Unchecked),
+                        blocks: vec![
+                            Block::Paragraph(vec![text("Unpack")]),
+                            Block::List(List {
+                                ordered: true,
+                                items: vec![
+                                    ListItem {
+                                        checkbox: None,
+                                        blocks: vec![Block::Paragraph(vec![text(
+                                            "Open the archive"
+                                        )])],
+                                    },
+                                    ListItem {
+                                        checkbox: None,
+                                        blocks: vec![Block::Paragraph(vec![text("Extract it")])],
+                                    },
+                                ],
+                            }),
+                        ],
+                    },
+                ],
+            })
+        );
+        assert_eq!(
+            document.blocks[3],
+            Block::Src {
+                language: Some("sh".to_string()),
+                code: "make install".to_string(),
+            }
+        );
+        assert!(matches!(document.blocks[5], Block::Table(_)));
+        assert_eq!(
+            document.blocks[6],
+            Block::Quote(vec![Block::Paragraph(vec![text("Quoted text.")])])
+        );
+        assert_eq!(document.blocks[7], Block::Example("fixed".to_string()));
+        assert_eq!(document.blocks[8], Block::HorizontalRule);
+        assert_eq!(document.blocks.len(), 10);
+        assert_eq!(
+            document.footnotes,
+            vec![Footnote {
+                name: "1".to_string(),
+                content: vec![text("A footnote.")],
+            }]
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Effort estimates in `:EFFORT:` properties, compared with the time clocked.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{format_minutes, Clock};
+use crate::config::Config;
+use crate::org::{Org, Section};
+use crate::report::{OutputFormat, Table};
+
+/// The length of each duration unit in minutes, unless configured otherwise.
+pub const DEFAULT_DURATION_UNITS: &[(&str, i64)] = &[
+    ("min", 1),
+    ("h", 60),
+    ("d", 24 * 60),
+    ("w", 7 * 24 * 60),
+    ("m", 30 * 24 * 60),
+    ("y", 365 * 24 * 60),
+];
+
+/// The lengths of duration units in minutes, configured as the
+/// `[duration_units]` table, for example `d = 480` for eight hour days.
+///
+/// Units that aren't configured keep their default length.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(transparent)]
+pub struct DurationUnits(pub BTreeMap<String, i64>);
+
+impl DurationUnits {
+    pub fn minutes(&self, unit: &str) -> Option<i64> {
+        self.0.get(unit).copied().or_else(|| {
+            DEFAULT_DURATION_UNITS
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .map(|(_, minutes)| *minutes)
+        })
+    }
+
+    /// Parse a duration such as `1:30`, `2d`, `30min`, `1.5h` or `1d 2h`
+    /// into minutes. A number without a unit is a number of minutes.
+    pub fn parse(&self, input: &str) -> Result<i64> {
+        let mut minutes = 0.0;
+        let mut words = input.split_whitespace().peekable();
+        if words.peek().is_none() {
+            bail!("Empty duration");
+        }
+        for word in words {
+            if let Some((hours, mins)) = word.split_once(':') {
+                let hours: i64 = hours.parse().context("Invalid hours")?;
+                let mins: i64 = mins.parse().context("Invalid minutes")?;
+                minutes += (hours * 60 + mins) as f64;
+                continue;
+            }
+            let mut rest = word;
+            while !rest.is_empty() {
+                let number_end = rest
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(rest.len());
+                let unit_end = rest[number_end..]
+                    .find(|c: char| !c.is_alphabetic())
+                    .map_or(rest.len(), |end| number_end + end);
+                let number: f64 = rest[..number_end]
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid duration: {}", input))?;
+                let unit = &rest[number_end..unit_end];
+                let unit_minutes = match unit {
+                    "" => 1,
+                    _ => self
+                        .minutes(unit)
+                        .ok_or_else(|| anyhow!("Unknown duration unit {} in {}", unit, input))?,
+                };
+                minutes += number * unit_minutes as f64;
+                rest = &rest[unit_end..];
+            }
+        }
+        Ok(minutes.round() as i64)
+    }
+}
+
+/// The estimate and clocked time of a heading and its subtree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffortRow {
+    pub file: String,
+    /// The headlines from the top level down to this heading.
+    pub outline_path: Vec<String>,
+    /// The heading's own estimate, or the sum of its subtasks' estimates if
+    /// any of them have one, as in Org's column view.
+    pub estimate_minutes: Option<i64>,
+    /// The finished clocks of the heading and its subtree.
+    pub actual_minutes: i64,
+}
+
+impl EffortRow {
+    pub fn level(&self) -> usize {
+        self.outline_path.len()
+    }
+}
+
+/// The estimate and actual time of every heading that has either, in
+/// document order.
+pub fn effort_rows(config: &Config, input: &str, file: &str) -> Result<Vec<EffortRow>> {
+    fn visit(
+        config: &Config,
+        input: &str,
+        file: &str,
+        section: &Section,
+        path: &mut Vec<String>,
+        rows: &mut Vec<EffortRow>,
+    ) -> Result<(Option<i64>, i64)> {
+        let entry = section.entry();
+        path.push(entry.headline().title.clone());
+        let own_estimate = match entry.property("EFFORT") {
+            Some(effort) => Some(
+                config
+                    .duration_units
+                    .parse(&effort)
+                    .with_context(|| format!("Invalid effort for {}", path.join(" / ")))?,
+            ),
+            None => None,
+        };
+        let mut actual = input[section.start_byte()..section.content_end_byte()]
+            .lines()
+            .filter_map(Clock::parse)
+            .filter_map(|clock| clock.duration())
+            .map(|duration| duration.num_minutes())
+            .sum::<i64>();
+
+        let index = rows.len();
+        rows.push(EffortRow {
+            file: file.to_string(),
+            outline_path: path.clone(),
+            estimate_minutes: None,
+            actual_minutes: 0,
+        });
+        let mut subtask_estimate = None;
+        for subsection in section.subsections() {
+            let (estimate, subtask_actual) = visit(config, input, file, &subsection, path, rows)?;
+            if let Some(estimate) = estimate {
+                subtask_estimate = Some(subtask_estimate.unwrap_or(0) + estimate);
+            }
+            actual += subtask_actual;
+        }
+        path.pop();
+
+        let estimate = subtask_estimate.or(own_estimate);
+        if estimate.is_none() && actual == 0 {
+            rows.remove(index);
+        } else {
+            rows[index].estimate_minutes = estimate;
+            rows[index].actual_minutes = actual;
+        }
+        Ok((estimate, actual))
+    }
+
+    let org = Org::from_config(config.clone()).load(input);
+    let mut rows = vec![];
+    for section in org.subsections() {
+        visit(config, input, file, &section, &mut vec![], &mut rows)?;
+    }
+    Ok(rows)
+}
+
+/// A comparison of estimates with the time clocked, with the totals of the
+/// top level headings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffortReport {
+    pub rows: Vec<EffortRow>,
+    pub total_estimate_minutes: i64,
+    pub total_actual_minutes: i64,
+}
+
+impl EffortReport {
+    /// Build a report of the rows at most `depth` levels deep.
+    pub fn new(rows: Vec<EffortRow>, depth: Option<usize>) -> Self {
+        let top_level = rows.iter().filter(|row| row.level() == 1);
+        let total_estimate_minutes = top_level
+            .clone()
+            .filter_map(|row| row.estimate_minutes)
+            .sum();
+        let total_actual_minutes = top_level.map(|row| row.actual_minutes).sum();
+        let depth = depth.unwrap_or(usize::MAX);
+        EffortReport {
+            rows: rows
+                .into_iter()
+                .filter(|row| row.level() <= depth)
+                .collect(),
+            total_estimate_minutes,
+            total_actual_minutes,
+        }
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Table => self.table(indented_title, format_minutes).to_org(),
+            OutputFormat::Csv => self
+                .table(
+                    |row| row.outline_path.join(" / "),
+                    |minutes| minutes.to_string(),
+                )
+                .to_csv(),
+            OutputFormat::Json => {
+                serde_json::to_string_pretty(self).expect("effort report serializes to JSON") + "\n"
+            }
+        }
+    }
+
+    fn table(&self, heading: impl Fn(&EffortRow) -> String, duration: fn(i64) -> String) -> Table {
+        let cells = |estimate: Option<i64>, actual: i64| {
+            vec![
+                estimate.map(duration).unwrap_or_default(),
+                duration(actual),
+                estimate.map(|e| duration(actual - e)).unwrap_or_default(),
+                estimate
+                    .filter(|e| *e > 0)
+                    .map(|e| format!("{:.0}", actual as f64 * 100.0 / e as f64))
+                    .unwrap_or_default(),
+            ]
+        };
+
+        let mut table = Table::new(&["Heading", "Estimate", "Actual", "Difference", "%"]);
+        for row in &self.rows {
+            let mut cells = cells(row.estimate_minutes, row.actual_minutes);
+            cells.insert(0, heading(row));
+            table.rows.push(cells);
+        }
+        let mut total = cells(Some(self.total_estimate_minutes), self.total_actual_minutes);
+        total.insert(0, "Total".to_string());
+        table.total = Some(total);
+        table
+    }
+}
+
+/// The heading's title, indented under its parent the way Org's clock
+/// tables do.
+fn indented_title(row: &EffortRow) -> String {
+    let title = row.outline_path.last().cloned().unwrap_or_default();
+    match row.level() {
+        0 | 1 => title,
+        level => format!("{}\\_ {}", "  ".repeat(level - 2), title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        let units = DurationUnits::default();
+        assert_eq!(units.parse("1:30").unwrap(), 90);
+        assert_eq!(units.parse("30min").unwrap(), 30);
+        assert_eq!(units.parse("2d").unwrap(), 2 * 24 * 60);
+        assert_eq!(units.parse("1.5h").unwrap(), 90);
+        assert_eq!(units.parse("1d 2h").unwrap(), 26 * 60);
+        assert_eq!(units.parse("45").unwrap(), 45);
+        assert!(units.parse("2 fortnights").is_err());
+        assert!(units.parse("").is_err());
+
+        let units = DurationUnits(BTreeMap::from([("d".to_string(), 8 * 60)]));
+        assert_eq!(units.parse("2d 1:00").unwrap(), 17 * 60);
+    }
+
+    #[test]
+    fn test_effort_rows_roll_up() {
+        // Given
+        let input = "* Project
+:PROPERTIES:
+:EFFORT: 1d
+:END:
+** Design
+:PROPERTIES:
+:EFFORT: 2h
+:END:
+CLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 12:00] =>  3:00
+** Build
+:PROPERTIES:
+:EFFORT: 1:30
+:END:
+*** Notes
+* Errands
+CLOCK: [2026-10-17 Sat 13:00]--[2026-10-17 Sat 13:20] =>  0:20
+* Someday
+";
+
+        // When
+        let rows = effort_rows(&Config::default(), input, "plan.org").unwrap();
+
+        // Then
+        let summary = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.outline_path.join(" / "),
+                    row.estimate_minutes,
+                    row.actual_minutes,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("Project".to_string(), Some(210), 180),
+                ("Project / Design".to_string(), Some(120), 180),
+                ("Project / Build".to_string(), Some(90), 0),
+                ("Errands".to_string(), None, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_effort_report() {
+        let input = "* Project\n** Design\n:PROPERTIES:\n:EFFORT: 2h\n:END:\nCLOCK: [2026-10-17 Sat 09:00]--[2026-10-17 Sat 12:00] =>  3:00\n";
+        let rows = effort_rows(&Config::default(), input, "plan.org").unwrap();
+        let report = EffortReport::new(rows, None);
+        assert_eq!(
+            report.render(OutputFormat::Table),
+            "| Heading   | Estimate | Actual | Difference |   % |
+|-----------+----------+--------+------------+-----|
+| Project   |     2:00 |   3:00 |       1:00 | 150 |
+| \\_ Design |     2:00 |   3:00 |       1:00 | 150 |
+|-----------+----------+--------+------------+-----|
+| Total     |     2:00 |   3:00 |       1:00 | 150 |
+"
+        );
+        assert!(EffortReport::new(report.rows, Some(1))
+            .render(OutputFormat::Csv)
+            .starts_with("Heading,Estimate,Actual,Difference,%\nProject,120,180,60,150\n"));
+    }
+}
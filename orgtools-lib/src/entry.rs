@@ -0,0 +1,549 @@
+//! Editing the entry of a section: its headline, planning line and drawers.
+//!
+//! An entry is the text of a section up to its first subsection. Edits are
+//! made in place so that any text which isn't changed keeps its formatting.
+
+use std::fmt;
+
+use crate::config::Config;
+use crate::outline::headline_level;
+use crate::timestamp::Timestamp;
+
+/// The parts of a headline line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Headline {
+    pub level: usize,
+    pub keyword: Option<String>,
+    pub priority: Option<char>,
+    pub title: String,
+    pub tags: Vec<String>,
+}
+
+impl Headline {
+    /// Parse a headline line, recognising the keywords from `config`.
+    pub fn parse(line: &str, config: &Config) -> Option<Headline> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let level = headline_level(line)?;
+        let mut rest = line[level..].trim();
+
+        let mut keyword = None;
+        let first_word = rest.split_whitespace().next().unwrap_or("");
+        if config.is_keyword(first_word) {
+            keyword = Some(first_word.to_string());
+            rest = rest[first_word.len()..].trim_start();
+        }
+
+        let mut priority = None;
+        if let Some(cookie) = rest.strip_prefix("[#") {
+            let mut chars = cookie.chars();
+            if let (Some(c), Some(']')) = (chars.next(), chars.next()) {
+                priority = Some(c);
+                rest = chars.as_str().trim_start();
+            }
+        }
+
+        let mut tags = vec![];
+        if let Some(last) = rest.split_whitespace().last() {
+            if let Some(parsed) = parse_tags(last) {
+                // A headline made of nothing but tags has an empty title.
+                tags = parsed;
+                rest = rest[..rest.len() - last.len()].trim_end();
+            }
+        }
+
+        Some(Headline {
+            level,
+            keyword,
+            priority,
+            title: rest.to_string(),
+            tags,
+        })
+    }
+}
+
+impl fmt::Display for Headline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", "*".repeat(self.level))?;
+        if let Some(keyword) = &self.keyword {
+            write!(f, " {}", keyword)?;
+        }
+        if let Some(priority) = self.priority {
+            write!(f, " [#{}]", priority)?;
+        }
+        if !self.title.is_empty() {
+            write!(f, " {}", self.title)?;
+        }
+        if !self.tags.is_empty() {
+            write!(f, " :{}:", self.tags.join(":"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a tag list such as `:work:urgent:`.
+fn parse_tags(text: &str) -> Option<Vec<String>> {
+    let inner = text.strip_prefix(':')?.strip_suffix(':')?;
+    let tags = inner.split(':').map(str::to_string).collect::<Vec<_>>();
+    let valid = tags.iter().all(|tag| {
+        !tag.is_empty()
+            && tag
+                .chars()
+                .all(|c| c.is_alphanumeric() || "_@#%".contains(c))
+    });
+    valid.then_some(tags)
+}
+
+/// The timestamps on the planning line below a headline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Planning {
+    pub closed: Option<Timestamp>,
+    pub deadline: Option<Timestamp>,
+    pub scheduled: Option<Timestamp>,
+}
+
+impl Planning {
+    /// Parse a planning line, returning `None` if the line isn't one.
+    pub fn parse(line: &str) -> Option<Planning> {
+        let mut planning = Planning::default();
+        let mut rest = line.trim();
+        if rest.is_empty() {
+            return None;
+        }
+        while !rest.is_empty() {
+            let (name, after) = rest.split_once(':')?;
+            let (timestamp, consumed) = Timestamp::parse_prefix(after.trim_start()).ok()?;
+            let slot = match name.trim() {
+                "CLOSED" => &mut planning.closed,
+                "DEADLINE" => &mut planning.deadline,
+                "SCHEDULED" => &mut planning.scheduled,
+                _ => return None,
+            };
+            *slot = Some(timestamp);
+            rest = after.trim_start()[consumed..].trim_start();
+        }
+        Some(planning)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.closed.is_none() && self.deadline.is_none() && self.scheduled.is_none()
+    }
+}
+
+impl fmt::Display for Planning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            ("CLOSED", &self.closed),
+            ("DEADLINE", &self.deadline),
+            ("SCHEDULED", &self.scheduled),
+        ]
+        .into_iter()
+        .filter_map(|(name, timestamp)| {
+            timestamp
+                .as_ref()
+                .map(|timestamp| format!("{}: {}", name, timestamp))
+        })
+        .collect::<Vec<_>>();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// A drawer such as `:PROPERTIES:` or `:LOGBOOK:`, kept as its raw lines.
+#[derive(Debug, Clone)]
+struct Drawer {
+    indent: String,
+    /// The name and `END` as written, which Org matches ignoring case.
+    name: String,
+    end: String,
+    lines: Vec<String>,
+}
+
+impl Drawer {
+    fn new(name: &str) -> Drawer {
+        Drawer {
+            indent: String::new(),
+            name: name.to_string(),
+            end: "END".to_string(),
+            lines: vec![],
+        }
+    }
+
+    fn render(&self, output: &mut String) {
+        output.push_str(&format!("{}:{}:\n", self.indent, self.name));
+        for line in &self.lines {
+            output.push_str(line);
+            output.push('\n');
+        }
+        output.push_str(&format!("{}:{}:\n", self.indent, self.end));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    headline: Headline,
+    headline_line: String,
+    planning: Planning,
+    planning_line: Option<String>,
+    properties: Option<Drawer>,
+    logbook: Option<Drawer>,
+    body: String,
+    trailing_newline: bool,
+}
+
+impl Entry {
+    /// Parse the text of an entry, which must start with a headline.
+    pub fn parse(text: &str, config: &Config) -> Option<Entry> {
+        let mut lines = text.split_inclusive('\n').peekable();
+        let headline_line = lines.next()?.trim_end_matches(['\n', '\r']).to_string();
+        let headline = Headline::parse(&headline_line, config)?;
+
+        let mut planning = Planning::default();
+        let mut planning_line = None;
+        if let Some(parsed) = lines.peek().and_then(|line| Planning::parse(line)) {
+            planning = parsed;
+            planning_line = lines.next().map(|line| line.trim_end().to_string());
+        }
+
+        let mut properties = None;
+        let mut logbook = None;
+        while let Some(name) = lines.peek().and_then(|line| drawer_name(line)) {
+            let slot = match name.to_uppercase().as_str() {
+                "PROPERTIES" if properties.is_none() && logbook.is_none() => &mut properties,
+                "LOGBOOK" if logbook.is_none() => &mut logbook,
+                _ => break,
+            };
+            let mut drawer_lines = lines.clone();
+            let opening = drawer_lines.next().unwrap_or_default();
+            let mut content = vec![];
+            let mut end = None;
+            for line in drawer_lines.by_ref() {
+                if line.trim().eq_ignore_ascii_case(":END:") {
+                    end = Some(line.trim()[1..4].to_string());
+                    break;
+                }
+                content.push(line.trim_end_matches(['\n', '\r']).to_string());
+            }
+            let Some(end) = end else {
+                break;
+            };
+            *slot = Some(Drawer {
+                indent: opening[..opening.len() - opening.trim_start().len()].to_string(),
+                name: name.to_string(),
+                end,
+                lines: content,
+            });
+            lines = drawer_lines;
+        }
+
+        Some(Entry {
+            headline,
+            headline_line,
+            planning,
+            planning_line,
+            properties,
+            logbook,
+            body: lines.collect(),
+            trailing_newline: text.ends_with('\n'),
+        })
+    }
+
+    pub fn headline(&self) -> &Headline {
+        &self.headline
+    }
+
+    pub fn keyword(&self) -> Option<&str> {
+        self.headline.keyword.as_deref()
+    }
+
+    /// Replace the TODO keyword, leaving the rest of the headline untouched.
+    pub fn set_keyword(&mut self, keyword: Option<&str>) {
+        let level = self.headline.level;
+        let mut rest = self.headline_line[level..].trim_start();
+        if let Some(old) = &self.headline.keyword {
+            rest = rest[old.len()..].trim_start();
+        }
+        let mut line = "*".repeat(level);
+        if let Some(keyword) = keyword {
+            line.push(' ');
+            line.push_str(keyword);
+        }
+        if !rest.is_empty() {
+            line.push(' ');
+            line.push_str(rest);
+        }
+        self.headline_line = line;
+        self.headline.keyword = keyword.map(str::to_string);
+    }
+
+    pub fn planning(&self) -> &Planning {
+        &self.planning
+    }
+
+    pub fn set_planning(&mut self, planning: Planning) {
+        if planning != self.planning {
+            let indent = self
+                .planning_line
+                .as_deref()
+                .map(|line| &line[..line.len() - line.trim_start().len()])
+                .unwrap_or("");
+            self.planning_line = (!planning.is_empty()).then(|| format!("{}{}", indent, planning));
+            self.planning = planning;
+        }
+    }
+
+    /// The properties in the property drawer, in order.
+    pub fn properties(&self) -> Vec<(String, String)> {
+        self.properties
+            .iter()
+            .flat_map(|drawer| drawer.lines.iter())
+            .filter_map(|line| parse_property(line))
+            .collect()
+    }
+
+    /// Look up a property, ignoring case.
+    pub fn property(&self, name: &str) -> Option<String> {
+        self.properties()
+            .into_iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Set a property, adding the property drawer if needed.
+    pub fn set_property(&mut self, name: &str, value: &str) {
+        let drawer = self
+            .properties
+            .get_or_insert_with(|| Drawer::new("PROPERTIES"));
+        let existing = drawer.lines.iter().position(|line| {
+            parse_property(line).is_some_and(|(key, _)| key.eq_ignore_ascii_case(name))
+        });
+        match existing {
+            Some(index) => {
+                let line = &drawer.lines[index];
+                let indent = &line[..line.len() - line.trim_start().len()];
+                drawer.lines[index] = format!("{}:{}: {}", indent, name, value);
+            }
+            None => drawer
+                .lines
+                .push(format!("{}:{}: {}", drawer.indent, name, value)),
+        }
+    }
+
+    /// Remove a property, ignoring case.
+    pub fn remove_property(&mut self, name: &str) {
+        if let Some(drawer) = &mut self.properties {
+            drawer.lines.retain(|line| {
+                !parse_property(line).is_some_and(|(key, _)| key.eq_ignore_ascii_case(name))
+            });
+        }
+    }
+
+    /// The lines of the `:LOGBOOK:` drawer.
+    pub fn logbook(&self) -> &[String] {
+        self.logbook
+            .as_ref()
+            .map(|drawer| drawer.lines.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn logbook_mut(&mut self) -> &mut Vec<String> {
+        &mut self
+            .logbook
+            .get_or_insert_with(|| Drawer::new("LOGBOOK"))
+            .lines
+    }
+
+    /// Add a line to the top of the `:LOGBOOK:` drawer, adding the drawer if needed.
+    pub fn add_logbook_item(&mut self, item: &str) {
+        self.logbook_mut().insert(0, item.to_string());
+    }
+
+    /// The text after the headline, planning line and drawers.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = String::new();
+        output.push_str(&self.headline_line);
+        output.push('\n');
+        if let Some(planning_line) = &self.planning_line {
+            output.push_str(planning_line);
+            output.push('\n');
+        }
+        if let Some(properties) = &self.properties {
+            properties.render(&mut output);
+        }
+        if let Some(logbook) = &self.logbook {
+            logbook.render(&mut output);
+        }
+        output.push_str(&self.body);
+        if !self.trailing_newline && output.ends_with('\n') {
+            output.pop();
+        }
+        write!(f, "{}", output)
+    }
+}
+
+/// The name of the drawer a line opens, e.g. `PROPERTIES` for `:PROPERTIES:`,
+/// as written.
+pub(crate) fn drawer_name(line: &str) -> Option<&str> {
+    let name = line.trim().strip_prefix(':')?.strip_suffix(':')?;
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    (valid && !name.eq_ignore_ascii_case("END")).then_some(name)
+}
+
+/// Parse a property drawer line such as `:CREATED: [2026-10-17 Sat]`.
+pub(crate) fn parse_property(line: &str) -> Option<(String, String)> {
+    let rest = line.trim().strip_prefix(':')?;
+    let (name, value) = rest.split_once(':')?;
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name.to_string(), value.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn parse(text: &str) -> Entry {
+        Entry::parse(text, &Config::default()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_headline() {
+        let headline = Headline::parse(
+            "** TODO [#A] Write report :work:urgent:",
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            headline,
+            Headline {
+                level: 2,
+                keyword: Some("TODO".to_string()),
+                priority: Some('A'),
+                title: "Write report".to_string(),
+                tags: vec!["work".to_string(), "urgent".to_string()],
+            }
+        );
+        assert_eq!(
+            headline.to_string(),
+            "** TODO [#A] Write report :work:urgent:"
+        );
+    }
+
+    #[test]
+    fn test_parse_headline_without_keyword() {
+        let headline = Headline::parse("* TODOS for: the week", &Config::default()).unwrap();
+        assert_eq!(headline.keyword, None);
+        assert_eq!(headline.title, "TODOS for: the week");
+        assert_eq!(headline.tags, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_parse_planning() {
+        let planning =
+            Planning::parse("CLOSED: [2026-10-17 Sat 10:00] SCHEDULED: <2026-10-17 Sat +1w>")
+                .unwrap();
+        assert!(planning.closed.is_some());
+        assert!(planning.scheduled.unwrap().repeater.is_some());
+        assert!(planning.deadline.is_none());
+        assert!(Planning::parse("Some text: here").is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "* TODO  Task   :tag:\n  SCHEDULED: <2026-10-17 Sat>\n  :PROPERTIES:\n  :ID:   abc\n  :END:\n:LOGBOOK:\nCLOCK: [2026-10-17 Sat 09:00]\n:END:\nBody\n\n";
+        assert_eq!(parse(text).to_string(), text);
+        assert_eq!(parse("* Task").to_string(), "* Task");
+    }
+
+    #[test]
+    fn test_round_trip_keeps_drawer_case() {
+        let text = "* Task\n:properties:\n:ID: abc\n:end:\n:Logbook:\n- Note\n:End:\n";
+        let mut entry = parse(text);
+        assert_eq!(entry.to_string(), text);
+        entry.add_logbook_item("- Newer note");
+        assert_eq!(
+            entry.to_string(),
+            "* Task\n:properties:\n:ID: abc\n:end:\n:Logbook:\n- Newer note\n- Note\n:End:\n"
+        );
+    }
+
+    #[test]
+    fn test_set_keyword() {
+        let mut entry = parse("* TODO [#B] Task  :tag:\n");
+        entry.set_keyword(Some("DONE"));
+        assert_eq!(entry.to_string(), "* DONE [#B] Task  :tag:\n");
+        entry.set_keyword(None);
+        assert_eq!(entry.to_string(), "* [#B] Task  :tag:\n");
+        entry.set_keyword(Some("TODO"));
+        assert_eq!(entry.to_string(), "* TODO [#B] Task  :tag:\n");
+    }
+
+    #[test]
+    fn test_set_planning() {
+        let mut entry = parse("* TODO Task\nBody\n");
+        let mut planning = entry.planning().clone();
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        planning.closed = Some(Timestamp::new(false, date, None));
+        entry.set_planning(planning);
+        assert_eq!(
+            entry.to_string(),
+            "* TODO Task\nCLOSED: [2026-10-17 Sat]\nBody\n"
+        );
+        entry.set_planning(Planning::default());
+        assert_eq!(entry.to_string(), "* TODO Task\nBody\n");
+    }
+
+    #[test]
+    fn test_properties() {
+        let mut entry = parse("* Task\n:PROPERTIES:\n:ID: abc\n:END:\n");
+        assert_eq!(entry.property("id"), Some("abc".to_string()));
+        entry.set_property("ID", "def");
+        entry.set_property("STYLE", "habit");
+        assert_eq!(
+            entry.to_string(),
+            "* Task\n:PROPERTIES:\n:ID: def\n:STYLE: habit\n:END:\n"
+        );
+        entry.remove_property("style");
+        assert_eq!(
+            entry.properties(),
+            vec![("ID".to_string(), "def".to_string())]
+        );
+
+        let mut entry = parse("* Task\nSCHEDULED: <2026-10-17 Sat>\nBody");
+        entry.set_property("ID", "abc");
+        assert_eq!(
+            entry.to_string(),
+            "* Task\nSCHEDULED: <2026-10-17 Sat>\n:PROPERTIES:\n:ID: abc\n:END:\nBody"
+        );
+    }
+
+    #[test]
+    fn test_add_logbook_item() {
+        let mut entry = parse("* Task\n:PROPERTIES:\n:ID: abc\n:END:\nBody\n");
+        entry.add_logbook_item("- Note");
+        entry.add_logbook_item("- Newer note");
+        assert_eq!(
+            entry.to_string(),
+            "* Task\n:PROPERTIES:\n:ID: abc\n:END:\n:LOGBOOK:\n- Newer note\n- Note\n:END:\nBody\n"
+        );
+        assert_eq!(entry.logbook().len(), 2);
+    }
+
+    #[test]
+    fn test_unclosed_drawer_is_body() {
+        let text = "* Task\n:LOGBOOK:\nno end\n";
+        let entry = parse(text);
+        assert!(entry.logbook().is_empty());
+        assert_eq!(entry.body(), ":LOGBOOK:\nno end\n");
+        assert_eq!(entry.to_string(), text);
+    }
+}
@@ -0,0 +1,494 @@
+//! Lead time and time in state of tasks, from `:CREATED:`, `CLOSED:` and the
+//! state changes in `:LOGBOOK:` drawers.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::matcher::Matcher;
+use crate::org::Org;
+use crate::report::{OutputFormat, Table};
+use crate::state::StateChange;
+use crate::stats::{count_by_period, render_counts, Period, PeriodCount};
+use crate::timestamp::Timestamp;
+
+/// A time spent in one TODO state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stay {
+    pub state: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+/// The history of a task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskFlow {
+    pub file: String,
+    pub outline_path: Vec<String>,
+    /// The task's tags, including inherited ones.
+    pub tags: Vec<String>,
+    pub created: Option<NaiveDateTime>,
+    pub closed: Option<NaiveDateTime>,
+    /// The finished stays in unfinished states, oldest first.
+    pub stays: Vec<Stay>,
+    /// When an open task entered its current state, if known.
+    pub open_since: Option<NaiveDateTime>,
+}
+
+impl TaskFlow {
+    /// The time from creation to closing, in minutes.
+    pub fn lead_time(&self) -> Option<i64> {
+        Some((self.closed? - self.created?).num_minutes())
+    }
+}
+
+/// The history of every task in a file matching `matcher`, if given.
+pub fn task_flows(
+    config: &Config,
+    input: &str,
+    file: &str,
+    matcher: Option<&Matcher>,
+) -> Vec<TaskFlow> {
+    let org = Org::from_config(config.clone()).load(input);
+    let mut flows = vec![];
+    for item in org.outline() {
+        let entry = item.section.entry();
+        let Some(keyword) = entry.keyword().filter(|keyword| config.is_keyword(keyword)) else {
+            continue;
+        };
+        if matcher.is_some_and(|matcher| !matcher.matches(&entry, &item.tags, item.level())) {
+            continue;
+        }
+
+        let created = entry
+            .property("CREATED")
+            .and_then(|created| Timestamp::parse_prefix(&created).ok())
+            .map(|(created, _)| created.datetime());
+        let mut changes = entry
+            .logbook()
+            .iter()
+            .filter_map(|line| StateChange::parse(line))
+            .collect::<Vec<_>>();
+        changes.sort_by_key(|change| change.time);
+
+        let mut stays = vec![];
+        let mut state = changes
+            .first()
+            .map_or(Some(keyword.to_string()), |change| change.from.clone());
+        let mut since = created;
+        for change in &changes {
+            if let (Some(state), Some(start)) = (&state, since) {
+                if config.is_unfinished(state) && start <= change.time {
+                    stays.push(Stay {
+                        state: state.clone(),
+                        start,
+                        end: change.time,
+                    });
+                }
+            }
+            state = change.to.clone();
+            since = Some(change.time);
+        }
+
+        flows.push(TaskFlow {
+            file: file.to_string(),
+            outline_path: item.outline_path.clone(),
+            tags: item.tags.clone(),
+            created,
+            closed: entry
+                .planning()
+                .closed
+                .as_ref()
+                .map(|closed| closed.datetime()),
+            stays,
+            open_since: since.filter(|_| config.is_unfinished(keyword)),
+        });
+    }
+    flows
+}
+
+/// How the lead time and open work of tasks are grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowGroup {
+    /// Under each of the task's tags, or `(none)`.
+    Tag,
+    File,
+    /// By the period the task was closed in, or created in if it's open.
+    Period(Period),
+}
+
+impl FromStr for FlowGroup {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "tag" => Ok(FlowGroup::Tag),
+            "file" => Ok(FlowGroup::File),
+            period => period.parse().map(FlowGroup::Period).map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid grouping, expected tag, file, day, week, month or year: {}",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+impl FlowGroup {
+    fn name(&self) -> &'static str {
+        match self {
+            FlowGroup::Tag => "Tag",
+            FlowGroup::File => "File",
+            FlowGroup::Period(_) => "Period",
+        }
+    }
+
+    fn keys(&self, flow: &TaskFlow) -> Vec<String> {
+        match self {
+            FlowGroup::Tag if flow.tags.is_empty() => vec!["(none)".to_string()],
+            FlowGroup::Tag => flow.tags.clone(),
+            FlowGroup::File => vec![flow.file.clone()],
+            FlowGroup::Period(period) => flow
+                .closed
+                .or(flow.created)
+                .map(|time| period.start(time.date()).to_string())
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowOptions {
+    pub by: FlowGroup,
+    /// The period throughput is counted in.
+    pub period: Period,
+    /// Only include tasks closed, and stays ended, on or after this date.
+    pub from: Option<NaiveDate>,
+    /// Only include tasks closed, and stays ended, on or before this date.
+    pub to: Option<NaiveDate>,
+    /// Open tasks that have been in their state for longer are stale.
+    pub stale_days: i64,
+    pub now: NaiveDateTime,
+}
+
+/// Percentiles of a set of durations, in days.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Percentiles {
+    pub count: usize,
+    pub p50: f64,
+    pub p85: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl Percentiles {
+    /// Nearest-rank percentiles of durations in minutes.
+    fn of(mut minutes: Vec<i64>) -> Option<Percentiles> {
+        if minutes.is_empty() {
+            return None;
+        }
+        minutes.sort();
+        let days = |minutes: i64| minutes as f64 / (24.0 * 60.0);
+        let rank = |p: f64| {
+            let index = (p / 100.0 * minutes.len() as f64).ceil() as usize;
+            days(minutes[index.clamp(1, minutes.len()) - 1])
+        };
+        Some(Percentiles {
+            count: minutes.len(),
+            p50: rank(50.0),
+            p85: rank(85.0),
+            p95: rank(95.0),
+            max: days(minutes[minutes.len() - 1]),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupPercentiles {
+    pub group: String,
+    #[serde(flatten)]
+    pub percentiles: Percentiles,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenWork {
+    pub group: String,
+    pub open: usize,
+    /// Open tasks in their state for longer than the stale limit.
+    pub stale: usize,
+    /// The median days open tasks have been in their state.
+    pub median_age: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlowReport {
+    pub lead_time: Vec<GroupPercentiles>,
+    pub time_in_state: Vec<GroupPercentiles>,
+    pub throughput: Vec<PeriodCount>,
+    /// Groups with open work, the stalest first.
+    pub open_work: Vec<OpenWork>,
+    #[serde(skip)]
+    group_name: &'static str,
+}
+
+pub fn flow_report(flows: &[TaskFlow], options: &FlowOptions) -> FlowReport {
+    let in_range = |time: NaiveDateTime| {
+        options.from.is_none_or(|from| time.date() >= from)
+            && options.to.is_none_or(|to| time.date() <= to)
+    };
+    let percentiles = |groups: BTreeMap<String, Vec<i64>>| {
+        groups
+            .into_iter()
+            .filter_map(|(group, minutes)| {
+                Some(GroupPercentiles {
+                    group,
+                    percentiles: Percentiles::of(minutes)?,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut lead_times: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    let mut stays: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    let mut ages: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    let mut closed = vec![];
+    for flow in flows {
+        if let Some(time) = flow.closed.filter(|time| in_range(*time)) {
+            closed.push(time.date());
+            if let Some(lead_time) = flow.lead_time() {
+                for key in options.by.keys(flow) {
+                    lead_times.entry(key).or_default().push(lead_time);
+                }
+            }
+        }
+        for stay in flow.stays.iter().filter(|stay| in_range(stay.end)) {
+            stays
+                .entry(stay.state.clone())
+                .or_default()
+                .push((stay.end - stay.start).num_minutes());
+        }
+        if let Some(since) = flow.open_since {
+            for key in options.by.keys(flow) {
+                ages.entry(key)
+                    .or_default()
+                    .push((options.now - since).num_minutes());
+            }
+        }
+    }
+
+    let stale_minutes = options.stale_days * 24 * 60;
+    let mut open_work = ages
+        .into_iter()
+        .filter_map(|(group, minutes)| {
+            Some(OpenWork {
+                group,
+                open: minutes.len(),
+                stale: minutes.iter().filter(|age| **age > stale_minutes).count(),
+                median_age: Percentiles::of(minutes)?.p50,
+            })
+        })
+        .collect::<Vec<_>>();
+    open_work.sort_by(|a, b| {
+        (b.stale as f64 / b.open as f64).total_cmp(&(a.stale as f64 / a.open as f64))
+    });
+
+    FlowReport {
+        lead_time: percentiles(lead_times),
+        time_in_state: percentiles(stays),
+        throughput: count_by_period(&closed, options.period, options.from, options.to),
+        open_work,
+        group_name: options.by.name(),
+    }
+}
+
+impl FlowReport {
+    pub fn render(&self, format: OutputFormat) -> String {
+        let lead_time = percentile_table(self.group_name, &self.lead_time);
+        let time_in_state = percentile_table("State", &self.time_in_state);
+        let mut open_work = Table::new(&[self.group_name, "Open", "Stale", "Median age"]);
+        for work in &self.open_work {
+            open_work.rows.push(vec![
+                work.group.clone(),
+                work.open.to_string(),
+                work.stale.to_string(),
+                format!("{:.1}", work.median_age),
+            ]);
+        }
+
+        match format {
+            OutputFormat::Table => format!(
+                "Lead time in days\n{}\nTime in state in days\n{}\nThroughput\n{}\nOpen work\n{}",
+                lead_time.to_org(),
+                time_in_state.to_org(),
+                render_counts(&self.throughput, format),
+                open_work.to_org()
+            ),
+            OutputFormat::Csv => [
+                lead_time.to_csv(),
+                time_in_state.to_csv(),
+                render_counts(&self.throughput, format),
+                open_work.to_csv(),
+            ]
+            .join("\n"),
+            OutputFormat::Json => {
+                serde_json::to_string_pretty(self).expect("flow report serializes to JSON") + "\n"
+            }
+        }
+    }
+}
+
+fn percentile_table(group_name: &str, rows: &[GroupPercentiles]) -> Table {
+    let mut table = Table::new(&[group_name, "Tasks", "p50", "p85", "p95", "Max"]);
+    for row in rows {
+        let p = &row.percentiles;
+        table.rows.push(vec![
+            row.group.clone(),
+            p.count.to_string(),
+            format!("{:.1}", p.p50),
+            format!("{:.1}", p.p85),
+            format!("{:.1}", p.p95),
+            format!("{:.1}", p.max),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> NaiveDateTime {
+        date.parse::<NaiveDate>()
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+
+    const INPUT: &str = "* DONE Report :work:
+CLOSED: [2026-10-11 Sun 09:00]
+:PROPERTIES:
+:CREATED: [2026-10-01 Thu 09:00]
+:END:
+:LOGBOOK:
+- State \"DONE\" from \"DOING\" [2026-10-11 Sun 09:00]
+- State \"DOING\" from \"TODO\" [2026-10-03 Sat 09:00]
+:END:
+* BLOCKED Taxes :home:
+:PROPERTIES:
+:CREATED: [2026-08-01 Sat 09:00]
+:END:
+:LOGBOOK:
+- State \"BLOCKED\" from \"TODO\" [2026-08-18 Tue 09:00]
+:END:
+* Notes
+";
+
+    #[test]
+    fn test_task_flows() {
+        let flows = task_flows(&Config::default(), INPUT, "tasks.org", None);
+        assert_eq!(flows.len(), 2);
+        assert_eq!(flows[0].lead_time(), Some(10 * 24 * 60));
+        assert_eq!(
+            flows[0].stays,
+            vec![
+                Stay {
+                    state: "TODO".to_string(),
+                    start: at("2026-10-01"),
+                    end: at("2026-10-03"),
+                },
+                Stay {
+                    state: "DOING".to_string(),
+                    start: at("2026-10-03"),
+                    end: at("2026-10-11"),
+                },
+            ]
+        );
+        assert_eq!(flows[0].open_since, None);
+        assert_eq!(flows[1].open_since, Some(at("2026-08-18")));
+
+        let matcher = "home".parse().unwrap();
+        assert_eq!(
+            task_flows(&Config::default(), INPUT, "tasks.org", Some(&matcher)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_flow_report() {
+        // Given
+        let flows = task_flows(&Config::default(), INPUT, "tasks.org", None);
+        let options = FlowOptions {
+            by: FlowGroup::Tag,
+            period: Period::Week,
+            from: None,
+            to: None,
+            stale_days: 30,
+            now: at("2026-10-18"),
+        };
+
+        // When
+        let report = flow_report(&flows, &options);
+
+        // Then
+        assert_eq!(report.lead_time.len(), 1);
+        assert_eq!(report.lead_time[0].group, "work");
+        assert_eq!(report.lead_time[0].percentiles.p50, 10.0);
+        let states = report
+            .time_in_state
+            .iter()
+            .map(|row| (row.group.as_str(), row.percentiles.max))
+            .collect::<Vec<_>>();
+        assert_eq!(states, vec![("DOING", 8.0), ("TODO", 17.0)]);
+        assert_eq!(
+            report.open_work,
+            vec![OpenWork {
+                group: "home".to_string(),
+                open: 1,
+                stale: 1,
+                median_age: 61.0,
+            }]
+        );
+        assert_eq!(
+            report.render(OutputFormat::Table),
+            "Lead time in days
+| Tag  | Tasks |  p50 |  p85 |  p95 |  Max |
+|------+-------+------+------+------+------|
+| work |     1 | 10.0 | 10.0 | 10.0 | 10.0 |
+
+Time in state in days
+| State | Tasks | p50 |  p85 |  p95 |  Max |
+|-------+-------+-----+------+------+------|
+| DOING |     1 | 8.0 |  8.0 |  8.0 |  8.0 |
+| TODO  |     2 | 2.0 | 17.0 | 17.0 | 17.0 |
+
+Throughput
+2026-10-05    1  =
+
+Open work
+| Tag  | Open | Stale | Median age |
+|------+------+-------+------------|
+| home |    1 |     1 |       61.0 |
+"
+        );
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let percentiles = Percentiles::of((1..=20).map(|day| day * 24 * 60).collect()).unwrap();
+        assert_eq!(
+            (
+                percentiles.p50,
+                percentiles.p85,
+                percentiles.p95,
+                percentiles.max
+            ),
+            (10.0, 17.0, 19.0, 20.0)
+        );
+        assert_eq!(Percentiles::of(vec![]), None);
+    }
+}
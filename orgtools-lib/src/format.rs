@@ -0,0 +1,323 @@
+//! Formatting Org text into a canonical layout.
+//!
+//! Lines inside blocks such as `#+BEGIN_SRC` only have their trailing
+//! whitespace removed.
+
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::document::verbatim_len;
+use crate::entry::{parse_property, Headline, Planning};
+use crate::outline::{headline_level, inside_blocks};
+use crate::timestamp::{is_day_name, Timestamp};
+
+/// Formatting options, configured as the `[format]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    /// The column tags are aligned to. A negative column aligns the end of
+    /// the tags, a positive one aligns their start, as in Emacs.
+    pub tags_column: i32,
+    /// The indentation of lines in property drawers.
+    pub property_indent: usize,
+    /// The number of blank lines before every headline after the first line,
+    /// or `None` to leave them up to `max_blank_lines`.
+    pub blank_lines_before_headlines: Option<usize>,
+    /// The longest run of blank lines kept outside blocks.
+    pub max_blank_lines: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            tags_column: -77,
+            property_indent: 0,
+            blank_lines_before_headlines: None,
+            max_blank_lines: 1,
+        }
+    }
+}
+
+/// Format Org text according to `config.format`.
+pub fn format(config: &Config, input: &str) -> String {
+    let options = &config.format;
+    let mut lines: Vec<String> = vec![];
+    let mut after_headline = false;
+    let mut in_properties = false;
+
+    let input_lines = input.lines().collect::<Vec<_>>();
+    for (line, inside) in input_lines.iter().zip(inside_blocks(&input_lines)) {
+        let line = line.trim_end();
+
+        if inside {
+            after_headline = false;
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let line = canonical_timestamps(line);
+        if headline_level(&line).is_some() {
+            let blank_lines = lines.iter().rev().take_while(|l| l.is_empty()).count();
+            if let Some(wanted) = options.blank_lines_before_headlines {
+                lines.truncate(lines.len() - blank_lines);
+                if !lines.is_empty() {
+                    lines.extend(std::iter::repeat_n(String::new(), wanted));
+                }
+            }
+            lines.push(format_headline(&line, config));
+            after_headline = true;
+            in_properties = false;
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if after_headline {
+            if let Some(planning) = Planning::parse(trimmed) {
+                lines.push(planning.to_string());
+                after_headline = false;
+                continue;
+            }
+        }
+        if trimmed.eq_ignore_ascii_case(":PROPERTIES:")
+            && (after_headline || lines_end_planning(&lines))
+        {
+            in_properties = true;
+            after_headline = false;
+            lines.push(format!(
+                "{}:PROPERTIES:",
+                " ".repeat(options.property_indent)
+            ));
+            continue;
+        }
+        after_headline = false;
+        if in_properties {
+            let indent = " ".repeat(options.property_indent);
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                in_properties = false;
+                lines.push(format!("{}:END:", indent));
+            } else if let Some((name, value)) = parse_property(trimmed) {
+                lines.push(
+                    format!("{}:{}: {}", indent, name, value)
+                        .trim_end()
+                        .to_string(),
+                );
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+
+        if line.is_empty() {
+            let blank_lines = lines.iter().rev().take_while(|l| l.is_empty()).count();
+            if blank_lines >= options.max_blank_lines {
+                continue;
+            }
+        }
+        lines.push(line);
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let mut output = lines.join("\n");
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+/// Whether the last line is a planning line directly under a headline.
+fn lines_end_planning(lines: &[String]) -> bool {
+    match lines {
+        [.., headline, planning] => {
+            headline_level(headline).is_some() && Planning::parse(planning).is_some()
+        }
+        _ => false,
+    }
+}
+
+/// Render a headline with single spaces between its parts and aligned tags.
+fn format_headline(line: &str, config: &Config) -> String {
+    let Some(mut headline) = Headline::parse(line, config) else {
+        return line.to_string();
+    };
+    let tags = std::mem::take(&mut headline.tags);
+    let mut text = headline.to_string();
+    if tags.is_empty() {
+        return text;
+    }
+
+    let tags = format!(":{}:", tags.join(":"));
+    let width = text.chars().count();
+    let column = config.format.tags_column;
+    let padding = if column < 0 {
+        (column.unsigned_abs() as usize).saturating_sub(width + tags.chars().count())
+    } else {
+        (column as usize).saturating_sub(width)
+    };
+    text.push_str(&" ".repeat(padding.max(1)));
+    text.push_str(&tags);
+    text
+}
+
+/// Re-render every valid timestamp in a line in its canonical form.
+///
+/// Verbatim and code spans are left alone.
+fn canonical_timestamps(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut index = 0;
+    while let Some(offset) = line[index..].find(['<', '[', '=', '~']) {
+        output.push_str(&line[index..index + offset]);
+        index += offset;
+        let rest = &line[index..];
+        let previous = line[..index].chars().next_back();
+        let len = if let Some(len) = verbatim_len(rest, previous) {
+            output.push_str(&rest[..len]);
+            len
+        } else if let Some((timestamp, len)) = canonical_timestamp(rest) {
+            output.push_str(&timestamp);
+            len
+        } else {
+            output.push_str(&rest[..1]);
+            1
+        };
+        index += len;
+    }
+    output.push_str(&line[index..]);
+    output
+}
+
+/// The canonical form of the timestamp at the start of `rest` and the
+/// length of the original.
+///
+/// Timestamps that would lose or change text when re-rendered, such as
+/// ones with a wrong day name or two repeaters, are not returned.
+fn canonical_timestamp(rest: &str) -> Option<(String, usize)> {
+    if !rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let (timestamp, len) = Timestamp::parse_prefix(rest).ok()?;
+    let canonical = timestamp.to_string();
+
+    fn parts(text: &str) -> Vec<&str> {
+        text[1..text.len() - 1].split_whitespace().collect()
+    }
+    let original = parts(&rest[..len]);
+    let rendered = parts(&canonical);
+    let others = match original.get(1) {
+        Some(day) if is_day_name(day) => {
+            if !day.eq_ignore_ascii_case(rendered[1]) {
+                return None;
+            }
+            original.len() - 2
+        }
+        _ => original.len() - 1,
+    };
+    (others == rendered.len() - 2).then_some((canonical, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_with(options: FormatOptions, input: &str) -> String {
+        let config = Config {
+            format: options,
+            ..Config::default()
+        };
+        format(&config, input)
+    }
+
+    #[test]
+    fn test_format_headlines() {
+        let input = "*  TODO   [#A]  Write   report   :work:\n* Notes :a:b:\n";
+        assert_eq!(
+            format_with(FormatOptions::default(), input),
+            format!(
+                "* TODO [#A] Write   report{}:work:\n* Notes{}:a:b:\n",
+                " ".repeat(77 - 26 - 6),
+                " ".repeat(77 - 7 - 5)
+            )
+        );
+        let options = FormatOptions {
+            tags_column: 20,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format_with(options, "* A long headline here :x:\n* B :y:\n"),
+            format!("* A long headline here :x:\n* B{}:y:\n", " ".repeat(17))
+        );
+    }
+
+    #[test]
+    fn test_format_planning_properties_and_timestamps() {
+        // Given
+        let input = "* Task\n  SCHEDULED: <2026-10-17>   DEADLINE: <2026-10-20 Tue 9:00>\n    :PROPERTIES:\n  :CREATED:    [2026-10-01 Thu 10:00]  \n  :END:\nMet on [2026-10-02].   \n";
+
+        // When
+        let output = format_with(FormatOptions::default(), input);
+
+        // Then
+        assert_eq!(
+            output,
+            "* Task\nDEADLINE: <2026-10-20 Tue 09:00> SCHEDULED: <2026-10-17 Sat>\n:PROPERTIES:\n:CREATED: [2026-10-01 Thu 10:00]\n:END:\nMet on [2026-10-02 Fri].\n"
+        );
+    }
+
+    #[test]
+    fn test_format_blank_lines() {
+        let input = "#+TITLE: Notes\n\n\n\n* A\nText\n\n\n* B\n* C\n\n\n";
+        assert_eq!(
+            format_with(FormatOptions::default(), input),
+            "#+TITLE: Notes\n\n* A\nText\n\n* B\n* C\n"
+        );
+        let options = FormatOptions {
+            blank_lines_before_headlines: Some(1),
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format_with(options, input),
+            "#+TITLE: Notes\n\n* A\nText\n\n* B\n\n* C\n"
+        );
+    }
+
+    #[test]
+    fn test_format_leaves_blocks_alone() {
+        let input = "#+BEGIN_SRC org\n*  TODO  not a headline   \n<2026-10-17>\n\n\n#+END_SRC\n";
+        assert_eq!(
+            format_with(FormatOptions::default(), input),
+            "#+BEGIN_SRC org\n*  TODO  not a headline\n<2026-10-17>\n\n\n#+END_SRC\n"
+        );
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let input = "* TODO Task :work:\nSCHEDULED: <2026-10-17 Sat>\n:PROPERTIES:\n:ID: 1\n:END:\n\nBody\n";
+        let once = format_with(FormatOptions::default(), input);
+        assert_eq!(format_with(FormatOptions::default(), &once), once);
+    }
+
+    #[test]
+    fn test_format_leaves_other_brackets_alone() {
+        let input = "Notes from [2026-10-17 standup notes] and <2026-10-17 Mon>.\nRun =[2026-10-17]= or ~<2026-10-17 +1w +2d>~, not <2026-10-17 +1w +2d>.\n";
+        assert_eq!(format_with(FormatOptions::default(), input), input);
+    }
+
+    #[test]
+    fn test_format_only_skips_terminated_blocks() {
+        let input = "#+BEGIN_SRC sh\n*   Not   a   heading\n#+end_srcfoo\n#+END_SRC\n#+BEGIN_QUOTE\n*   Heading\n";
+        assert_eq!(
+            format_with(FormatOptions::default(), input),
+            "#+BEGIN_SRC sh\n*   Not   a   heading\n#+end_srcfoo\n#+END_SRC\n#+BEGIN_QUOTE\n* Heading\n"
+        );
+    }
+
+    #[test]
+    fn test_format_non_ascii_verbatim() {
+        let input = "Café =naïve [2026-10-17]= and ~très~, due <2026-10-17>.\n";
+        assert_eq!(
+            format_with(FormatOptions::default(), input),
+            "Café =naïve [2026-10-17]= and ~très~, due <2026-10-17 Sat>.\n"
+        );
+    }
+}
@@ -0,0 +1,434 @@
+//! Habits: tasks with `:STYLE: habit` and a `.+` or `++` repeater on their
+//! `SCHEDULED:` timestamp, as in Org's org-habit.
+//!
+//! A habit's history is rebuilt from the completions noted in its
+//! `:LOGBOOK:` drawer and its `:LAST_REPEAT:` property. A repeater such as
+//! `.+2d/4d` makes the habit due two days after each completion, and late
+//! after four.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::org::Org;
+use crate::report::{OutputFormat, Table};
+use crate::state::StateChange;
+use crate::timestamp::{Interval, Repeater, RepeaterKind, TimeUnit, Timestamp};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Habit {
+    pub file: String,
+    pub outline_path: Vec<String>,
+    /// The date the habit is next due.
+    pub scheduled: NaiveDate,
+    pub repeater: Repeater,
+    /// The days the habit was done, oldest first.
+    pub completions: Vec<NaiveDate>,
+}
+
+/// Where a day falls relative to when a habit is due, as coloured in the
+/// consistency graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HabitDay {
+    /// Before it is due.
+    Early,
+    /// Due, but not yet late.
+    Due,
+    /// The last day before it is late.
+    LastChance,
+    Overdue,
+}
+
+impl HabitDay {
+    fn color(&self) -> (u8, u8, u8) {
+        match self {
+            HabitDay::Early => (130, 112, 249),
+            HabitDay::Due => (77, 249, 70),
+            HabitDay::LastChance => (245, 249, 70),
+            HabitDay::Overdue => (249, 55, 45),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HabitStats {
+    pub completions: usize,
+    /// The completions in a row that weren't late, up to today.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// The completions that were late, and today if the habit is overdue.
+    pub missed: usize,
+    pub last_done: Option<NaiveDate>,
+    pub next_due: NaiveDate,
+}
+
+/// The habits in a file.
+pub fn habits(config: &Config, input: &str, file: &str) -> Vec<Habit> {
+    let org = Org::from_config(config.clone()).load(input);
+    let mut habits = vec![];
+    for item in org.outline() {
+        let entry = item.section.entry();
+        if !entry
+            .property("STYLE")
+            .is_some_and(|style| style.eq_ignore_ascii_case("habit"))
+        {
+            continue;
+        }
+        let Some(scheduled) = &entry.planning().scheduled else {
+            continue;
+        };
+        let Some(repeater) = scheduled
+            .repeater
+            .filter(|repeater| repeater.kind != RepeaterKind::Cumulate)
+        else {
+            continue;
+        };
+
+        let mut completions = entry
+            .logbook()
+            .iter()
+            .filter_map(|line| StateChange::parse(line))
+            .filter(|change| {
+                change
+                    .to
+                    .as_deref()
+                    .is_some_and(|to| config.is_finished(to))
+            })
+            .map(|change| change.time.date())
+            .chain(
+                entry
+                    .property("LAST_REPEAT")
+                    .and_then(|last| Timestamp::parse_prefix(&last).ok())
+                    .map(|(last, _)| last.date),
+            )
+            .collect::<Vec<_>>();
+        completions.sort();
+        completions.dedup();
+
+        habits.push(Habit {
+            file: file.to_string(),
+            outline_path: item.outline_path.clone(),
+            scheduled: scheduled.date,
+            repeater,
+            completions,
+        });
+    }
+    habits
+}
+
+impl Habit {
+    pub fn title(&self) -> &str {
+        self.outline_path.last().map_or("", String::as_str)
+    }
+
+    /// The date the habit was due after being done on `done`, or the
+    /// scheduled date after the last completion.
+    fn due_after(&self, done: Option<NaiveDate>) -> Result<NaiveDate> {
+        let Some(done) = done.filter(|done| Some(done) != self.completions.last()) else {
+            return Ok(self.scheduled);
+        };
+        let interval = self.repeater.interval;
+        match self.repeater.kind {
+            RepeaterKind::CatchUp => {
+                // Stay on the schedule's calendar: the first repetition
+                // after the completion.
+                let start = self.scheduled.and_time(NaiveTime::MIN);
+                let due = interval.first_after(start, done.and_time(NaiveTime::MIN))?;
+                Ok(due.date())
+            }
+            _ => Ok(interval.add_to(done.and_time(NaiveTime::MIN))?.date()),
+        }
+    }
+
+    /// The days after the due date before the habit is late.
+    fn slack_days(&self) -> i64 {
+        self.repeater.habit_max.map_or(0, |max| {
+            (approx_days(max) - approx_days(self.repeater.interval))
+                .round()
+                .max(0.0) as i64
+        })
+    }
+
+    fn day_after_due(&self, day: NaiveDate, due: NaiveDate) -> HabitDay {
+        let deadline = due + Duration::days(self.slack_days());
+        if day < due {
+            HabitDay::Early
+        } else if day < deadline {
+            HabitDay::Due
+        } else if day == deadline {
+            HabitDay::LastChance
+        } else {
+            HabitDay::Overdue
+        }
+    }
+
+    /// Where `day` falls relative to when the habit was due, given the
+    /// completions before it.
+    pub fn day(&self, day: NaiveDate) -> Result<HabitDay> {
+        let count = self.completions.partition_point(|done| *done < day);
+        let previous = count.checked_sub(1).map(|index| self.completions[index]);
+        Ok(self.day_after_due(day, self.due_after(previous)?))
+    }
+
+    pub fn stats(&self, today: NaiveDate) -> Result<HabitStats> {
+        let mut streak = 0;
+        let mut longest_streak = 0;
+        let mut missed = 0;
+        let mut previous = None;
+        for done in &self.completions {
+            if previous.is_some() && self.day(*done)? == HabitDay::Overdue {
+                missed += 1;
+                streak = 0;
+            }
+            streak += 1;
+            longest_streak = longest_streak.max(streak);
+            previous = Some(*done);
+        }
+        let overdue = self.day_after_due(today, self.scheduled) == HabitDay::Overdue
+            && previous.is_none_or(|done| done < today);
+        if overdue {
+            missed += 1;
+            streak = 0;
+        }
+        Ok(HabitStats {
+            completions: self.completions.len(),
+            current_streak: streak,
+            longest_streak,
+            missed,
+            last_done: previous,
+            next_due: self.scheduled,
+        })
+    }
+
+    /// The consistency graph from `days_before` today to `days_after`, a
+    /// coloured cell per day marked `*` if the habit was done and `!` for
+    /// today.
+    pub fn graph(&self, today: NaiveDate, days_before: i64, days_after: i64) -> Result<String> {
+        let mut graph = String::new();
+        for offset in -days_before..=days_after {
+            let day = today + Duration::days(offset);
+            let mark = if self.completions.contains(&day) {
+                '*'
+            } else if day == today {
+                '!'
+            } else {
+                ' '
+            };
+            let (r, g, b) = self.day(day)?.color();
+            graph.push_str(&format!("\x1b[30;48;2;{};{};{}m{}\x1b[0m", r, g, b, mark));
+        }
+        Ok(graph)
+    }
+}
+
+/// An approximate length of an interval in days, to compare it with a
+/// habit's maximum interval.
+fn approx_days(interval: Interval) -> f64 {
+    let value = f64::from(interval.value);
+    match interval.unit {
+        TimeUnit::Hour => value / 24.0,
+        TimeUnit::Day => value,
+        TimeUnit::Week => value * 7.0,
+        TimeUnit::Month => value * 30.0,
+        TimeUnit::Year => value * 365.0,
+    }
+}
+
+#[derive(Serialize)]
+struct HabitSummary<'a> {
+    file: &'a str,
+    heading: String,
+    #[serde(flatten)]
+    stats: HabitStats,
+}
+
+/// Render each habit's consistency graph and streaks, or their statistics
+/// as CSV or JSON.
+pub fn render_habits(
+    habits: &[Habit],
+    today: NaiveDate,
+    (days_before, days_after): (i64, i64),
+    format: OutputFormat,
+) -> Result<String> {
+    let mut summaries = vec![];
+    for habit in habits {
+        summaries.push(HabitSummary {
+            file: &habit.file,
+            heading: habit.outline_path.join(" / "),
+            stats: habit.stats(today)?,
+        });
+    }
+    Ok(match format {
+        OutputFormat::Table => {
+            let width = habits
+                .iter()
+                .map(|habit| habit.title().chars().count())
+                .max()
+                .unwrap_or(0);
+            let mut output = String::new();
+            for (habit, summary) in habits.iter().zip(&summaries) {
+                output.push_str(&format!(
+                    "{:<width$}  {}  {} in a row, best {}, {} missed\n",
+                    habit.title(),
+                    habit.graph(today, days_before, days_after)?,
+                    summary.stats.current_streak,
+                    summary.stats.longest_streak,
+                    summary.stats.missed,
+                ));
+            }
+            output
+        }
+        OutputFormat::Csv => {
+            let mut table = Table::new(&[
+                "file",
+                "heading",
+                "completions",
+                "current_streak",
+                "longest_streak",
+                "missed",
+                "last_done",
+                "next_due",
+            ]);
+            for summary in &summaries {
+                let stats = &summary.stats;
+                table.rows.push(vec![
+                    summary.file.to_string(),
+                    summary.heading.clone(),
+                    stats.completions.to_string(),
+                    stats.current_streak.to_string(),
+                    stats.longest_streak.to_string(),
+                    stats.missed.to_string(),
+                    stats.last_done.map(|d| d.to_string()).unwrap_or_default(),
+                    stats.next_due.to_string(),
+                ]);
+            }
+            table.to_csv()
+        }
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(&summaries).expect("habits serialize to JSON") + "\n"
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    const INPUT: &str = "* TODO Exercise
+SCHEDULED: <2026-10-18 Sun .+2d/3d>
+:PROPERTIES:
+:STYLE: habit
+:LAST_REPEAT: [2026-10-16 Fri 08:00]
+:END:
+:LOGBOOK:
+- State \"DONE\" from \"TODO\" [2026-10-16 Fri 08:00]
+- State \"DONE\" from \"TODO\" [2026-10-14 Wed 08:00]
+- State \"DONE\" from \"TODO\" [2026-10-08 Thu 08:00]
+- State \"DONE\" from \"TODO\" [2026-10-06 Tue 08:00]
+:END:
+* TODO Pay rent
+SCHEDULED: <2026-11-01 Sun +1m>
+:PROPERTIES:
+:STYLE: habit
+:END:
+* TODO Water plants
+SCHEDULED: <2026-10-12 Mon ++1w>
+:PROPERTIES:
+:STYLE: habit
+:END:
+";
+
+    #[test]
+    fn test_habits() {
+        let habits = habits(&Config::default(), INPUT, "habits.org");
+        assert_eq!(habits.len(), 2);
+        assert_eq!(habits[0].title(), "Exercise");
+        assert_eq!(
+            habits[0].completions,
+            vec![
+                date("2026-10-06"),
+                date("2026-10-08"),
+                date("2026-10-14"),
+                date("2026-10-16")
+            ]
+        );
+        assert_eq!(habits[1].title(), "Water plants");
+        assert!(habits[1].completions.is_empty());
+    }
+
+    #[test]
+    fn test_habit_days() {
+        // Given
+        let habit = &habits(&Config::default(), INPUT, "habits.org")[0];
+
+        // When
+        let days = (7..=20)
+            .map(|day| {
+                habit
+                    .day(date("2026-10-01") + Duration::days(day - 1))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Then
+        use HabitDay::*;
+        assert_eq!(
+            days,
+            vec![
+                Early, Due, Early, Due, LastChance, Overdue, Overdue, Overdue, Early, Due, Early,
+                Due, LastChance, Overdue,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_catch_up_habit_days() {
+        let input = "* TODO Water plants\nSCHEDULED: <2026-10-19 Mon ++1w>\n:PROPERTIES:\n:STYLE: habit\n:END:\n:LOGBOOK:\n- State \"DONE\" from \"TODO\" [2026-10-13 Tue 08:00]\n- State \"DONE\" from \"TODO\" [2026-10-06 Tue 08:00]\n- State \"DONE\" from \"TODO\" [2020-01-01 Wed 08:00]\n:END:\n";
+        let habit = &habits(&Config::default(), input, "habits.org")[0];
+        assert_eq!(habit.day(date("2026-10-08")).unwrap(), HabitDay::Early);
+        assert_eq!(habit.day(date("2026-10-12")).unwrap(), HabitDay::LastChance);
+        assert_eq!(habit.day(date("2020-01-06")).unwrap(), HabitDay::LastChance);
+        assert_eq!(habit.day(date("2020-01-07")).unwrap(), HabitDay::Overdue);
+    }
+
+    #[test]
+    fn test_habit_stats() {
+        let habits = habits(&Config::default(), INPUT, "habits.org");
+        assert_eq!(
+            habits[0].stats(date("2026-10-18")).unwrap(),
+            HabitStats {
+                completions: 4,
+                current_streak: 2,
+                longest_streak: 2,
+                missed: 1,
+                last_done: Some(date("2026-10-16")),
+                next_due: date("2026-10-18"),
+            }
+        );
+        let plants = habits[1].stats(date("2026-10-18")).unwrap();
+        assert_eq!((plants.current_streak, plants.missed), (0, 1));
+    }
+
+    #[test]
+    fn test_render_habits() {
+        let habits = habits(&Config::default(), INPUT, "habits.org");
+        let today = date("2026-10-18");
+        assert_eq!(
+            render_habits(&habits, today, (21, 7), OutputFormat::Csv).unwrap(),
+            "file,heading,completions,current_streak,longest_streak,missed,last_done,next_due
+habits.org,Exercise,4,2,2,1,2026-10-16,2026-10-18
+habits.org,Water plants,0,0,0,1,,2026-10-12
+"
+        );
+        let graph = habits[0].graph(today, 2, 1).unwrap();
+        assert_eq!(
+            graph,
+            "\x1b[30;48;2;77;249;70m*\x1b[0m\x1b[30;48;2;130;112;249m \x1b[0m\x1b[30;48;2;77;249;70m!\x1b[0m\x1b[30;48;2;245;249;70m \x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Calendar heatmaps of daily counts, as SVG or coloured terminal blocks.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+impl FromStr for WeekStart {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "monday" => Ok(WeekStart::Monday),
+            "sunday" => Ok(WeekStart::Sunday),
+            _ => bail!("Invalid week start, expected monday or sunday: {}", s),
+        }
+    }
+}
+
+impl WeekStart {
+    fn weekday(&self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+        }
+    }
+
+    /// The row of a date in the grid, from 0 at the start of the week.
+    fn row(&self, date: NaiveDate) -> usize {
+        match self {
+            WeekStart::Monday => date.weekday().num_days_from_monday() as usize,
+            WeekStart::Sunday => date.weekday().num_days_from_sunday() as usize,
+        }
+    }
+}
+
+/// Heatmap options, configured as the `[heatmap]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HeatmapOptions {
+    /// `#rrggbb` colours from no tasks to the most tasks in a day.
+    pub colors: Vec<String>,
+    pub week_start: WeekStart,
+}
+
+impl Default for HeatmapOptions {
+    fn default() -> Self {
+        HeatmapOptions {
+            colors: ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"]
+                .map(str::to_string)
+                .to_vec(),
+            week_start: WeekStart::Monday,
+        }
+    }
+}
+
+/// Daily counts laid out as a grid of weeks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    /// The first day of the first week shown.
+    start: NaiveDate,
+    /// The first and last days counted.
+    first: NaiveDate,
+    last: NaiveDate,
+    counts: BTreeMap<NaiveDate, usize>,
+    options: HeatmapOptions,
+}
+
+const CELL: usize = 12;
+const LEFT: usize = 30;
+const TOP: usize = 20;
+
+impl Heatmap {
+    /// Count the dates from `first` to `last`.
+    pub fn new(
+        dates: &[NaiveDate],
+        first: NaiveDate,
+        last: NaiveDate,
+        options: &HeatmapOptions,
+    ) -> Result<Heatmap> {
+        if options.colors.len() < 2 {
+            bail!("A heatmap needs at least two colours");
+        }
+        for color in &options.colors {
+            parse_color(color)?;
+        }
+        let mut counts = BTreeMap::new();
+        for date in dates.iter().filter(|date| (first..=last).contains(*date)) {
+            *counts.entry(*date).or_insert(0) += 1;
+        }
+        Ok(Heatmap {
+            start: first.week(options.week_start.weekday()).first_day(),
+            first,
+            last,
+            counts,
+            options: options.clone(),
+        })
+    }
+
+    /// The days from `first` to `last` with their grid column and row.
+    fn days(&self) -> impl Iterator<Item = (NaiveDate, usize, usize)> + '_ {
+        self.first
+            .iter_days()
+            .take_while(|date| *date <= self.last)
+            .map(|date| {
+                let column = (date - self.start).num_days() as usize / 7;
+                (date, column, self.options.week_start.row(date))
+            })
+    }
+
+    fn count(&self, date: NaiveDate) -> usize {
+        self.counts.get(&date).copied().unwrap_or(0)
+    }
+
+    /// The colour of a count: the first colour for none, and the rest
+    /// spread evenly up to the busiest day.
+    fn color(&self, count: usize) -> &str {
+        let colors = &self.options.colors;
+        let max = self.counts.values().copied().max().unwrap_or(0);
+        if count == 0 || max == 0 {
+            return &colors[0];
+        }
+        let steps = colors.len() - 1;
+        let level = (count * steps).div_ceil(max).clamp(1, steps);
+        &colors[level]
+    }
+
+    /// The columns where each month starts, with the month's name.
+    ///
+    /// A partial first month is only labelled if there is room before the
+    /// next label.
+    fn month_labels(&self) -> Vec<(usize, String)> {
+        let mut labels = self
+            .days()
+            .filter(|(date, _, _)| date.day() == 1 || *date == self.first)
+            .map(|(date, column, _)| (column, date.format("%b").to_string()))
+            .collect::<Vec<_>>();
+        if self.first.day() != 1 && labels.get(1).is_some_and(|(column, _)| *column < 2) {
+            labels.remove(0);
+        }
+        labels
+    }
+
+    fn weekday_labels(&self) -> [(usize, &'static str); 3] {
+        match self.options.week_start {
+            WeekStart::Monday => [(0, "Mon"), (2, "Wed"), (4, "Fri")],
+            WeekStart::Sunday => [(1, "Mon"), (3, "Wed"), (5, "Fri")],
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let columns = self
+            .days()
+            .map(|(_, column, _)| column + 1)
+            .max()
+            .unwrap_or(0);
+        let width = LEFT + columns * CELL;
+        let height = TOP + 7 * CELL;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"9\">\n",
+            width, height
+        );
+        for (column, month) in self.month_labels() {
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\">{}</text>\n",
+                LEFT + column * CELL,
+                TOP - 6,
+                month
+            ));
+        }
+        for (row, weekday) in self.weekday_labels() {
+            svg.push_str(&format!(
+                "  <text x=\"0\" y=\"{}\">{}</text>\n",
+                TOP + row * CELL + 9,
+                weekday
+            ));
+        }
+        for (date, column, row) in self.days() {
+            let count = self.count(date);
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" rx=\"2\" fill=\"{}\"><title>{}: {} {}</title></rect>\n",
+                LEFT + column * CELL,
+                TOP + row * CELL,
+                self.color(count),
+                date,
+                count,
+                if count == 1 { "task" } else { "tasks" }
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render with 24-bit ANSI colours, a row per weekday.
+    pub fn to_terminal(&self) -> String {
+        let columns = self
+            .days()
+            .map(|(_, column, _)| column + 1)
+            .max()
+            .unwrap_or(0);
+        let mut rows = vec![vec!["  ".to_string(); columns]; 7];
+        for (date, column, row) in self.days() {
+            let (r, g, b) = parse_color(self.color(self.count(date))).expect("colours are checked");
+            rows[row][column] = format!("\x1b[38;2;{};{};{}m■\x1b[0m ", r, g, b);
+        }
+
+        let mut months = String::new();
+        for (column, month) in self.month_labels() {
+            let position = 4 + column * 2;
+            if months.len() < position {
+                months.push_str(&" ".repeat(position - months.len()));
+                months.push_str(&month);
+            }
+        }
+
+        let mut output = months;
+        output.push('\n');
+        let weekday_labels = self.weekday_labels();
+        for (row, cells) in rows.iter().enumerate() {
+            let label = weekday_labels
+                .iter()
+                .find(|(label_row, _)| *label_row == row)
+                .map_or("", |(_, label)| label);
+            output.push_str(format!("{:<4}{}", label, cells.concat()).trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// The 365 days up to and including `last`.
+pub fn year_ending(last: NaiveDate) -> (NaiveDate, NaiveDate) {
+    (last - Duration::days(364), last)
+}
+
+fn parse_color(color: &str) -> Result<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Invalid colour, expected #rrggbb: {}", color);
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).expect("checked hex digits");
+    Ok((channel(0), channel(2), channel(4)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn heatmap(week_start: WeekStart) -> Heatmap {
+        let options = HeatmapOptions {
+            colors: vec![
+                "#000000".to_string(),
+                "#00ff00".to_string(),
+                "#ffffff".to_string(),
+            ],
+            week_start,
+        };
+        let dates = [
+            date("2026-10-01"),
+            date("2026-10-01"),
+            date("2026-10-02"),
+            date("2027-01-01"),
+        ];
+        Heatmap::new(&dates, date("2026-09-30"), date("2026-10-05"), &options).unwrap()
+    }
+
+    #[test]
+    fn test_heatmap_colors() {
+        let heatmap = heatmap(WeekStart::Monday);
+        assert_eq!(heatmap.color(0), "#000000");
+        assert_eq!(heatmap.color(1), "#00ff00");
+        assert_eq!(heatmap.color(2), "#ffffff");
+        assert!(Heatmap::new(
+            &[],
+            date("2026-01-01"),
+            date("2026-12-31"),
+            &HeatmapOptions {
+                colors: vec!["green".to_string(), "#ffffff".to_string()],
+                week_start: WeekStart::Monday,
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_heatmap_terminal() {
+        let cell = |color: &str| format!("\x1b[38;2;{}m■\x1b[0m", color);
+        let (black, green, white) = (cell("0;0;0"), cell("0;255;0"), cell("255;255;255"));
+        assert_eq!(
+            heatmap(WeekStart::Monday).to_terminal(),
+            format!("    Oct\nMon   {black}\n\nWed {black}\n    {white}\nFri {green}\n    {black}\n    {black}\n")
+        );
+        assert_eq!(
+            heatmap(WeekStart::Sunday).to_terminal(),
+            format!("    Oct\n      {black}\nMon   {black}\n\nWed {black}\n    {white}\nFri {green}\n    {black}\n")
+        );
+    }
+
+    #[test]
+    fn test_heatmap_svg() {
+        let svg = heatmap(WeekStart::Monday).to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"54\""));
+        assert!(svg.contains(
+            "<rect x=\"30\" y=\"56\" width=\"10\" height=\"10\" rx=\"2\" fill=\"#ffffff\"><title>2026-10-01: 2 tasks</title></rect>"
+        ));
+        assert_eq!(svg.matches("<rect").count(), 6);
+    }
+}
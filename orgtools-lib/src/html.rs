@@ -0,0 +1,740 @@
+//! Exporting documents to HTML5 pages.
+//!
+//! The table of contents and body are rendered from the [`Document`]
+//! element tree, then filled into a template holding the rest of the page,
+//! such as a header, footer and stylesheets.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::document::{
+    heading_link_title, plain_text, slug, Block, Checkbox, Document, Heading, Inline, List, Table,
+    IMAGE_EXTENSIONS,
+};
+use crate::timestamp::Timestamp;
+
+/// The URL schemes links may use. Links with any other scheme, such as
+/// `javascript:`, are exported as plain text.
+const SAFE_SCHEMES: [&str; 4] = ["http", "https", "mailto", "ftp"];
+
+/// The page used when no template is configured.
+pub const DEFAULT_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{title}}</title>
+</head>
+<body>
+<main>
+{{toc}}
+{{body}}
+</main>
+</body>
+</html>
+"#;
+
+/// HTML export options, configured as the `[html]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HtmlOptions {
+    /// A file to use instead of [`DEFAULT_TEMPLATE`], with `{{title}}`,
+    /// `{{toc}}` and `{{body}}` placeholders.
+    pub template: Option<String>,
+    /// The deepest heading level in the table of contents, or 0 for none.
+    pub toc_depth: usize,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        HtmlOptions {
+            template: None,
+            toc_depth: 3,
+        }
+    }
+}
+
+/// The parts of an exported page, to fill into a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlPage {
+    /// The `#+TITLE:` or else the first heading, as plain text.
+    pub title: String,
+    /// A `<nav>` with links to the headings, or nothing.
+    pub toc: String,
+    pub body: String,
+}
+
+impl HtmlPage {
+    /// Replace the `{{title}}`, `{{toc}}` and `{{body}}` placeholders in
+    /// `template`, leaving any other `{{...}}` as it is.
+    pub fn render(&self, template: &str) -> String {
+        let mut output = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find("}}") else {
+                break;
+            };
+            let value = match rest[2..end].trim() {
+                "title" => escape(&self.title),
+                "toc" => self.toc.clone(),
+                "body" => self.body.clone(),
+                _ => {
+                    output.push_str("{{");
+                    rest = &rest[2..];
+                    continue;
+                }
+            };
+            output.push_str(&value);
+            rest = &rest[end + 2..];
+        }
+        output.push_str(rest);
+        output
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes elements, escaping text and attribute values.
+#[derive(Debug, Default)]
+struct HtmlWriter {
+    html: String,
+}
+
+impl HtmlWriter {
+    fn start(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.html.push('<');
+        self.html.push_str(name);
+        for (attribute, value) in attributes {
+            self.html
+                .push_str(&format!(" {}=\"{}\"", attribute, escape(value)));
+        }
+        self.html.push('>');
+    }
+
+    fn end(&mut self, name: &str) {
+        self.html.push_str(&format!("</{}>", name));
+    }
+
+    fn text(&mut self, text: &str) {
+        self.html.push_str(&escape(text));
+    }
+
+    fn element(&mut self, name: &str, attributes: &[(&str, &str)], text: &str) {
+        self.start(name, attributes);
+        self.text(text);
+        self.end(name);
+    }
+
+    fn newline(&mut self) {
+        self.html.push('\n');
+    }
+}
+
+/// Render a document as the parts of an HTML page.
+///
+/// Each heading opens a `<section>` holding its subsections, with an `id`
+/// from its `:CUSTOM_ID:` or a slug of its title. A `#+TITLE:` becomes an
+/// `<h1>` with the document's headings a level below it.
+pub fn to_html(config: &Config, document: &Document, options: &HtmlOptions) -> HtmlPage {
+    let mut exporter = Exporter::new(config, document);
+    let offset = usize::from(document.title().is_some());
+    let mut body = HtmlWriter::default();
+    if let Some(title) = document.title() {
+        body.element("h1", &[("class", "title")], title);
+        body.newline();
+    }
+
+    let mut sections = vec![];
+    let mut headings = 0;
+    for block in &document.blocks {
+        let Block::Heading(heading) = block else {
+            exporter.block(&mut body, block);
+            continue;
+        };
+        while sections.last().is_some_and(|&level| level >= heading.level) {
+            body.end("section");
+            body.newline();
+            sections.pop();
+        }
+        body.start("section", &[]);
+        body.newline();
+        let id = exporter.ids[headings].clone();
+        exporter.heading(&mut body, heading, offset, &id);
+        sections.push(heading.level);
+        headings += 1;
+    }
+    for _ in sections {
+        body.end("section");
+        body.newline();
+    }
+
+    if !document.footnotes.is_empty() {
+        body.start(
+            "section",
+            &[("class", "footnotes"), ("role", "doc-endnotes")],
+        );
+        body.newline();
+        body.element(&format!("h{}", (offset + 1).min(6)), &[], "Footnotes");
+        body.newline();
+        for footnote in &document.footnotes {
+            let id = format!("fn.{}", footnote.name);
+            body.start("p", &[("id", &id)]);
+            body.start("sup", &[]);
+            body.element(
+                "a",
+                &[("href", &format!("#fnr.{}", footnote.name))],
+                &footnote.name,
+            );
+            body.end("sup");
+            body.text(" ");
+            exporter.inlines(&mut body, &footnote.content);
+            body.end("p");
+            body.newline();
+        }
+        body.end("section");
+        body.newline();
+    }
+
+    HtmlPage {
+        title: document
+            .title()
+            .map(str::to_string)
+            .or_else(|| document.headings().next().map(|h| plain_text(&h.title)))
+            .unwrap_or_default(),
+        toc: exporter.toc(document, options.toc_depth),
+        body: body.html,
+    }
+}
+
+struct Exporter<'a> {
+    config: &'a Config,
+    /// The anchor of each heading, in document order.
+    ids: Vec<String>,
+    /// Anchors by heading title, for `[[*Heading]]` and `[[Heading]]` links.
+    targets: BTreeMap<String, String>,
+    /// Footnotes already referenced, whose later references get no anchor.
+    referenced: BTreeSet<String>,
+}
+
+impl<'a> Exporter<'a> {
+    fn new(config: &'a Config, document: &Document) -> Exporter<'a> {
+        let mut used = document
+            .headings()
+            .filter_map(|heading| heading.custom_id.clone())
+            .collect::<BTreeSet<_>>();
+        let mut ids = vec![];
+        let mut targets = BTreeMap::new();
+        for heading in document.headings() {
+            let title = plain_text(&heading.title);
+            let id = match &heading.custom_id {
+                Some(custom_id) => custom_id.clone(),
+                None => {
+                    let stem = match slug(&title) {
+                        stem if stem.is_empty() => "section".to_string(),
+                        stem => stem,
+                    };
+                    let mut id = stem.clone();
+                    let mut suffix = 1;
+                    while !used.insert(id.clone()) {
+                        suffix += 1;
+                        id = format!("{}-{}", stem, suffix);
+                    }
+                    id
+                }
+            };
+            targets.entry(title).or_insert_with(|| id.clone());
+            ids.push(id);
+        }
+        Exporter {
+            config,
+            ids,
+            targets,
+            referenced: BTreeSet::new(),
+        }
+    }
+
+    /// A `<nav>` of nested lists of links to the headings down to `depth`.
+    fn toc(&self, document: &Document, depth: usize) -> String {
+        let entries = document
+            .headings()
+            .zip(&self.ids)
+            .filter(|(heading, _)| heading.level <= depth)
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return String::new();
+        }
+        let mut toc = HtmlWriter::default();
+        toc.start("nav", &[("id", "table-of-contents")]);
+        toc.newline();
+        toc.element("h2", &[], "Contents");
+        toc.newline();
+        let mut lists: Vec<usize> = vec![];
+        for (heading, id) in entries {
+            while lists.len() > 1 && lists.last().is_some_and(|&level| level > heading.level) {
+                toc.end("li");
+                toc.newline();
+                toc.end("ul");
+                toc.newline();
+                lists.pop();
+            }
+            match lists.last() {
+                Some(&level) if heading.level > level => {
+                    toc.newline();
+                    toc.start("ul", &[]);
+                    toc.newline();
+                    lists.push(heading.level);
+                }
+                Some(_) => {
+                    toc.end("li");
+                    toc.newline();
+                }
+                None => {
+                    toc.start("ul", &[]);
+                    toc.newline();
+                    lists.push(heading.level);
+                }
+            }
+            toc.start("li", &[]);
+            toc.element(
+                "a",
+                &[("href", &format!("#{}", id))],
+                &plain_text(&heading.title),
+            );
+        }
+        for _ in lists {
+            toc.end("li");
+            toc.newline();
+            toc.end("ul");
+            toc.newline();
+        }
+        toc.end("nav");
+        toc.newline();
+        toc.html
+    }
+
+    fn heading(&mut self, out: &mut HtmlWriter, heading: &Heading, offset: usize, id: &str) {
+        let name = format!("h{}", (heading.level + offset).min(6));
+        out.start(&name, &[("id", id)]);
+        if let Some(keyword) = &heading.keyword {
+            let state = if self.config.is_finished(keyword) {
+                "done"
+            } else {
+                "todo"
+            };
+            out.element(
+                "span",
+                &[("class", &format!("{} {}", state, keyword))],
+                keyword,
+            );
+            out.text(" ");
+        }
+        if let Some(priority) = heading.priority {
+            out.element(
+                "span",
+                &[("class", "priority")],
+                &format!("[#{}]", priority),
+            );
+            out.text(" ");
+        }
+        self.inlines(out, &heading.title);
+        if !heading.tags.is_empty() {
+            out.text(" ");
+            out.start("span", &[("class", "tags")]);
+            for (index, tag) in heading.tags.iter().enumerate() {
+                if index > 0 {
+                    out.text(" ");
+                }
+                out.element("span", &[("class", "tag")], tag);
+            }
+            out.end("span");
+        }
+        out.end(&name);
+        out.newline();
+    }
+
+    fn block(&mut self, out: &mut HtmlWriter, block: &Block) {
+        match block {
+            Block::Heading(heading) => {
+                let id = slug(&plain_text(&heading.title));
+                self.heading(out, heading, 0, &id);
+            }
+            Block::Paragraph(inlines) => {
+                out.start("p", &[]);
+                self.inlines(out, inlines);
+                out.end("p");
+                out.newline();
+            }
+            Block::List(list) => self.list(out, list),
+            Block::Table(table) => self.table(out, table),
+            Block::Src { language, code } => {
+                match language {
+                    Some(language) => {
+                        out.start("pre", &[("class", &format!("src src-{}", language))]);
+                        out.element(
+                            "code",
+                            &[("class", &format!("language-{}", language))],
+                            code,
+                        );
+                    }
+                    None => {
+                        out.start("pre", &[("class", "src")]);
+                        out.element("code", &[], code);
+                    }
+                }
+                out.end("pre");
+                out.newline();
+            }
+            Block::Example(text) => {
+                out.element("pre", &[("class", "example")], text);
+                out.newline();
+            }
+            Block::Quote(blocks) => {
+                out.start("blockquote", &[]);
+                out.newline();
+                for block in blocks {
+                    self.block(out, block);
+                }
+                out.end("blockquote");
+                out.newline();
+            }
+            Block::HorizontalRule => {
+                out.start("hr", &[]);
+                out.newline();
+            }
+        }
+    }
+
+    fn list(&mut self, out: &mut HtmlWriter, list: &List) {
+        let name = if list.ordered { "ol" } else { "ul" };
+        out.start(name, &[]);
+        out.newline();
+        for item in &list.items {
+            let class = match item.checkbox {
+                Some(Checkbox::Checked) => Some("on"),
+                Some(Checkbox::Partial) => Some("trans"),
+                Some(Checkbox::Unchecked) => Some("off"),
+                None => None,
+            };
+            match class {
+                Some(class) => out.start("li", &[("class", class)]),
+                None => out.start("li", &[]),
+            }
+            if let Some(checkbox) = item.checkbox {
+                let mut attributes = vec![("type", "checkbox"), ("disabled", "")];
+                if checkbox == Checkbox::Checked {
+                    attributes.push(("checked", ""));
+                }
+                out.start("input", &attributes);
+                out.text(" ");
+            }
+            // A leading paragraph is the item's text rather than a <p>.
+            let mut blocks = item.blocks.as_slice();
+            if let Some((Block::Paragraph(inlines), rest)) = blocks.split_first() {
+                self.inlines(out, inlines);
+                blocks = rest;
+            }
+            if !blocks.is_empty() {
+                out.newline();
+            }
+            for block in blocks {
+                self.block(out, block);
+            }
+            out.end("li");
+            out.newline();
+        }
+        out.end(name);
+        out.newline();
+    }
+
+    fn table(&mut self, out: &mut HtmlWriter, table: &Table) {
+        let (header, body) = table.header_and_body();
+        out.start("table", &[]);
+        out.newline();
+        for (section, cell, rows) in [("thead", "th", header), ("tbody", "td", body)] {
+            if rows.is_empty() {
+                continue;
+            }
+            out.start(section, &[]);
+            out.newline();
+            for row in rows {
+                out.start("tr", &[]);
+                for inlines in row {
+                    out.start(cell, &[]);
+                    self.inlines(out, inlines);
+                    out.end(cell);
+                }
+                out.end("tr");
+                out.newline();
+            }
+            out.end(section);
+            out.newline();
+        }
+        out.end("table");
+        out.newline();
+    }
+
+    fn inlines(&mut self, out: &mut HtmlWriter, inlines: &[Inline]) {
+        for inline in inlines {
+            self.inline(out, inline);
+        }
+    }
+
+    fn inline(&mut self, out: &mut HtmlWriter, inline: &Inline) {
+        let (name, inner) = match inline {
+            Inline::Text(text) => return out.text(text),
+            Inline::Bold(inner) => ("strong", inner),
+            Inline::Italic(inner) => ("em", inner),
+            Inline::Underline(inner) => ("u", inner),
+            Inline::StrikeThrough(inner) => ("del", inner),
+            Inline::Verbatim(code) | Inline::Code(code) => return out.element("code", &[], code),
+            Inline::Link {
+                target,
+                description,
+            } => return self.link(out, target, description.as_deref()),
+            Inline::FootnoteReference(name) => {
+                let href = format!("#fn.{}", name);
+                let id = format!("fnr.{}", name);
+                let mut attributes = vec![("class", "footref"), ("href", href.as_str())];
+                if self.referenced.insert(name.clone()) {
+                    attributes.insert(0, ("id", id.as_str()));
+                }
+                out.start("sup", &[]);
+                out.element("a", &attributes, name);
+                out.end("sup");
+                return;
+            }
+            Inline::Timestamp(timestamp) => {
+                out.element(
+                    "time",
+                    &[("class", "timestamp"), ("datetime", &datetime(timestamp))],
+                    &timestamp.to_string(),
+                );
+                return;
+            }
+            Inline::LineBreak => {
+                out.start("br", &[]);
+                out.newline();
+                return;
+            }
+        };
+        out.start(name, &[]);
+        self.inlines(out, inner);
+        out.end(name);
+    }
+
+    /// Links to headings that aren't in the document and links with unsafe
+    /// URLs are exported as their text.
+    fn link(&mut self, out: &mut HtmlWriter, target: &str, description: Option<&[Inline]>) {
+        let title = heading_link_title(target);
+        let href = if let Some(title) = title {
+            self.targets.get(title).map(|id| format!("#{}", id))
+        } else if let Some(path) = target.strip_prefix("file:") {
+            match path.strip_suffix(".org") {
+                Some(stem) => Some(format!("{}.html", stem)),
+                None => Some(path.to_string()),
+            }
+        } else {
+            Some(target.to_string())
+        };
+        let text = title.unwrap_or(target);
+        let Some(href) = href.filter(|href| is_safe_url(href)) else {
+            match description {
+                Some(description) => self.inlines(out, description),
+                None => out.text(text),
+            }
+            return;
+        };
+        match description {
+            None if IMAGE_EXTENSIONS
+                .iter()
+                .any(|ext| href.to_lowercase().ends_with(ext)) =>
+            {
+                let alt = href.rsplit('/').next().unwrap_or(&href).to_string();
+                out.start("img", &[("src", &href), ("alt", &alt)]);
+            }
+            None => out.element("a", &[("href", &href)], text),
+            Some(description) => {
+                out.start("a", &[("href", &href)]);
+                self.inlines(out, description);
+                out.end("a");
+            }
+        }
+    }
+}
+
+/// Whether a URL is relative or uses one of the [`SAFE_SCHEMES`].
+///
+/// Browsers ignore whitespace and control characters in a scheme, so they
+/// are ignored here too.
+fn is_safe_url(url: &str) -> bool {
+    let url = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect::<String>();
+    let scheme_end = url.find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)));
+    match scheme_end {
+        Some(end) if end > 0 && url[end..].starts_with(':') => {
+            SAFE_SCHEMES.contains(&url[..end].to_ascii_lowercase().as_str())
+        }
+        _ => true,
+    }
+}
+
+/// A timestamp's `datetime` attribute: its date, and its start time.
+fn datetime(timestamp: &Timestamp) -> String {
+    match timestamp.time {
+        Some(time) => format!("{}T{}", timestamp.date, time.format("%H:%M")),
+        None => timestamp.date.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::parse_document;
+
+    fn html(input: &str) -> HtmlPage {
+        let config = Config::default();
+        to_html(
+            &config,
+            &parse_document(&config, input),
+            &HtmlOptions::default(),
+        )
+    }
+
+    #[test]
+    fn test_to_html() {
+        // Given
+        let input = "#+TITLE: Guide & notes
+Due <2026-10-20 Tue 09:30>.[fn:1]
+
+* TODO [#A] Install :setup:
+:PROPERTIES:
+:CUSTOM_ID: install
+:END:
+- [X] Download
+- [ ] See [[*Usage][usage]]
+** Details
+#+BEGIN_SRC rust
+let x = a < b;
+#+END_SRC
+* DONE Usage
+| Option | Meaning |
+|--------+---------|
+| -v     | /Verbose/ |
+
+[fn:1] Or sooner.
+";
+
+        // When
+        let page = html(input);
+
+        // Then
+        assert_eq!(page.title, "Guide & notes");
+        assert_eq!(
+            page.body,
+            r##"<h1 class="title">Guide &amp; notes</h1>
+<p>Due <time class="timestamp" datetime="2026-10-20T09:30">&lt;2026-10-20 Tue 09:30&gt;</time>.<sup><a id="fnr.1" class="footref" href="#fn.1">1</a></sup></p>
+<section>
+<h2 id="install"><span class="todo TODO">TODO</span> <span class="priority">[#A]</span> Install <span class="tags"><span class="tag">setup</span></span></h2>
+<ul>
+<li class="on"><input type="checkbox" disabled="" checked=""> Download</li>
+<li class="off"><input type="checkbox" disabled=""> See <a href="#usage">usage</a></li>
+</ul>
+<section>
+<h3 id="details">Details</h3>
+<pre class="src src-rust"><code class="language-rust">let x = a &lt; b;</code></pre>
+</section>
+</section>
+<section>
+<h2 id="usage"><span class="done DONE">DONE</span> Usage</h2>
+<table>
+<thead>
+<tr><th>Option</th><th>Meaning</th></tr>
+</thead>
+<tbody>
+<tr><td>-v</td><td><em>Verbose</em></td></tr>
+</tbody>
+</table>
+</section>
+<section class="footnotes" role="doc-endnotes">
+<h2>Footnotes</h2>
+<p id="fn.1"><sup><a href="#fnr.1">1</a></sup> Or sooner.</p>
+</section>
+"##
+        );
+        assert_eq!(
+            page.toc,
+            r##"<nav id="table-of-contents">
+<h2>Contents</h2>
+<ul>
+<li><a href="#install">Install</a>
+<ul>
+<li><a href="#details">Details</a></li>
+</ul>
+</li>
+<li><a href="#usage">Usage</a></li>
+</ul>
+</nav>
+"##
+        );
+    }
+
+    #[test]
+    fn test_heading_ids() {
+        let page = html("* Notes\n* Notes\n* Other\n:PROPERTIES:\n:CUSTOM_ID: notes-2\n:END:\n");
+        assert!(page.body.contains("<h1 id=\"notes\">"));
+        assert!(page.body.contains("<h1 id=\"notes-3\">"));
+        assert!(page.body.contains("<h1 id=\"notes-2\">Other"));
+        assert_eq!(page.title, "Notes");
+    }
+
+    #[test]
+    fn test_unsafe_links_are_text() {
+        let page = html("[[javascript:alert(1)][Click]] [[java\tscript:alert(1)]] [[https://example.com][ok]] [[notes.png]]\n");
+        assert_eq!(
+            page.body,
+            "<p>Click java\tscript:alert(1) <a href=\"https://example.com\">ok</a> <img src=\"notes.png\" alt=\"notes.png\"></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_internal_links() {
+        let page = html(
+            "* Usage\nSee [[*Usage]], [[Usage][how to]], [[*Missing]] and [[Gone][elsewhere]].\n",
+        );
+        assert_eq!(
+            page.body,
+            "<section>\n<h1 id=\"usage\">Usage</h1>\n<p>See <a href=\"#usage\">Usage</a>, <a href=\"#usage\">how to</a>, Missing and elsewhere.</p>\n</section>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_template() {
+        let page = HtmlPage {
+            title: "A <b>".to_string(),
+            toc: String::new(),
+            body: "<p>{{title}}</p>".to_string(),
+        };
+        assert_eq!(
+            page.render("<title>{{ title }}</title>{{toc}}{{body}}{{other}}{{"),
+            "<title>A &lt;b&gt;</title><p>{{title}}</p>{{other}}{{"
+        );
+    }
+}
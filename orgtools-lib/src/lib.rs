@@ -1,5 +1,32 @@
+pub mod archive;
+pub mod capture;
+pub mod clock;
+pub mod clocktable;
 pub mod config;
+pub mod datetree;
+pub mod document;
+pub mod effort;
+pub mod entry;
+pub mod flow;
+pub mod format;
+pub mod habits;
+pub mod heatmap;
+pub mod html;
+pub mod lint;
+pub mod markdown;
+pub mod matcher;
+pub mod narrow;
 pub mod org;
+pub mod outline;
+pub mod prune;
+pub mod report;
+pub mod review;
+pub mod sort;
+pub mod split;
+pub mod state;
+pub mod stats;
+pub mod subtree;
+pub mod timestamp;
 mod utils;
 
 pub mod tree_sitter {
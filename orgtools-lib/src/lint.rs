@@ -0,0 +1,473 @@
+//! Checking Org text for likely mistakes.
+//!
+//! Each rule has a level, configured in the `[lint]` table by rule name,
+//! e.g. `skipped-level = "off"` or `unknown-keyword = "error"`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::entry::{drawer_name, parse_property, Headline, Planning};
+use crate::outline::{headline_level, inside_blocks};
+use crate::timestamp::Timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Off => "off",
+            Level::Info => "info",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The rules and their default levels.
+///
+/// `unknown-keyword` is off by default as titles often start with an
+/// acronym.
+pub const RULES: &[(&str, Level)] = &[
+    ("unknown-keyword", Level::Off),
+    ("duplicate-id", Level::Error),
+    ("broken-link", Level::Error),
+    ("malformed-timestamp", Level::Error),
+    ("deadline-before-scheduled", Level::Warning),
+    ("closed-unfinished", Level::Warning),
+    ("skipped-level", Level::Warning),
+    ("empty-headline", Level::Warning),
+    ("orphan-drawer", Level::Error),
+];
+
+/// The configured levels of the lint rules, by rule name.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct LintOptions(pub BTreeMap<String, Level>);
+
+impl LintOptions {
+    pub fn level(&self, rule: &str) -> Level {
+        self.0.get(rule).copied().unwrap_or_else(|| {
+            RULES
+                .iter()
+                .find(|(name, _)| *name == rule)
+                .map_or(Level::Off, |(_, level)| *level)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+    pub rule: &'static str,
+    pub level: Level,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}[{}]: {}",
+            self.line, self.column, self.level, self.rule, self.message
+        )
+    }
+}
+
+/// Check the input against the rules that aren't turned off.
+///
+/// Diagnostics are returned in line order.
+pub fn lint(config: &Config, input: &str) -> Result<Vec<Diagnostic>> {
+    for name in config.lint.0.keys() {
+        if !RULES.iter().any(|(rule, _)| rule == name) {
+            bail!("Unknown lint rule: {}", name);
+        }
+    }
+
+    let mut linter = Linter {
+        options: &config.lint,
+        diagnostics: vec![],
+    };
+    linter.check(config, input);
+    linter
+        .diagnostics
+        .sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    Ok(linter.diagnostics)
+}
+
+struct Linter<'a> {
+    options: &'a LintOptions,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Where a link or ID was found.
+type Location = (usize, usize);
+
+impl Linter<'_> {
+    fn report(&mut self, rule: &'static str, (line, column): Location, message: String) {
+        let level = self.options.level(rule);
+        if level != Level::Off {
+            self.diagnostics.push(Diagnostic {
+                line,
+                column,
+                rule,
+                level,
+                message,
+            });
+        }
+    }
+
+    fn check(&mut self, config: &Config, input: &str) {
+        let mut titles = HashSet::new();
+        let mut custom_ids = HashSet::new();
+        let mut targets = HashSet::new();
+        let mut ids: HashMap<String, Location> = HashMap::new();
+        let mut links: Vec<(String, Location)> = vec![];
+
+        let mut previous_level = 0;
+        let mut headline: Option<Headline> = None;
+        // Whether drawers may start here: directly after a headline, its
+        // planning line or another drawer.
+        let mut after_headline = false;
+        let mut drawer: Option<(String, Location)> = None;
+
+        let lines = input.lines().collect::<Vec<_>>();
+        for (index, (line, inside)) in lines.iter().zip(inside_blocks(&lines)).enumerate() {
+            let number = index + 1;
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+
+            if inside {
+                after_headline = false;
+                continue;
+            }
+            if let Some(name) = trimmed
+                .strip_prefix("#+")
+                .and_then(|rest| rest.split_once(':'))
+                .filter(|(key, _)| key.eq_ignore_ascii_case("NAME"))
+                .map(|(_, name)| name.trim())
+            {
+                targets.insert(name.to_string());
+            }
+
+            if let Some(level) = headline_level(line) {
+                if let Some((name, location)) = drawer.take() {
+                    self.report(
+                        "orphan-drawer",
+                        location,
+                        format!("Drawer :{}: is never closed", name),
+                    );
+                }
+                let parsed = Headline::parse(line, config).expect("line is a headline");
+                self.check_headline(config, &parsed, line, number, previous_level);
+                titles.insert(parsed.title.clone());
+                previous_level = level;
+                headline = Some(parsed);
+                after_headline = true;
+                self.collect_links(line, number, &mut links, &mut targets);
+                continue;
+            }
+
+            if let Some((name, _)) = &drawer {
+                if trimmed.eq_ignore_ascii_case(":END:") {
+                    drawer = None;
+                    after_headline = true;
+                } else if name.eq_ignore_ascii_case("PROPERTIES") {
+                    if let Some((key, value)) = parse_property(trimmed) {
+                        if key.eq_ignore_ascii_case("ID") {
+                            self.check_id(&value, (number, indent + 1), &mut ids);
+                        } else if key.eq_ignore_ascii_case("CUSTOM_ID") {
+                            custom_ids.insert(value);
+                        }
+                    }
+                }
+                self.check_timestamps(line, number);
+                continue;
+            }
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                self.report(
+                    "orphan-drawer",
+                    (number, indent + 1),
+                    ":END: without an opening drawer".to_string(),
+                );
+                continue;
+            }
+            if let Some(name) = drawer_name(trimmed) {
+                if name.eq_ignore_ascii_case("PROPERTIES") && !after_headline {
+                    self.report(
+                        "orphan-drawer",
+                        (number, indent + 1),
+                        "Property drawer is not directly under a headline".to_string(),
+                    );
+                }
+                drawer = Some((name.to_string(), (number, indent + 1)));
+                continue;
+            }
+
+            if after_headline {
+                if let Some(planning) = Planning::parse(trimmed) {
+                    self.check_planning(config, &planning, headline.as_ref(), number, indent);
+                    continue;
+                }
+            }
+            after_headline = false;
+            self.check_timestamps(line, number);
+            self.collect_links(line, number, &mut links, &mut targets);
+        }
+        if let Some((name, location)) = drawer {
+            self.report(
+                "orphan-drawer",
+                location,
+                format!("Drawer :{}: is never closed", name),
+            );
+        }
+
+        for (target, location) in links {
+            let found = match target.strip_prefix('#') {
+                Some(custom_id) => custom_ids.contains(custom_id),
+                None => match target.strip_prefix('*') {
+                    Some(title) => titles.contains(title.trim()),
+                    None => targets.contains(&target) || titles.contains(&target),
+                },
+            };
+            if !found {
+                self.report(
+                    "broken-link",
+                    location,
+                    format!("Link to {:?} has no target in this file", target),
+                );
+            }
+        }
+    }
+
+    fn check_headline(
+        &mut self,
+        config: &Config,
+        headline: &Headline,
+        line: &str,
+        number: usize,
+        previous_level: usize,
+    ) {
+        let level = headline.level;
+        if level > previous_level + 1 {
+            self.report(
+                "skipped-level",
+                (number, 1),
+                format!(
+                    "Level {} headline follows a level {} headline",
+                    level, previous_level
+                ),
+            );
+        }
+        if headline.title.is_empty() {
+            self.report(
+                "empty-headline",
+                (number, 1),
+                "Headline has no title".to_string(),
+            );
+        }
+        if headline.keyword.is_none() {
+            let mut words = headline.title.split_whitespace();
+            let word = words.next().unwrap_or_default();
+            let looks_like_keyword = word.len() > 1
+                && word.chars().all(|c| c.is_ascii_uppercase())
+                && words.next().is_some();
+            if looks_like_keyword && !config.is_keyword(word) {
+                let column = line.find(word).unwrap_or(0) + 1;
+                self.report(
+                    "unknown-keyword",
+                    (number, column),
+                    format!("Unknown TODO keyword {}", word),
+                );
+            }
+        }
+        self.check_timestamps(line, number);
+    }
+
+    fn check_planning(
+        &mut self,
+        config: &Config,
+        planning: &Planning,
+        headline: Option<&Headline>,
+        number: usize,
+        indent: usize,
+    ) {
+        let location = (number, indent + 1);
+        if let (Some(deadline), Some(scheduled)) = (&planning.deadline, &planning.scheduled) {
+            if deadline.datetime() < scheduled.datetime() {
+                self.report(
+                    "deadline-before-scheduled",
+                    location,
+                    format!("DEADLINE {} is before SCHEDULED {}", deadline, scheduled),
+                );
+            }
+        }
+        let keyword = headline.and_then(|headline| headline.keyword.as_deref());
+        if planning.closed.is_some() && keyword.is_some_and(|k| config.is_unfinished(k)) {
+            self.report(
+                "closed-unfinished",
+                location,
+                format!(
+                    "{} task has a CLOSED timestamp",
+                    keyword.unwrap_or_default()
+                ),
+            );
+        }
+    }
+
+    fn check_timestamps(&mut self, line: &str, number: usize) {
+        for (index, _) in line.match_indices(['<', '[']) {
+            let rest = &line[index + 1..];
+            let looks_like_date = rest.len() >= 5
+                && rest[..4].chars().all(|c| c.is_ascii_digit())
+                && rest[4..].starts_with('-');
+            if looks_like_date {
+                if let Err(err) = Timestamp::parse_prefix(&line[index..]) {
+                    let column = line[..index].chars().count() + 1;
+                    self.report("malformed-timestamp", (number, column), err.to_string());
+                }
+            }
+        }
+    }
+
+    fn check_id(&mut self, id: &str, location: Location, ids: &mut HashMap<String, Location>) {
+        match ids.get(id) {
+            Some((line, _)) => self.report(
+                "duplicate-id",
+                location,
+                format!("ID {} is already used on line {}", id, line),
+            ),
+            None => {
+                ids.insert(id.to_string(), location);
+            }
+        }
+    }
+
+    /// Collect the internal links in a line, and any `<<targets>>`.
+    fn collect_links(
+        &mut self,
+        line: &str,
+        number: usize,
+        links: &mut Vec<(String, Location)>,
+        targets: &mut HashSet<String>,
+    ) {
+        let mut rest = line;
+        while let Some(start) = rest.find("<<") {
+            rest = &rest[start + 2..];
+            if let Some(end) = rest.find(">>") {
+                targets.insert(rest[..end].trim_start_matches('<').to_string());
+            }
+        }
+
+        for (index, _) in line.match_indices("[[") {
+            let link = &line[index + 2..];
+            let Some(end) = link.find(']') else {
+                continue;
+            };
+            let target = &link[..end];
+            let is_internal = target.starts_with(['#', '*'])
+                || !target.contains(':') && !target.starts_with(['/', '.', '~']);
+            if is_internal && !target.is_empty() {
+                let column = line[..index].chars().count() + 1;
+                links.push((target.to_string(), (number, column)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_rules(input: &str) -> Vec<String> {
+        let config = Config::from_toml("[lint]\nunknown-keyword = \"warning\"\n").unwrap();
+        lint(&config, input)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_headlines() {
+        let input = "* NEXT Call Sam\n*** Too deep\n* \n* TODO Real task\n* FAQ\n";
+        assert_eq!(
+            lint_rules(input),
+            vec![
+                "1:3: warning[unknown-keyword]: Unknown TODO keyword NEXT",
+                "2:1: warning[skipped-level]: Level 3 headline follows a level 1 headline",
+                "3:1: warning[empty-headline]: Headline has no title",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_planning_and_timestamps() {
+        let input = "* TODO Task\nCLOSED: [2026-10-17 Sat] DEADLINE: <2026-10-10 Sat> SCHEDULED: <2026-10-12 Mon>\nMeet on <2026-13-01 Fri>\n";
+        assert_eq!(
+            lint_rules(input),
+            vec![
+                "2:1: warning[deadline-before-scheduled]: DEADLINE <2026-10-10 Sat> is before SCHEDULED <2026-10-12 Mon>",
+                "2:1: warning[closed-unfinished]: TODO task has a CLOSED timestamp",
+                "3:9: error[malformed-timestamp]: Invalid date in timestamp: 2026-13-01",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_ids_links_and_drawers() {
+        let input = "* A\n:PROPERTIES:\n:ID: abc\n:CUSTOM_ID: a\n:END:\n* B\n:PROPERTIES:\n:ID: abc\n:END:\nSee [[#a]], [[*B]], [[target]], [[*C]] and [[https://example.com]].\n<<target>>\n:END:\n:NOTES:\n";
+        assert_eq!(
+            lint_rules(input),
+            vec![
+                "8:1: error[duplicate-id]: ID abc is already used on line 3",
+                "10:33: error[broken-link]: Link to \"*C\" has no target in this file",
+                "12:1: error[orphan-drawer]: :END: without an opening drawer",
+                "13:1: error[orphan-drawer]: Drawer :NOTES: is never closed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_skips_terminated_blocks() {
+        let input =
+            "* A\n#+BEGIN_SRC org\n*** Example\n#+end_srcfoo\n#+END_SRC\n#+BEGIN_QUOTE\n*** Deep\n";
+        assert_eq!(
+            lint_rules(input),
+            vec!["7:1: warning[skipped-level]: Level 3 headline follows a level 1 headline"]
+        );
+    }
+
+    #[test]
+    fn test_lint_rule_levels() {
+        let config =
+            Config::from_toml("[lint]\nskipped-level = \"off\"\nunknown-keyword = \"error\"\n")
+                .unwrap();
+        let diagnostics = lint(&config, "* NEXT Thing\n*** Deep\n").unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, Level::Error);
+
+        let config = Config::from_toml("[lint]\nno-such-rule = \"off\"\n").unwrap();
+        assert!(lint(&config, "").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keyword_is_off_by_default() {
+        let diagnostics = lint(&Config::default(), "* NASA launch notes\n").unwrap();
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+//! Exporting documents to Markdown: CommonMark with the GitHub Flavored
+//! Markdown extensions for tables, task lists, strike-through and
+//! footnotes.
+
+use crate::document::{
+    heading_link_title, Block, Checkbox, Document, Heading, Inline, List, Row, Table,
+    IMAGE_EXTENSIONS,
+};
+
+/// Render a document as Markdown.
+///
+/// A `#+TITLE:` becomes a level 1 heading with the document's headings one
+/// level below it. Heading levels beyond 6 are rendered at level 6.
+pub fn to_markdown(document: &Document) -> String {
+    let mut chunks = vec![];
+    let offset = match document.title() {
+        Some(title) => {
+            chunks.push(format!("# {}", escape(title)));
+            1
+        }
+        None => 0,
+    };
+    for block in &document.blocks {
+        chunks.push(match block {
+            Block::Heading(heading) => render_heading(heading, offset),
+            block => render_block(block),
+        });
+    }
+    for footnote in &document.footnotes {
+        chunks.push(format!(
+            "[^{}]: {}",
+            footnote.name,
+            indent(&render_inlines(&footnote.content), "    ").trim_start()
+        ));
+    }
+    if chunks.is_empty() {
+        return String::new();
+    }
+    chunks.join("\n\n") + "\n"
+}
+
+fn render_heading(heading: &Heading, offset: usize) -> String {
+    let mut line = "#".repeat((heading.level + offset).min(6));
+    line.push(' ');
+    if let Some(custom_id) = &heading.custom_id {
+        line.push_str(&format!("<a id=\"{}\"></a>", custom_id));
+    }
+    if let Some(keyword) = &heading.keyword {
+        line.push_str(keyword);
+        line.push(' ');
+    }
+    if let Some(priority) = heading.priority {
+        line.push_str(&format!("\\[#{}\\] ", priority));
+    }
+    line.push_str(&render_inlines(&heading.title).replace('\n', " "));
+    line.trim_end().to_string()
+}
+
+fn render_block(block: &Block) -> String {
+    match block {
+        Block::Heading(heading) => render_heading(heading, 0),
+        Block::Paragraph(inlines) => render_paragraph(inlines),
+        Block::List(list) => render_list(list),
+        Block::Table(table) => render_table(table),
+        Block::Src { language, code } => fence(language.as_deref().unwrap_or(""), code),
+        Block::Example(text) => fence("", text),
+        Block::Quote(blocks) => {
+            let inner = blocks
+                .iter()
+                .map(render_block)
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            inner
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        ">".to_string()
+                    } else {
+                        format!("> {}", line)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Block::HorizontalRule => "---".to_string(),
+    }
+}
+
+/// Escape the starts of lines that would otherwise begin a heading, list or
+/// quote.
+fn render_paragraph(inlines: &[Inline]) -> String {
+    render_inlines(inlines)
+        .split('\n')
+        .map(|line| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            if line.starts_with('#') || line.starts_with("- ") || line.starts_with("+ ") {
+                format!("\\{}", line)
+            } else if digits > 0 && line[digits..].starts_with(['.', ')']) {
+                format!("{}\\{}", &line[..digits], &line[digits..])
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_list(list: &List) -> String {
+    let mut items = vec![];
+    for (number, item) in list.items.iter().enumerate() {
+        let mut marker = if list.ordered {
+            format!("{}. ", number + 1)
+        } else {
+            "- ".to_string()
+        };
+        let width = marker.len();
+        match item.checkbox {
+            Some(Checkbox::Checked) => marker.push_str("[x] "),
+            Some(Checkbox::Unchecked | Checkbox::Partial) => marker.push_str("[ ] "),
+            None => {}
+        }
+        let mut content = String::new();
+        for (index, block) in item.blocks.iter().enumerate() {
+            if index > 0 {
+                content.push_str(if matches!(block, Block::List(_)) {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            content.push_str(&render_block(block));
+        }
+        items.push(format!(
+            "{}{}",
+            marker,
+            indent(&content, &" ".repeat(width)).trim_start()
+        ));
+    }
+    items.join("\n")
+}
+
+fn render_table(table: &Table) -> String {
+    let (header, body) = table.header_and_body();
+    let (header, body) = match header.first() {
+        Some(header) => (*header, body),
+        None => match body.split_first() {
+            Some((header, body)) => (*header, body.to_vec()),
+            None => return String::new(),
+        },
+    };
+    let columns = body
+        .iter()
+        .map(|row| row.len())
+        .chain([header.len()])
+        .max()
+        .unwrap_or(0);
+    let row = |cells: &Row| {
+        let mut line = "|".to_string();
+        for column in 0..columns {
+            let cell = cells.get(column).map(|cell| render_inlines(cell));
+            line.push_str(&format!(
+                " {} |",
+                cell.unwrap_or_default()
+                    .replace('|', "\\|")
+                    .replace('\n', " ")
+            ));
+        }
+        line
+    };
+    let mut lines = vec![row(header), format!("|{}", " --- |".repeat(columns))];
+    lines.extend(body.into_iter().map(row));
+    lines.join("\n")
+}
+
+/// A fenced code block, with a fence longer than any run of backticks in
+/// the code.
+fn fence(info: &str, code: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(code).max(2) + 1);
+    format!("{}{}\n{}\n{}", fence, info, code, fence)
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+fn indent(text: &str, prefix: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escape characters that Markdown would read as markup.
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if "\\`*_[]<>~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn code_span(code: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(code) + 1);
+    if code.starts_with('`') || code.ends_with('`') {
+        format!("{} {} {}", fence, code, fence)
+    } else {
+        format!("{}{}{}", fence, code, fence)
+    }
+}
+
+fn render_inlines(inlines: &[Inline]) -> String {
+    inlines.iter().map(render_inline).collect()
+}
+
+fn render_inline(inline: &Inline) -> String {
+    match inline {
+        Inline::Text(text) => escape(text),
+        Inline::Bold(inner) => format!("**{}**", render_inlines(inner)),
+        Inline::Italic(inner) => format!("*{}*", render_inlines(inner)),
+        Inline::Underline(inner) => format!("<u>{}</u>", render_inlines(inner)),
+        Inline::StrikeThrough(inner) => format!("~~{}~~", render_inlines(inner)),
+        Inline::Verbatim(code) | Inline::Code(code) => code_span(code),
+        Inline::Link {
+            target,
+            description,
+        } => {
+            // Headings have no anchors to link to.
+            if let Some(title) = heading_link_title(target) {
+                return match description {
+                    Some(description) => render_inlines(description),
+                    None => escape(title),
+                };
+            }
+            let target = target.strip_prefix("file:").unwrap_or(target);
+            let destination = if target.contains([' ', '(', ')']) {
+                format!("<{}>", target)
+            } else {
+                target.to_string()
+            };
+            let lower = target.to_lowercase();
+            match description {
+                None if IMAGE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) => {
+                    format!("![]({})", destination)
+                }
+                None if target.contains("://") => format!("<{}>", target),
+                None => format!("[{}]({})", escape(target), destination),
+                Some(description) => {
+                    format!("[{}]({})", render_inlines(description), destination)
+                }
+            }
+        }
+        Inline::FootnoteReference(name) => format!("[^{}]", name),
+        Inline::Timestamp(timestamp) => escape(&timestamp.to_string()),
+        Inline::LineBreak => "\\\n".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::document::parse_document;
+
+    fn markdown(input: &str) -> String {
+        to_markdown(&parse_document(&Config::default(), input))
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        // Given
+        let input = "#+TITLE: Guide
+Read *this* and /that/, _under_ +gone+ ~a`b~ [[https://example.com][site]].[fn:1]
+
+* TODO Install
+- [X] Download
+- [ ] Unpack
+  1. Open [[file:archive.png]]
+  2. Extract
+
+#+BEGIN_SRC rust
+fn main() {}
+#+END_SRC
+* Notes :noexport:
+Hidden.
+* Reference
+| Option | Meaning |
+|--------+---------|
+| -v | x*y |
+#+BEGIN_QUOTE
+Quoted.
+
+Twice.
+#+END_QUOTE
+
+[fn:1] The footnote.
+";
+
+        // When
+        let output = markdown(input);
+
+        // Then
+        assert_eq!(
+            output,
+            "# Guide
+
+Read **this** and *that*, <u>under</u> ~~gone~~ ``a`b`` [site](https://example.com).[^1]
+
+## TODO Install
+
+- [x] Download
+- [ ] Unpack
+  1. Open ![](archive.png)
+  2. Extract
+
+```rust
+fn main() {}
+```
+
+## Reference
+
+| Option | Meaning |
+| --- | --- |
+| -v | x\\*y |
+
+> Quoted.
+>
+> Twice.
+
+[^1]: The footnote.
+"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            markdown("Cost 5*3 [x]\n#hash <2026-10-17 Sat> *a_b*\n"),
+            "Cost 5\\*3 \\[x\\]\n\\#hash \\<2026-10-17 Sat\\> **a\\_b**\n"
+        );
+    }
+    #[test]
+    fn test_heading_links_are_text() {
+        assert_eq!(
+            markdown("* Usage\nSee [[*Usage]], [[Usage][how *to*]] and [[./usage.md]].\n"),
+            "# Usage\n\nSee Usage, how **to** and [./usage.md](./usage.md).\n"
+        );
+    }
+}
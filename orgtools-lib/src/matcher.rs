@@ -0,0 +1,236 @@
+//! Org's tag and property match syntax, as in `+work-someday|TODO="WAITING"`.
+//!
+//! A match is a list of alternatives separated by `|`. Each alternative is a
+//! list of terms that must all hold: a tag, or a comparison of a property
+//! with a quoted string or a number, such as `OWNER="sam"` or `LEVEL<=2`.
+//! Terms prefixed with `-` must not hold. `TODO`, `LEVEL` and `ITEM` compare
+//! the keyword, level and title of the headline.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::entry::Entry;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matcher {
+    alternatives: Vec<Vec<Term>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Tag(String),
+    Property {
+        name: String,
+        operator: Operator,
+        value: Value,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl FromStr for Matcher {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut alternatives = vec![];
+        for alternative in split_alternatives(s) {
+            let terms = parse_terms(alternative.trim())?;
+            if terms.is_empty() {
+                bail!("Empty match: {}", s);
+            }
+            alternatives.push(terms);
+        }
+        Ok(Matcher { alternatives })
+    }
+}
+
+impl Matcher {
+    /// Whether a headline matches, given its tags including inherited ones.
+    pub fn matches(&self, entry: &Entry, tags: &[String], level: usize) -> bool {
+        self.alternatives.iter().any(|terms| {
+            terms
+                .iter()
+                .all(|term| term.condition.holds(entry, tags, level) != term.negated)
+        })
+    }
+}
+
+impl Condition {
+    fn holds(&self, entry: &Entry, tags: &[String], level: usize) -> bool {
+        match self {
+            Condition::Tag(tag) => tags.contains(tag),
+            Condition::Property {
+                name,
+                operator,
+                value,
+            } => {
+                let actual = match name.as_str() {
+                    "TODO" => entry.keyword().unwrap_or_default().to_string(),
+                    "LEVEL" => level.to_string(),
+                    "ITEM" => entry.headline().title.clone(),
+                    _ => entry.property(name).unwrap_or_default(),
+                };
+                let ordering = match value {
+                    Value::Number(expected) => match actual.trim().parse::<f64>() {
+                        Ok(actual) => actual.partial_cmp(expected),
+                        Err(_) => return false,
+                    },
+                    Value::Text(expected) => Some(actual.as_str().cmp(expected.as_str())),
+                };
+                let Some(ordering) = ordering else {
+                    return false;
+                };
+                match operator {
+                    Operator::Equal => ordering.is_eq(),
+                    Operator::NotEqual => ordering.is_ne(),
+                    Operator::Less => ordering.is_lt(),
+                    Operator::LessOrEqual => ordering.is_le(),
+                    Operator::Greater => ordering.is_gt(),
+                    Operator::GreaterOrEqual => ordering.is_ge(),
+                }
+            }
+        }
+    }
+}
+
+/// Split on `|` outside quoted strings.
+fn split_alternatives(s: &str) -> Vec<&str> {
+    let mut alternatives = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    for (index, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '|' if !quoted => {
+                alternatives.push(&s[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&s[start..]);
+    alternatives
+}
+
+fn parse_terms(s: &str) -> Result<Vec<Term>> {
+    let mut terms = vec![];
+    let mut rest = s;
+    while !rest.is_empty() {
+        let negated = rest.starts_with('-');
+        rest = rest.strip_prefix(['+', '-', '&']).unwrap_or(rest);
+
+        let name_end = rest
+            .find(|c: char| !(c.is_alphanumeric() || "_@#%".contains(c)))
+            .unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        if name.is_empty() {
+            bail!("Invalid match at: {}", rest);
+        }
+        rest = &rest[name_end..];
+
+        let operator = [
+            ("<>", Operator::NotEqual),
+            ("<=", Operator::LessOrEqual),
+            (">=", Operator::GreaterOrEqual),
+            ("=", Operator::Equal),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ]
+        .into_iter()
+        .find(|(symbol, _)| rest.starts_with(symbol));
+        let condition = match operator {
+            None => Condition::Tag(name.to_string()),
+            Some((symbol, operator)) => {
+                rest = &rest[symbol.len()..];
+                let value = if let Some(quoted) = rest.strip_prefix('"') {
+                    let Some(end) = quoted.find('"') else {
+                        bail!("Unterminated string in match: {}", s);
+                    };
+                    rest = &quoted[end + 1..];
+                    Value::Text(quoted[..end].to_string())
+                } else {
+                    let end = rest
+                        .char_indices()
+                        .find(|&(index, c)| {
+                            !(c.is_ascii_digit() || c == '.' || (index == 0 && c == '-'))
+                        })
+                        .map_or(rest.len(), |(index, _)| index);
+                    let number = rest[..end].parse().map_err(|_| {
+                        anyhow!("Expected a number or a quoted string at: {}", rest)
+                    })?;
+                    rest = &rest[end..];
+                    Value::Number(number)
+                };
+                Condition::Property {
+                    name: name.to_uppercase(),
+                    operator,
+                    value,
+                }
+            }
+        };
+        terms.push(Term { negated, condition });
+    }
+    Ok(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn entry(text: &str) -> Entry {
+        Entry::parse(text, &Config::default()).unwrap()
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn test_match_tags() {
+        let matcher: Matcher = "+work-someday|home".parse().unwrap();
+        let task = entry("* TODO Task");
+        assert!(matcher.matches(&task, &tags(&["work"]), 1));
+        assert!(!matcher.matches(&task, &tags(&["work", "someday"]), 1));
+        assert!(matcher.matches(&task, &tags(&["home", "someday"]), 1));
+        assert!(!matcher.matches(&task, &tags(&[]), 1));
+    }
+
+    #[test]
+    fn test_match_properties() {
+        let task = entry("* TODO Call Sam\n:PROPERTIES:\n:SIZE: 3\n:OWNER: me\n:END:\n");
+        let matches = |s: &str| {
+            s.parse::<Matcher>()
+                .unwrap()
+                .matches(&task, &tags(&["work"]), 2)
+        };
+        assert!(matches("TODO=\"TODO\""));
+        assert!(matches("work+SIZE>2+owner=\"me\""));
+        assert!(!matches("SIZE<3"));
+        assert!(matches("LEVEL<=2-ITEM=\"Other\""));
+        assert!(!matches("LEVEL<>2"));
+        assert!("SIZE>big".parse::<Matcher>().is_err());
+        assert!("+".parse::<Matcher>().is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Extracting a subtree to a standalone file and splicing it back.
+//!
+//! The extracted file starts with the source file's `#+` keywords and
+//! `#+ORGTOOLS_` keywords recording where the subtree came from: the source
+//! file, its outline path, its level and a hash of its text. Splicing
+//! refuses to replace a subtree whose text no longer has that hash.
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::config::Config;
+use crate::org::{Org, OrgFile, Section};
+use crate::outline::{headline_level, inside_blocks, relevel};
+
+const SOURCE: &str = "#+ORGTOOLS_SOURCE:";
+const OUTLINE_PATH: &str = "#+ORGTOOLS_OUTLINE_PATH:";
+const LEVEL: &str = "#+ORGTOOLS_LEVEL:";
+const HASH: &str = "#+ORGTOOLS_HASH:";
+
+/// A 64-bit FNV-1a hash of `text`, as 16 hex digits.
+pub fn content_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Whether `line` is a `#+KEY: value` keyword line, rather than the start or
+/// end of a block.
+pub(crate) fn is_keyword_line(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix("#+")
+        .and_then(|rest| rest.split_once(':'))
+        .is_some_and(|(key, _)| !key.is_empty() && !key.contains(char::is_whitespace))
+}
+
+/// The `#+KEY: value` keyword lines before the first headline, leaving out
+/// blocks.
+pub fn file_keywords(input: &str) -> impl Iterator<Item = &str> {
+    let lines = input
+        .lines()
+        .take_while(|line| headline_level(line).is_none())
+        .collect::<Vec<_>>();
+    let inside = inside_blocks(&lines);
+    lines
+        .into_iter()
+        .zip(inside)
+        .filter(|(line, inside)| !inside && is_keyword_line(line))
+        .map(|(line, _)| line)
+}
+
+/// The text of a standalone file holding `section`, with its headlines
+/// starting at level 1.
+///
+/// `source` is recorded as the file to splice the subtree back into.
+pub fn extract_subtree(org_file: &OrgFile, section: &Section, source: &str) -> Result<String> {
+    let outline_path = org_file
+        .outline()
+        .into_iter()
+        .find(|item| item.section.start_byte() == section.start_byte())
+        .map(|item| item.outline_path)
+        .ok_or_else(|| anyhow!("Section is not in the file"))?;
+    let text = section.text();
+
+    let mut output = String::new();
+    for keyword in file_keywords(org_file.input()).filter(|line| !line.starts_with("#+ORGTOOLS_")) {
+        output.push_str(keyword);
+        output.push('\n');
+    }
+    output.push_str(&format!("{} {}\n", SOURCE, source));
+    output.push_str(&format!(
+        "{} {}\n",
+        OUTLINE_PATH,
+        serde_json::to_string(&outline_path)?
+    ));
+    output.push_str(&format!("{} {}\n", LEVEL, section.stars()));
+    output.push_str(&format!("{} {}\n", HASH, content_hash(text)));
+    output.push_str(&relevel(text, 1)?);
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Where an extracted subtree came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractHeader {
+    pub source: String,
+    pub outline_path: Vec<String>,
+    pub level: usize,
+    pub hash: String,
+}
+
+impl ExtractHeader {
+    pub fn parse(extracted: &str) -> Result<ExtractHeader> {
+        let keyword = |name: &str| {
+            file_keywords(extracted)
+                .find_map(|line| line.strip_prefix(name))
+                .map(str::trim)
+                .ok_or_else(|| anyhow!("Missing {} keyword, is this an extracted file?", name))
+        };
+        Ok(ExtractHeader {
+            source: keyword(SOURCE)?.to_string(),
+            outline_path: serde_json::from_str(keyword(OUTLINE_PATH)?)
+                .context("Invalid outline path")?,
+            level: keyword(LEVEL)?.parse().context("Invalid level")?,
+            hash: keyword(HASH)?.to_string(),
+        })
+    }
+}
+
+/// Replace the subtree an extracted file came from in `input` with the
+/// extracted file's headlines, at their original level.
+///
+/// Fails if the subtree is missing or has changed since it was extracted.
+pub fn splice_subtree(config: &Config, input: &str, extracted: &str) -> Result<String> {
+    let header = ExtractHeader::parse(extracted)?;
+    let org_file = Org::from_config(config.clone()).load(input);
+    let section = org_file
+        .find_outline_path(&header.outline_path)
+        .ok_or_else(|| anyhow!("Could not find {}", header.outline_path.join(" / ")))?;
+    if content_hash(section.text()) != header.hash {
+        bail!(
+            "{} has changed since it was extracted",
+            header.outline_path.join(" / ")
+        );
+    }
+
+    let preamble = extracted
+        .split_inclusive('\n')
+        .take_while(|line| headline_level(line).is_none())
+        .collect::<Vec<_>>();
+    if preamble
+        .iter()
+        .any(|line| !(line.trim().is_empty() || line.trim_start().starts_with("#+")))
+    {
+        bail!("Text before the first headline would be lost, move it under a headline");
+    }
+    let body_start = preamble.iter().map(|line| line.len()).sum::<usize>();
+    let mut body = relevel(&extracted[body_start..], header.level)?;
+    if section.text().ends_with('\n') && !body.ends_with('\n') {
+        body.push('\n');
+    }
+
+    let mut output = org_file.output_builder();
+    output.append_to(section.start_byte());
+    output.insert_text(&body);
+    output.skip_to(section.end_byte());
+    Ok(output.append_to_end_of_input())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#+TITLE: Projects
+#+STARTUP: overview
+Intro text.
+* Work
+** TODO Website :web:
+Notes.
+*** Design
+** Reports
+";
+
+    fn extract(input: &str) -> String {
+        let org_file = Org::from_config(Config::default()).load(input);
+        let section = org_file.find_section("Website").unwrap();
+        extract_subtree(&org_file, &section, "projects.org").unwrap()
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_extract_subtree() {
+        let extracted = extract(INPUT);
+        assert_eq!(
+            extracted,
+            format!(
+                "#+TITLE: Projects
+#+STARTUP: overview
+#+ORGTOOLS_SOURCE: projects.org
+#+ORGTOOLS_OUTLINE_PATH: [\"Work\",\"Website\"]
+#+ORGTOOLS_LEVEL: 2
+#+ORGTOOLS_HASH: {}
+* TODO Website :web:
+Notes.
+** Design
+",
+                content_hash("** TODO Website :web:\nNotes.\n*** Design\n")
+            )
+        );
+        assert_eq!(
+            ExtractHeader::parse(&extracted).unwrap().outline_path,
+            vec!["Work".to_string(), "Website".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_file_keywords_leave_out_blocks() {
+        let input = "#+TITLE: Notes\n#+BEGIN_SRC sh :results output\n#+KEY: in block\n#+END_SRC\n#+begin_comment\n* Heading\n#+STARTUP: overview\n";
+        assert_eq!(
+            file_keywords(input).collect::<Vec<_>>(),
+            vec!["#+TITLE: Notes"]
+        );
+    }
+
+    #[test]
+    fn test_splice_subtree() {
+        // Given
+        let edited = extract(INPUT).replace("** Design", "** DONE Design\n** Build");
+
+        // When
+        let output = splice_subtree(&Config::default(), INPUT, &edited).unwrap();
+
+        // Then
+        assert_eq!(
+            output,
+            INPUT.replace("*** Design\n", "*** DONE Design\n*** Build\n")
+        );
+    }
+
+    #[test]
+    fn test_splice_subtree_refuses_changed_source() {
+        let edited = extract(INPUT);
+        let changed = INPUT.replace("Notes.", "Other notes.");
+        let err = splice_subtree(&Config::default(), &changed, &edited).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Work / Website has changed since it was extracted"
+        );
+        assert!(splice_subtree(&Config::default(), INPUT, "* Website\n").is_err());
+        let with_text = edited.replace("* TODO Website", "Loose text\n* TODO Website");
+        assert!(splice_subtree(&Config::default(), INPUT, &with_text).is_err());
+    }
+}
@@ -5,6 +5,7 @@ use std::{cell::RefCell, rc::Rc};
 use tree_sitter::{Node, Tree};
 
 use crate::config::Config;
+use crate::entry::Entry;
 use crate::utils::get_parser;
 
 pub struct Org {
@@ -69,6 +70,11 @@ impl<'a> OrgFile<'a> {
         }
     }
 
+    /// The text the file was loaded from.
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
     pub fn subsections(&'a self) -> Vec<Section<'a>> {
         get_subsections(&self.config, self.input, self.root)
     }
@@ -77,11 +83,96 @@ impl<'a> OrgFile<'a> {
         find_section(&self.config, self.input, self.root, search)
     }
 
+    /// Find a section by its outline path, the headline texts from the top level down.
+    pub fn find_outline_path(&'a self, path: &[String]) -> Option<Section<'a>> {
+        let mut sections = self.subsections();
+        let mut found = None;
+        for title in path {
+            let section = sections
+                .into_iter()
+                .find(|section| section.headline_text() == Some(title.as_str()))?;
+            sections = section.subsections();
+            found = Some(section);
+        }
+        found
+    }
+
+    /// The sections at the same level under the same parent as `section`, including itself.
+    pub fn siblings(&'a self, section: &Section) -> Vec<Section<'a>> {
+        fn find<'a>(sections: Vec<Section<'a>>, start_byte: usize) -> Option<Vec<Section<'a>>> {
+            if sections
+                .iter()
+                .any(|section| section.start_byte() == start_byte)
+            {
+                return Some(sections);
+            }
+            sections
+                .into_iter()
+                .filter(|section| (section.start_byte()..section.end_byte()).contains(&start_byte))
+                .find_map(|section| find(section.subsections(), start_byte))
+        }
+        find(self.subsections(), section.start_byte()).unwrap_or_default()
+    }
+
+    /// Every section in document order, with its outline path and tags.
+    pub fn outline(&'a self) -> Vec<OutlineSection<'a>> {
+        fn visit<'a>(
+            section: Section<'a>,
+            outline_path: &mut Vec<String>,
+            tags: &mut Vec<String>,
+            sections: &mut Vec<OutlineSection<'a>>,
+        ) {
+            let entry = section.entry();
+            let headline = entry.headline();
+            outline_path.push(headline.title.clone());
+            let inherited = tags.len();
+            for tag in &headline.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+
+            let subsections = section.subsections();
+            sections.push(OutlineSection {
+                section,
+                outline_path: outline_path.clone(),
+                tags: tags.clone(),
+            });
+            for subsection in subsections {
+                visit(subsection, outline_path, tags, sections);
+            }
+
+            tags.truncate(inherited);
+            outline_path.pop();
+        }
+
+        let mut sections = vec![];
+        for section in self.subsections() {
+            visit(section, &mut vec![], &mut vec![], &mut sections);
+        }
+        sections
+    }
+
     pub fn output_builder(&self) -> OutputBuilder {
         OutputBuilder::new(self.input)
     }
 }
 
+/// A section with its place in the outline.
+pub struct OutlineSection<'a> {
+    pub section: Section<'a>,
+    /// The headline titles from the top level down to this section.
+    pub outline_path: Vec<String>,
+    /// The section's tags, including inherited ones.
+    pub tags: Vec<String>,
+}
+
+impl OutlineSection<'_> {
+    pub fn level(&self) -> usize {
+        self.outline_path.len()
+    }
+}
+
 pub struct Section<'a> {
     config: &'a Config,
     input: &'a str,
@@ -126,6 +217,27 @@ impl<'a> Section<'a> {
         self.node.end_byte()
     }
 
+    /// The text of the whole subtree.
+    pub fn text(&self) -> &'a str {
+        &self.input[self.start_byte()..self.end_byte()]
+    }
+
+    /// The end of the section's own content, where its first subsection starts.
+    pub fn content_end_byte(&self) -> usize {
+        self.subsections()
+            .first()
+            .map_or(self.end_byte(), |subsection| subsection.start_byte())
+    }
+
+    /// Parse the section's entry: its headline, planning line, drawers and body.
+    pub fn entry(&self) -> Entry {
+        Entry::parse(
+            &self.input[self.start_byte()..self.content_end_byte()],
+            self.config,
+        )
+        .expect("Section starts with a headline")
+    }
+
     pub fn node(&self) -> Node<'a> {
         self.node
     }
@@ -251,6 +363,13 @@ impl<'a> OutputBuilder<'a> {
     pub fn insert_text(&mut self, text: &str) {
         self.output.push_str(text);
     }
+
+    /// Insert a newline unless the output is empty or already ends with one.
+    pub fn ensure_newline(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.output.push('\n');
+        }
+    }
 }
 
 #[cfg(test)]
@@ -349,4 +468,76 @@ mod tests {
         // Then
         assert_eq!(section.headline_text_full().unwrap(), "Headline 1");
     }
+
+    #[test]
+    fn test_section_entry() {
+        // Given
+        let input = "* TODO Headline 1\nSCHEDULED: <2026-10-17 Sat>\nBody\n** Headline 1.1\n";
+
+        // When
+        let org = Org::new().load(input);
+        let section = &org.subsections()[0];
+        let entry = section.entry();
+
+        // Then
+        assert_eq!(section.content_end_byte(), input.find("** ").unwrap());
+        assert_eq!(entry.keyword(), Some("TODO"));
+        assert!(entry.planning().scheduled.is_some());
+        assert_eq!(entry.body(), "Body\n");
+    }
+
+    #[test]
+    fn test_siblings() {
+        // Given
+        let input = "* Headline 1\n** Headline 1.1\n** Headline 1.2\n* Headline 2\n";
+
+        // When
+        let org = Org::new().load(input);
+        let section = org.find_section("Headline 1.2").unwrap();
+        let siblings = org.siblings(&section);
+
+        // Then
+        let headlines = siblings
+            .iter()
+            .filter_map(|section| section.headline_text())
+            .collect::<Vec<_>>();
+        assert_eq!(headlines, vec!["Headline 1.1", "Headline 1.2"]);
+        assert_eq!(section.text(), "** Headline 1.2\n");
+    }
+
+    #[test]
+    fn test_outline() {
+        let input = "* Work :work:\n** Report :urgent:work:\n* Home\n";
+        let org = Org::new().load(input);
+        let outline = org
+            .outline()
+            .into_iter()
+            .map(|item| (item.outline_path.join(" / "), item.tags.join(":")))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outline,
+            vec![
+                ("Work".to_string(), "work".to_string()),
+                ("Work / Report".to_string(), "work:urgent".to_string()),
+                ("Home".to_string(), "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_outline_path() {
+        // Given
+        let input = "* Projects\n** Work\n* Areas\n** Home\n** Work\n";
+
+        // When
+        let org = Org::new().load(input);
+        let path = vec![String::from("Areas"), String::from("Work")];
+        let section = org.find_outline_path(&path).unwrap();
+
+        // Then
+        assert_eq!(section.start_byte(), input.rfind("** Work").unwrap());
+        assert!(org
+            .find_outline_path(&[String::from("Areas"), String::from("Play")])
+            .is_none());
+    }
 }
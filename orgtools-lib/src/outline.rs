@@ -0,0 +1,129 @@
+//! Operations on the outline structure of Org text.
+
+use anyhow::{bail, Result};
+
+/// Returns the level of a headline line, or `None` if it is not a headline.
+pub fn headline_level(line: &str) -> Option<usize> {
+    let stars = line.bytes().take_while(|&b| b == b'*').count();
+    match line.as_bytes().get(stars) {
+        Some(b' ') | Some(b'\t') if stars > 0 => Some(stars),
+        _ => None,
+    }
+}
+
+/// The name of the block a `#+BEGIN_NAME` line starts, in upper case.
+fn block_name(line: &str) -> Option<String> {
+    let line = line.trim();
+    let rest = line
+        .get(..8)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("#+BEGIN_"))
+        .map(|_| &line[8..])?;
+    let name = rest.split_whitespace().next()?;
+    Some(name.to_uppercase())
+}
+
+fn is_block_end(line: &str, name: &str) -> bool {
+    let line = line.trim();
+    line.len() == name.len() + 6
+        && line[..6].eq_ignore_ascii_case("#+END_")
+        && line[6..].eq_ignore_ascii_case(name)
+}
+
+/// Whether each line is part of a `#+BEGIN_...`/`#+END_...` block, such as
+/// a src or example block, whose lines starting with `*` are not headlines.
+///
+/// A block without an end line isn't a block.
+pub fn inside_blocks(lines: &[&str]) -> Vec<bool> {
+    let mut inside = vec![false; lines.len()];
+    let mut index = 0;
+    while index < lines.len() {
+        let end = block_name(lines[index])
+            .and_then(|name| (index + 1..lines.len()).find(|&end| is_block_end(lines[end], &name)));
+        match end {
+            Some(end) => {
+                inside[index..=end].fill(true);
+                index = end + 1;
+            }
+            None => index += 1,
+        }
+    }
+    inside
+}
+
+/// Change the level of every headline in `text` by `delta`, leaving the
+/// contents of blocks alone.
+///
+/// Fails if any headline would end up above level 1.
+pub fn shift_levels(text: &str, delta: isize) -> Result<String> {
+    let mut output = String::with_capacity(text.len());
+    let lines = text.split_inclusive('\n').collect::<Vec<_>>();
+    for (line, inside) in lines.iter().zip(inside_blocks(&lines)) {
+        match headline_level(line).filter(|_| !inside) {
+            Some(level) => {
+                let new_level = level as isize + delta;
+                if new_level < 1 {
+                    bail!("Cannot change headline level below 1: {}", line.trim_end());
+                }
+                output.push_str(&"*".repeat(new_level as usize));
+                output.push_str(&line[level..]);
+            }
+            None => output.push_str(line),
+        }
+    }
+    Ok(output)
+}
+
+/// Shift the headlines in `text` so that the first one is at `level`.
+pub fn relevel(text: &str, level: usize) -> Result<String> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let current = lines
+        .iter()
+        .zip(inside_blocks(&lines))
+        .find_map(|(line, inside)| headline_level(line).filter(|_| !inside));
+    let Some(current) = current else {
+        bail!("Text does not contain a headline");
+    };
+    shift_levels(text, level as isize - current as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headline_level() {
+        assert_eq!(headline_level("** Headline"), Some(2));
+        assert_eq!(headline_level("* "), Some(1));
+        assert_eq!(headline_level("*bold* text"), None);
+        assert_eq!(headline_level("Text"), None);
+    }
+
+    #[test]
+    fn test_shift_levels() {
+        let text = "* Headline\nBody *with* stars\n** Child\n";
+        assert_eq!(
+            shift_levels(text, 1).unwrap(),
+            "** Headline\nBody *with* stars\n*** Child\n"
+        );
+        assert!(shift_levels(text, -1).is_err());
+    }
+
+    #[test]
+    fn test_shift_levels_skips_blocks() {
+        let text = "* Notes\n#+BEGIN_SRC org\n* Example\n#+end_src\n** Child\n#+BEGIN_EXAMPLE\n* Unterminated\n";
+        assert_eq!(
+            shift_levels(text, 1).unwrap(),
+            "** Notes\n#+BEGIN_SRC org\n* Example\n#+end_src\n*** Child\n#+BEGIN_EXAMPLE\n** Unterminated\n"
+        );
+        assert_eq!(
+            relevel("#+BEGIN_QUOTE\n* Quoted\n#+END_QUOTE\n** Headline\n", 1).unwrap(),
+            "#+BEGIN_QUOTE\n* Quoted\n#+END_QUOTE\n* Headline\n"
+        );
+    }
+
+    #[test]
+    fn test_relevel() {
+        let text = "*** Headline\n**** Child";
+        assert_eq!(relevel(text, 1).unwrap(), "* Headline\n** Child");
+    }
+}
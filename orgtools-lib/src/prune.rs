@@ -0,0 +1,386 @@
+//! Removing finished entries according to a prune policy.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::org::{Keyword, Org, Section};
+use crate::outline::shift_levels;
+
+/// Which finished entries are removed, configured as the `[prune]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PrunePolicy {
+    /// Only remove entries closed more than this many days ago.
+    ///
+    /// Entries without a `CLOSED:` timestamp are kept when this is set.
+    pub older_than_days: Option<i64>,
+    /// What to do with a finished entry that has open descendants.
+    pub open_descendants: OpenDescendants,
+    /// Entries with any of these tags are never removed.
+    pub keep_tags: Vec<String>,
+    /// Entries with any of these properties are never removed.
+    pub keep_properties: Vec<String>,
+}
+
+impl Default for PrunePolicy {
+    fn default() -> Self {
+        PrunePolicy {
+            older_than_days: None,
+            open_descendants: OpenDescendants::Keep,
+            keep_tags: vec!["keep".to_string()],
+            keep_properties: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenDescendants {
+    /// Keep the finished entry and everything under it.
+    Keep,
+    /// Remove the finished entry and move its subtrees, other than finished
+    /// ones without open entries, up a level.
+    Promote,
+    /// Remove the finished entry along with its open descendants.
+    Prune,
+}
+
+impl FromStr for OpenDescendants {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "keep" => Ok(OpenDescendants::Keep),
+            "promote" => Ok(OpenDescendants::Promote),
+            "prune" => Ok(OpenDescendants::Prune),
+            _ => bail!("Invalid value, expected keep, promote or prune: {}", s),
+        }
+    }
+}
+
+/// What happened to a finished entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PruneAction {
+    Removed,
+    /// Removed, with this many subtrees moved up a level.
+    Promoted(usize),
+    Kept(KeepReason),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeepReason {
+    Tag(String),
+    Property(String),
+    NotClosed,
+    TooRecent(NaiveDate),
+    OpenDescendants(usize),
+}
+
+/// A line of the prune report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruneReportItem {
+    /// The headlines from the top level down to the finished entry.
+    pub outline_path: Vec<String>,
+    pub action: PruneAction,
+}
+
+impl fmt::Display for PruneReportItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.outline_path.join("/");
+        match &self.action {
+            PruneAction::Removed => write!(f, "removed  {}", path),
+            PruneAction::Promoted(count) => {
+                write!(f, "removed  {} (promoted {} subtrees)", path, count)
+            }
+            PruneAction::Kept(reason) => {
+                write!(f, "kept     {}: ", path)?;
+                match reason {
+                    KeepReason::Tag(tag) => write!(f, "tagged :{}:", tag),
+                    KeepReason::Property(name) => write!(f, "has property {}", name),
+                    KeepReason::NotClosed => write!(f, "no CLOSED timestamp"),
+                    KeepReason::TooRecent(date) => write!(f, "closed recently on {}", date),
+                    KeepReason::OpenDescendants(count) => write!(f, "{} open descendants", count),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Pruned {
+    pub output: String,
+    /// Every finished entry that was considered, in file order.
+    pub report: Vec<PruneReportItem>,
+}
+
+/// Remove finished entries from the input according to `config.prune`.
+pub fn prune(config: &Config, input: &str, today: NaiveDate) -> Result<Pruned> {
+    let org = Org::from_config(config.clone()).load(input);
+    let pruner = Pruner {
+        policy: &config.prune,
+        input,
+        tags: org
+            .outline()
+            .into_iter()
+            .map(|item| (item.section.start_byte(), item.tags))
+            .collect(),
+        today,
+    };
+
+    let mut output = org.output_builder();
+    let mut report = vec![];
+    for section in org.subsections() {
+        output.append_up_to_section(&section);
+        output.insert_text(&pruner.pruned_text(&section, &mut vec![], &mut report)?);
+        output.skip_to(section.end_byte());
+    }
+
+    Ok(Pruned {
+        output: output.append_to_end_of_input(),
+        report,
+    })
+}
+
+struct Pruner<'a> {
+    policy: &'a PrunePolicy,
+    input: &'a str,
+    /// The tags of each section, including inherited ones, by start byte.
+    tags: HashMap<usize, Vec<String>>,
+    today: NaiveDate,
+}
+
+impl Pruner<'_> {
+    /// The text of a section once the finished entries in it are removed.
+    fn pruned_text(
+        &self,
+        section: &Section,
+        path: &mut Vec<String>,
+        report: &mut Vec<PruneReportItem>,
+    ) -> Result<String> {
+        path.push(section.entry().headline().title.clone());
+        let action = match section.keyword() {
+            Keyword::Finished(_) => Some(self.action(section)),
+            _ => None,
+        };
+        if let Some(action) = &action {
+            report.push(PruneReportItem {
+                outline_path: path.clone(),
+                action: action.clone(),
+            });
+        }
+
+        let text = match action {
+            Some(PruneAction::Removed) => String::new(),
+            Some(PruneAction::Promoted(_)) => {
+                let mut text = String::new();
+                for subsection in section.subsections() {
+                    text.push_str(&self.pruned_text(&subsection, path, report)?);
+                }
+                shift_levels(&text, -1)?
+            }
+            Some(PruneAction::Kept(_)) | None => {
+                let mut text =
+                    self.input[section.start_byte()..section.content_end_byte()].to_string();
+                for subsection in section.subsections() {
+                    text.push_str(&self.pruned_text(&subsection, path, report)?);
+                }
+                text
+            }
+        };
+        path.pop();
+        Ok(text)
+    }
+
+    /// Decide what to do with a finished section.
+    fn action(&self, section: &Section) -> PruneAction {
+        let entry = section.entry();
+        if let Some(tag) = self
+            .tags
+            .get(&section.start_byte())
+            .into_iter()
+            .flatten()
+            .find(|tag| self.policy.keep_tags.contains(tag))
+        {
+            return PruneAction::Kept(KeepReason::Tag(tag.clone()));
+        }
+        if let Some(name) = self
+            .policy
+            .keep_properties
+            .iter()
+            .find(|name| entry.property(name).is_some())
+        {
+            return PruneAction::Kept(KeepReason::Property(name.clone()));
+        }
+        if let Some(days) = self.policy.older_than_days {
+            match &entry.planning().closed {
+                None => return PruneAction::Kept(KeepReason::NotClosed),
+                Some(closed) if (self.today - closed.date).num_days() <= days => {
+                    return PruneAction::Kept(KeepReason::TooRecent(closed.date));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let open = section.subsections().iter().map(count_open).sum::<usize>();
+        match self.policy.open_descendants {
+            _ if open == 0 => PruneAction::Removed,
+            OpenDescendants::Keep => PruneAction::Kept(KeepReason::OpenDescendants(open)),
+            OpenDescendants::Promote => PruneAction::Promoted(
+                section
+                    .subsections()
+                    .iter()
+                    .filter(|subsection| {
+                        !matches!(subsection.keyword(), Keyword::Finished(_))
+                            || self.action(subsection) != PruneAction::Removed
+                    })
+                    .count(),
+            ),
+            OpenDescendants::Prune => PruneAction::Removed,
+        }
+    }
+}
+
+/// The number of unfinished entries in a section, including itself.
+fn count_open(section: &Section) -> usize {
+    let own = match section.keyword() {
+        Keyword::Unfinished(_) => 1,
+        _ => 0,
+    };
+    own + section.subsections().iter().map(count_open).sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+    }
+
+    fn prune_with(policy: PrunePolicy, input: &str) -> Pruned {
+        let config = Config {
+            prune: policy,
+            ..Config::default()
+        };
+        prune(&config, input, today()).unwrap()
+    }
+
+    #[test]
+    fn test_keep_open_descendants() {
+        // Given
+        let input = "* DONE Project\n** TODO Follow up\n* DONE Chore\n** Notes\n";
+
+        // When
+        let pruned = prune_with(PrunePolicy::default(), input);
+
+        // Then
+        assert_eq!(pruned.output, "* DONE Project\n** TODO Follow up\n");
+        assert_eq!(
+            pruned.report[0].action,
+            PruneAction::Kept(KeepReason::OpenDescendants(1))
+        );
+        assert_eq!(pruned.report[1].to_string(), "removed  Chore");
+    }
+
+    #[test]
+    fn test_promote_open_descendants() {
+        // Given
+        let input = "* Projects\n** DONE House\n*** TODO Paint\n**** Buy brushes\n*** DONE Sand\n*** DONE Receipt :keep:\n*** Notes\n";
+        let policy = PrunePolicy {
+            open_descendants: OpenDescendants::Promote,
+            keep_tags: vec!["keep".to_string()],
+            ..PrunePolicy::default()
+        };
+
+        // When
+        let pruned = prune_with(policy, input);
+
+        // Then
+        assert_eq!(
+            pruned.output,
+            "* Projects\n** TODO Paint\n*** Buy brushes\n** DONE Receipt :keep:\n** Notes\n"
+        );
+        assert_eq!(
+            pruned.report[0].to_string(),
+            "removed  Projects/House (promoted 3 subtrees)"
+        );
+    }
+
+    #[test]
+    fn test_prune_open_descendants() {
+        let policy = PrunePolicy {
+            open_descendants: OpenDescendants::Prune,
+            ..PrunePolicy::default()
+        };
+        let pruned = prune_with(policy, "* DONE Project\n** TODO Follow up\n* Other\n");
+        assert_eq!(pruned.output, "* Other\n");
+    }
+
+    #[test]
+    fn test_prune_by_age() {
+        // Given
+        let input = "* DONE Old\nCLOSED: [2026-09-01 Tue]\n* DONE Recent\nCLOSED: [2026-10-10 Sat 09:00]\n* DONE Unknown\n";
+        let policy = PrunePolicy {
+            older_than_days: Some(30),
+            ..PrunePolicy::default()
+        };
+
+        // When
+        let pruned = prune_with(policy, input);
+
+        // Then
+        assert_eq!(
+            pruned.output,
+            "* DONE Recent\nCLOSED: [2026-10-10 Sat 09:00]\n* DONE Unknown\n"
+        );
+        let report = pruned
+            .report
+            .iter()
+            .map(|item| item.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            report,
+            vec![
+                "removed  Old",
+                "kept     Recent: closed recently on 2026-10-10",
+                "kept     Unknown: no CLOSED timestamp",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_tag_and_property() {
+        // Given
+        let input = "* DONE Reference :keep:\n* DONE Receipt\n:PROPERTIES:\n:RECEIPT: 42\n:END:\n* DONE Done\n";
+        let policy = PrunePolicy {
+            keep_properties: vec!["RECEIPT".to_string()],
+            ..PrunePolicy::default()
+        };
+
+        // When
+        let pruned = prune_with(policy, input);
+
+        // Then
+        assert_eq!(
+            pruned.output,
+            "* DONE Reference :keep:\n* DONE Receipt\n:PROPERTIES:\n:RECEIPT: 42\n:END:\n"
+        );
+    }
+
+    #[test]
+    fn test_keep_inherited_tag() {
+        let input = "* Reference :keep:\n** DONE Paper\n* DONE Chore\n";
+        let pruned = prune_with(PrunePolicy::default(), input);
+        assert_eq!(pruned.output, "* Reference :keep:\n** DONE Paper\n");
+        assert_eq!(
+            pruned.report[0].to_string(),
+            "kept     Reference/Paper: tagged :keep:"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Rendering reports as Org tables, CSV or JSON.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+
+/// How a report is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => bail!("Invalid format, expected table, csv or json: {}", s),
+        }
+    }
+}
+
+/// The cells of a report, with an optional total row.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total: Option<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(header: &[&str]) -> Self {
+        Table {
+            header: header.iter().map(|cell| cell.to_string()).collect(),
+            ..Table::default()
+        }
+    }
+
+    /// Render as an aligned Org table, with the total row below a rule.
+    ///
+    /// The first column is aligned left and the others right.
+    pub fn to_org(&self) -> String {
+        let all_rows = || {
+            std::iter::once(&self.header)
+                .chain(&self.rows)
+                .chain(&self.total)
+        };
+        let columns = all_rows().map(Vec::len).max().unwrap_or(0);
+        let widths = (0..columns)
+            .map(|column| {
+                all_rows()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        let render = |row: &Vec<String>| {
+            let cells = widths
+                .iter()
+                .enumerate()
+                .map(|(column, &width)| {
+                    let cell = row.get(column).map_or("", String::as_str);
+                    match column {
+                        0 => format!(" {:<width$} ", cell),
+                        _ => format!(" {:>width$} ", cell),
+                    }
+                })
+                .collect::<Vec<_>>();
+            format!("|{}|\n", cells.join("|"))
+        };
+        let rule = format!(
+            "|{}|\n",
+            widths
+                .iter()
+                .map(|width| "-".repeat(width + 2))
+                .collect::<Vec<_>>()
+                .join("+")
+        );
+
+        let mut output = render(&self.header);
+        output.push_str(&rule);
+        for row in &self.rows {
+            output.push_str(&render(row));
+        }
+        if let Some(total) = &self.total {
+            output.push_str(&rule);
+            output.push_str(&render(total));
+        }
+        output
+    }
+
+    /// Render as CSV, with the total as the last row.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::new();
+        for row in std::iter::once(&self.header)
+            .chain(&self.rows)
+            .chain(&self.total)
+        {
+            let fields = row.iter().map(|field| csv_field(field)).collect::<Vec<_>>();
+            output.push_str(&fields.join(","));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table() {
+        let mut table = Table::new(&["Name", "Time"]);
+        table
+            .rows
+            .push(vec!["Report, final".to_string(), "1:30".to_string()]);
+        table.total = Some(vec!["Total".to_string(), "10:30".to_string()]);
+
+        assert_eq!(
+            table.to_org(),
+            "| Name          |  Time |\n|---------------+-------|\n| Report, final |  1:30 |\n|---------------+-------|\n| Total         | 10:30 |\n"
+        );
+        assert_eq!(
+            table.to_csv(),
+            "Name,Time\n\"Report, final\",1:30\nTotal,10:30\n"
+        );
+    }
+}
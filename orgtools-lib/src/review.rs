@@ -0,0 +1,409 @@
+//! Weekly review reports: the next action of each project, stuck projects
+//! and the items waiting on someone else.
+//!
+//! A project is an open heading with subtasks, that is children with a TODO
+//! keyword. Subprojects are reviewed on their own rather than as part of the
+//! projects they are in.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::org::{Keyword, Org, Section};
+use crate::report::{OutputFormat, Table};
+use crate::state::StateChange;
+use crate::timestamp::Timestamp;
+
+/// What counts as a next action or a waiting item, configured as the
+/// `[review]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ReviewOptions {
+    /// Keywords of tasks that can be worked on. A project without any is
+    /// stuck.
+    pub next_action_keywords: Vec<String>,
+    /// Projects with any of these tags, including inherited ones, are never
+    /// stuck, as in `someday`.
+    pub unstuck_tags: Vec<String>,
+    /// Keywords of tasks waiting on someone else.
+    pub waiting_keywords: Vec<String>,
+}
+
+impl Default for ReviewOptions {
+    fn default() -> Self {
+        ReviewOptions {
+            next_action_keywords: vec!["TODO".to_string(), "DOING".to_string()],
+            unstuck_tags: vec!["someday".to_string()],
+            waiting_keywords: vec!["WAITING".to_string(), "BLOCKED".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NextAction {
+    pub file: String,
+    pub project: Vec<String>,
+    pub keyword: String,
+    pub action: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StuckProject {
+    pub file: String,
+    pub project: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WaitingItem {
+    pub file: String,
+    pub outline_path: Vec<String>,
+    pub keyword: String,
+    /// The `:DELEGATED_TO:` property.
+    pub delegated_to: Option<String>,
+    /// When the item entered its state, from the `:LOGBOOK:` drawer, or
+    /// when it was created.
+    pub since: Option<NaiveDate>,
+    pub age_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Review {
+    pub next_actions: Vec<NextAction>,
+    pub stuck_projects: Vec<StuckProject>,
+    pub waiting: Vec<WaitingItem>,
+}
+
+/// The review of a file, with the ages of waiting items on `today`.
+pub fn review(config: &Config, input: &str, file: &str, today: NaiveDate) -> Review {
+    let options = &config.review;
+    let org = Org::from_config(config.clone()).load(input);
+    let mut review = Review::default();
+    for item in org.outline() {
+        let keyword = item.section.keyword();
+        if is_project(&item.section) {
+            match next_action(&item.section, options) {
+                Some((keyword, action)) => review.next_actions.push(NextAction {
+                    file: file.to_string(),
+                    project: item.outline_path.clone(),
+                    keyword,
+                    action,
+                }),
+                None if !has_subprojects(&item.section)
+                    && !item
+                        .tags
+                        .iter()
+                        .any(|tag| options.unstuck_tags.contains(tag)) =>
+                {
+                    review.stuck_projects.push(StuckProject {
+                        file: file.to_string(),
+                        project: item.outline_path.clone(),
+                    })
+                }
+                None => {}
+            }
+        }
+
+        let Keyword::Unfinished(keyword) = keyword else {
+            continue;
+        };
+        if !options.waiting_keywords.contains(&keyword) {
+            continue;
+        }
+        let entry = item.section.entry();
+        let since = entry
+            .logbook()
+            .iter()
+            .filter_map(|line| StateChange::parse(line))
+            .filter(|change| change.to.as_deref() == Some(keyword.as_str()))
+            .map(|change| change.time.date())
+            .max()
+            .or_else(|| {
+                entry
+                    .property("CREATED")
+                    .and_then(|created| Timestamp::parse_prefix(&created).ok())
+                    .map(|(created, _)| created.date)
+            });
+        review.waiting.push(WaitingItem {
+            file: file.to_string(),
+            outline_path: item.outline_path.clone(),
+            keyword,
+            delegated_to: entry.property("DELEGATED_TO"),
+            since,
+            age_days: since.map(|since| (today - since).num_days()),
+        });
+    }
+    review
+}
+
+/// Whether a section is an open heading with children that have a TODO
+/// keyword.
+fn is_project(section: &Section) -> bool {
+    !matches!(section.keyword(), Keyword::Finished(_))
+        && section
+            .subsections()
+            .iter()
+            .any(|subsection| subsection.keyword() != Keyword::None)
+}
+
+/// Whether any section under a project is a project itself.
+fn has_subprojects(project: &Section) -> bool {
+    project
+        .subsections()
+        .iter()
+        .any(|subsection| is_project(subsection) || has_subprojects(subsection))
+}
+
+/// The keyword and title of the first next action under a project,
+/// skipping subprojects, which have their own, and finished subtrees.
+fn next_action(project: &Section, options: &ReviewOptions) -> Option<(String, String)> {
+    for subsection in project.subsections() {
+        if is_project(&subsection) {
+            continue;
+        }
+        match subsection.keyword() {
+            Keyword::Finished(_) => continue,
+            Keyword::Unfinished(keyword) if options.next_action_keywords.contains(&keyword) => {
+                let title = subsection.headline_text().unwrap_or_default().to_string();
+                return Some((keyword, title));
+            }
+            _ => {}
+        }
+        if let Some(action) = next_action(&subsection, options) {
+            return Some(action);
+        }
+    }
+    None
+}
+
+impl Review {
+    pub fn extend(&mut self, other: Review) {
+        self.next_actions.extend(other.next_actions);
+        self.stuck_projects.extend(other.stuck_projects);
+        self.waiting.extend(other.waiting);
+    }
+
+    /// Render as a table per report under Org headings, as a single CSV
+    /// table with a `report` column, or as JSON.
+    pub fn render(&self, format: OutputFormat) -> Result<String> {
+        Ok(match format {
+            OutputFormat::Table => {
+                let mut next_actions = Table::new(&["Project", "Next action", "File"]);
+                for action in &self.next_actions {
+                    next_actions.rows.push(vec![
+                        action.project.join(" / "),
+                        format!("{} {}", action.keyword, action.action),
+                        action.file.clone(),
+                    ]);
+                }
+                let mut stuck_projects = Table::new(&["Project", "File"]);
+                for project in &self.stuck_projects {
+                    stuck_projects
+                        .rows
+                        .push(vec![project.project.join(" / "), project.file.clone()]);
+                }
+                let mut waiting = Table::new(&["Task", "Delegated to", "Days", "File"]);
+                for item in &self.waiting {
+                    waiting.rows.push(vec![
+                        format!("{} {}", item.keyword, item.outline_path.join(" / ")),
+                        item.delegated_to.clone().unwrap_or_default(),
+                        item.age_days.map(|age| age.to_string()).unwrap_or_default(),
+                        item.file.clone(),
+                    ]);
+                }
+                format!(
+                    "* Next actions\n{}\n* Stuck projects\n{}\n* Waiting for\n{}",
+                    next_actions.to_org(),
+                    stuck_projects.to_org(),
+                    waiting.to_org()
+                )
+            }
+            OutputFormat::Csv => {
+                let mut table = Table::new(&[
+                    "report",
+                    "file",
+                    "heading",
+                    "keyword",
+                    "delegated_to",
+                    "since",
+                ]);
+                for action in &self.next_actions {
+                    let mut heading = action.project.clone();
+                    heading.push(action.action.clone());
+                    table.rows.push(vec![
+                        "next action".to_string(),
+                        action.file.clone(),
+                        heading.join(" / "),
+                        action.keyword.clone(),
+                        String::new(),
+                        String::new(),
+                    ]);
+                }
+                for project in &self.stuck_projects {
+                    table.rows.push(vec![
+                        "stuck project".to_string(),
+                        project.file.clone(),
+                        project.project.join(" / "),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                    ]);
+                }
+                for item in &self.waiting {
+                    table.rows.push(vec![
+                        "waiting".to_string(),
+                        item.file.clone(),
+                        item.outline_path.join(" / "),
+                        item.keyword.clone(),
+                        item.delegated_to.clone().unwrap_or_default(),
+                        item.since.map(|d| d.to_string()).unwrap_or_default(),
+                    ]);
+                }
+                table.to_csv()
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "* Website
+** DONE Pick a host
+** Design
+*** TODO Sketch pages
+** TODO Launch
+* Garden :home:
+** DONE Buy seeds
+** WAITING Delivery of soil
+:PROPERTIES:
+:DELEGATED_TO: Garden centre
+:CREATED: [2026-10-01 Thu]
+:END:
+:LOGBOOK:
+- State \"WAITING\" from \"TODO\" [2026-10-11 Sun 09:00]
+:END:
+* Novel :someday:
+** DONE Outline
+* DONE Taxes
+** DONE File return
+";
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.keywords_unfinished.push("WAITING".to_string());
+        config
+    }
+
+    #[test]
+    fn test_review() {
+        // Given
+        let today = "2026-10-18".parse().unwrap();
+
+        // When
+        let review = review(&config(), INPUT, "projects.org", today);
+
+        // Then
+        assert_eq!(
+            review
+                .next_actions
+                .iter()
+                .map(|action| (action.project.join(" / "), action.action.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Website".to_string(), "Launch"),
+                ("Website / Design".to_string(), "Sketch pages"),
+            ]
+        );
+        assert_eq!(
+            review.stuck_projects,
+            vec![StuckProject {
+                file: "projects.org".to_string(),
+                project: vec!["Garden".to_string()],
+            }]
+        );
+        assert_eq!(
+            review.waiting,
+            vec![WaitingItem {
+                file: "projects.org".to_string(),
+                outline_path: vec!["Garden".to_string(), "Delivery of soil".to_string()],
+                keyword: "WAITING".to_string(),
+                delegated_to: Some("Garden centre".to_string()),
+                since: Some("2026-10-11".parse().unwrap()),
+                age_days: Some(7),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_review_nested_projects() {
+        // Given
+        let input = "* Projects
+** TODO Website
+*** TODO Design
+** Garden
+*** DONE Buy seeds
+";
+
+        // When
+        let review = review(
+            &config(),
+            input,
+            "projects.org",
+            "2026-10-18".parse().unwrap(),
+        );
+
+        // Then
+        assert_eq!(
+            review
+                .next_actions
+                .iter()
+                .map(|action| (action.project.join(" / "), action.action.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("Projects / Website".to_string(), "Design")]
+        );
+        assert_eq!(
+            review
+                .stuck_projects
+                .iter()
+                .map(|project| project.project.join(" / "))
+                .collect::<Vec<_>>(),
+            vec!["Projects / Garden"]
+        );
+    }
+
+    #[test]
+    fn test_render_review() {
+        let review = review(
+            &config(),
+            INPUT,
+            "projects.org",
+            "2026-10-18".parse().unwrap(),
+        );
+        assert_eq!(
+            review.render(OutputFormat::Table).unwrap(),
+            "* Next actions
+| Project          |       Next action |         File |
+|------------------+-------------------+--------------|
+| Website          |       TODO Launch | projects.org |
+| Website / Design | TODO Sketch pages | projects.org |
+
+* Stuck projects
+| Project |         File |
+|---------+--------------|
+| Garden  | projects.org |
+
+* Waiting for
+| Task                              |  Delegated to | Days |         File |
+|-----------------------------------+---------------+------+--------------|
+| WAITING Garden / Delivery of soil | Garden centre |    7 | projects.org |
+"
+        );
+        assert!(review
+            .render(OutputFormat::Csv)
+            .unwrap()
+            .contains("\nstuck project,projects.org,Garden,,,\n"));
+    }
+}
@@ -0,0 +1,230 @@
+//! Sorting the children of a section, or the top level sections of a file.
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::NaiveDateTime;
+
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::org::{OrgFile, Section};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    /// `[#A]` before `[#B]`.
+    Priority,
+    /// In the order of the configured keywords, unfinished then finished.
+    Todo,
+    Scheduled,
+    Deadline,
+    Closed,
+    /// By headline title, ignoring case.
+    Alpha,
+    /// By property value, numerically if both values are numbers.
+    Property(String),
+}
+
+impl FromStr for SortKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "priority" => Ok(SortKey::Priority),
+            "todo" => Ok(SortKey::Todo),
+            "scheduled" => Ok(SortKey::Scheduled),
+            "deadline" => Ok(SortKey::Deadline),
+            "closed" => Ok(SortKey::Closed),
+            "alpha" => Ok(SortKey::Alpha),
+            _ => match s.strip_prefix("property:") {
+                Some(name) if !name.is_empty() => Ok(SortKey::Property(name.to_string())),
+                _ => bail!(
+                    "Invalid sort key, expected priority, todo, scheduled, deadline, closed, alpha or property:NAME: {}",
+                    s
+                ),
+            },
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SortValue {
+    Number(f64),
+    Text(String),
+    Date(NaiveDateTime),
+}
+
+impl SortValue {
+    fn compare(&self, other: &SortValue) -> Ordering {
+        match (self, other) {
+            (SortValue::Number(a), SortValue::Number(b)) => a.total_cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortValue::Date(a), SortValue::Date(b)) => a.cmp(b),
+            (SortValue::Number(_), _) => Ordering::Less,
+            (_, SortValue::Number(_)) => Ordering::Greater,
+            (a, b) => a.text().cmp(&b.text()),
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            SortValue::Number(n) => n.to_string(),
+            SortValue::Text(text) => text.clone(),
+            SortValue::Date(date) => date.to_string(),
+        }
+    }
+}
+
+fn sort_value(entry: &Entry, key: &SortKey, config: &Config) -> Option<SortValue> {
+    let planning = entry.planning();
+    match key {
+        SortKey::Priority => entry
+            .headline()
+            .priority
+            .map(|priority| SortValue::Number(priority as u32 as f64)),
+        SortKey::Todo => {
+            let keyword = entry.keyword()?;
+            config
+                .keywords_unfinished
+                .iter()
+                .chain(&config.keywords_finished)
+                .position(|k| k == keyword)
+                .map(|index| SortValue::Number(index as f64))
+        }
+        SortKey::Scheduled => planning
+            .scheduled
+            .as_ref()
+            .map(|t| SortValue::Date(t.datetime())),
+        SortKey::Deadline => planning
+            .deadline
+            .as_ref()
+            .map(|t| SortValue::Date(t.datetime())),
+        SortKey::Closed => planning
+            .closed
+            .as_ref()
+            .map(|t| SortValue::Date(t.datetime())),
+        SortKey::Alpha => Some(SortValue::Text(entry.headline().title.to_lowercase())),
+        SortKey::Property(name) => entry.property(name).map(|value| match value.parse() {
+            Ok(number) => SortValue::Number(number),
+            Err(_) => SortValue::Text(value.to_lowercase()),
+        }),
+    }
+}
+
+/// Sort the children of `parent`, or the top level sections if there is
+/// no parent, returning the new text of the whole file.
+///
+/// The sort is stable and sections without a value for the key go last,
+/// even when the order is reversed. The sections' text is not changed.
+pub fn sort_subtrees(
+    config: &Config,
+    org_file: &OrgFile,
+    parent: Option<&Section>,
+    key: &SortKey,
+    reverse: bool,
+) -> String {
+    let children = match parent {
+        Some(parent) => parent.subsections(),
+        None => org_file.subsections(),
+    };
+    let mut output = org_file.output_builder();
+    let (Some(first), Some(last)) = (children.first(), children.last()) else {
+        return output.append_to_end_of_input();
+    };
+
+    let mut keyed = children
+        .iter()
+        .map(|section| (sort_value(&section.entry(), key, config), section.text()))
+        .collect::<Vec<_>>();
+    keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+        (Some(a), Some(b)) if reverse => b.compare(a),
+        (Some(a), Some(b)) => a.compare(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    let mut text = String::new();
+    for (_, section_text) in keyed {
+        text.push_str(section_text);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    if !last.text().ends_with('\n') {
+        text.pop();
+    }
+
+    output.append_up_to_section(first);
+    output.insert_text(&text);
+    output.skip_to(last.end_byte());
+    output.append_to_end_of_input()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::org::Org;
+
+    fn sort(input: &str, parent: Option<&str>, key: &str, reverse: bool) -> String {
+        let config = Config::default();
+        let org_file = Org::from_config(config.clone()).load(input);
+        let parent = parent.map(|search| org_file.find_section(search).unwrap());
+        sort_subtrees(
+            &config,
+            &org_file,
+            parent.as_ref(),
+            &key.parse().unwrap(),
+            reverse,
+        )
+    }
+
+    #[test]
+    fn test_sort_by_priority() {
+        // Given
+        let input =
+            "* Projects\nNotes\n** [#C] Shed\n** Garden\n** [#A] House\n*** Paint\n* Other\n";
+
+        // When
+        let output = sort(input, Some("Projects"), "priority", false);
+
+        // Then
+        assert_eq!(
+            output,
+            "* Projects\nNotes\n** [#A] House\n*** Paint\n** [#C] Shed\n** Garden\n* Other\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_top_level_by_todo_reversed() {
+        let input = "* DONE One\n* TODO Two\n* Three\n* BLOCKED Four";
+        assert_eq!(
+            sort(input, None, "todo", true),
+            "* DONE One\n* BLOCKED Four\n* TODO Two\n* Three"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_date_and_alpha() {
+        let input = "* b\nSCHEDULED: <2026-10-20 Tue>\n* A\n* c\nSCHEDULED: <2026-10-18 Sun>\n";
+        assert_eq!(
+            sort(input, None, "scheduled", false),
+            "* c\nSCHEDULED: <2026-10-18 Sun>\n* b\nSCHEDULED: <2026-10-20 Tue>\n* A\n"
+        );
+        assert_eq!(
+            sort(input, None, "alpha", false),
+            "* A\n* b\nSCHEDULED: <2026-10-20 Tue>\n* c\nSCHEDULED: <2026-10-18 Sun>\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_property() {
+        let input =
+            "* A\n:PROPERTIES:\n:SIZE: 10\n:END:\n* B\n:PROPERTIES:\n:SIZE: 9\n:END:\n* C\n";
+        assert_eq!(
+            sort(input, None, "property:SIZE", false),
+            "* B\n:PROPERTIES:\n:SIZE: 9\n:END:\n* A\n:PROPERTIES:\n:SIZE: 10\n:END:\n* C\n"
+        );
+        assert!("property:".parse::<SortKey>().is_err());
+    }
+}
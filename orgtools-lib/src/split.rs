@@ -0,0 +1,266 @@
+//! Splitting a file into one file per top-level section, and joining files
+//! into one outline.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+
+use crate::config::Config;
+use crate::document::slug;
+use crate::narrow::is_keyword_line;
+use crate::org::Org;
+use crate::outline::{headline_level, inside_blocks, shift_levels};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    /// A file per top-level section.
+    Level1,
+}
+
+impl FromStr for SplitBy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "level1" => Ok(SplitBy::Level1),
+            _ => bail!("Invalid split, expected level1: {}", s),
+        }
+    }
+}
+
+/// The text before the first headline, split into its `#+KEY:` keyword
+/// lines and everything else, including blocks.
+fn preamble(input: &str) -> (Vec<&str>, String) {
+    let mut keywords = vec![];
+    let mut text = String::new();
+    let lines = input
+        .split_inclusive('\n')
+        .take_while(|line| headline_level(line).is_none())
+        .collect::<Vec<_>>();
+    for (line, inside) in lines.iter().zip(inside_blocks(&lines)) {
+        if !inside && is_keyword_line(line) {
+            keywords.push(line.trim_end());
+        } else {
+            text.push_str(line);
+        }
+    }
+    (keywords, text)
+}
+
+/// The value of a `#+NAME:` keyword line, ignoring case.
+fn keyword_value<'a>(keyword: &'a str, name: &str) -> Option<&'a str> {
+    let (key, value) = keyword.trim_start().strip_prefix("#+")?.split_once(':')?;
+    key.eq_ignore_ascii_case(name).then(|| value.trim())
+}
+
+/// The value of a `#+TITLE:` keyword line.
+fn title_keyword(keyword: &str) -> Option<&str> {
+    keyword_value(keyword, "TITLE")
+}
+
+/// A file name for a headline title: lower case words joined by `-`.
+fn file_stem(title: &str) -> String {
+    let stem = slug(title);
+    if stem.is_empty() {
+        "section".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Split `input` into `(file name, contents)` pairs, one per top-level
+/// section, each starting with the input's `#+` keywords and a `#+TITLE:`
+/// of the section's title.
+///
+/// The input's own `#+TITLE:` is kept as `#+ORIGINAL_TITLE:`, which
+/// [`join`] turns back into the title.
+///
+/// Any other text before the first headline goes in the first file.
+pub fn split(config: &Config, input: &str, by: SplitBy) -> Result<Vec<(String, String)>> {
+    let SplitBy::Level1 = by;
+    let org = Org::from_config(config.clone()).load(input);
+    let (keywords, mut text) = preamble(input);
+    let mut names = BTreeSet::new();
+    let mut files = vec![];
+    for section in org.subsections() {
+        let title = section.entry().headline().title.clone();
+        let stem = file_stem(&title);
+        let mut name = format!("{}.org", stem);
+        let mut suffix = 1;
+        while !names.insert(name.clone()) {
+            suffix += 1;
+            name = format!("{}-{}.org", stem, suffix);
+        }
+
+        let mut contents = format!("#+TITLE: {}\n", title);
+        for keyword in &keywords {
+            match title_keyword(keyword) {
+                Some(title) => contents.push_str(&format!("#+ORIGINAL_TITLE: {}", title)),
+                None => contents.push_str(keyword),
+            }
+            contents.push('\n');
+        }
+        contents.push_str(&std::mem::take(&mut text));
+        contents.push_str(section.text());
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        files.push((name, contents));
+    }
+    if files.is_empty() {
+        bail!("There are no headlines to split by");
+    }
+    Ok(files)
+}
+
+/// Join `(file name, contents)` pairs into one outline, each file under a
+/// top-level heading named after its `#+TITLE:` or its file name.
+///
+/// A file whose only top-level headline is its title, as written by
+/// [`split`], is not wrapped again, and its text before the first headline
+/// goes before all headlines, so joining undoes splitting.
+///
+/// The other `#+` keywords of the files are kept once at the top, with an
+/// `#+ORIGINAL_TITLE:` from [`split`] becoming the `#+TITLE:` again. Any
+/// other text before a file's first headline becomes the body of its heading.
+pub fn join(config: &Config, files: &[(String, String)]) -> Result<String> {
+    let mut keywords = vec![];
+    let mut preamble_text = String::new();
+    let mut sections = String::new();
+    for (name, contents) in files {
+        let (file_keywords, text) = preamble(contents);
+        let title = file_keywords
+            .iter()
+            .find_map(|keyword| title_keyword(keyword))
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+                name.strip_suffix(".org").unwrap_or(name).to_string()
+            });
+        for keyword in file_keywords {
+            if title_keyword(keyword).is_some() {
+                continue;
+            }
+            let keyword = match keyword_value(keyword, "ORIGINAL_TITLE") {
+                Some(title) => format!("#+TITLE: {}", title),
+                None => keyword.to_string(),
+            };
+            if !keywords.contains(&keyword) {
+                keywords.push(keyword);
+            }
+        }
+
+        let headlines_start = contents
+            .split_inclusive('\n')
+            .take_while(|line| headline_level(line).is_none())
+            .map(str::len)
+            .sum::<usize>();
+        let org = Org::from_config(config.clone()).load(contents);
+        let top_level = org.subsections();
+        let is_split = matches!(
+            top_level.as_slice(),
+            [section] if section.stars() == 1 && section.entry().headline().title == title
+        );
+        let mut headlines = if is_split {
+            preamble_text.push_str(&text);
+            contents[headlines_start..].to_string()
+        } else {
+            sections.push_str(&format!("* {}\n", title));
+            sections.push_str(&text);
+            shift_levels(&contents[headlines_start..], 1)?
+        };
+        if !headlines.is_empty() && !headlines.ends_with('\n') {
+            headlines.push('\n');
+        }
+        sections.push_str(&headlines);
+    }
+
+    let mut output = String::new();
+    for keyword in keywords {
+        output.push_str(&keyword);
+        output.push('\n');
+    }
+    output.push_str(&preamble_text);
+    output.push_str(&sections);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#+TITLE: Notes
+#+STARTUP: overview
+Things to remember.
+* Rust: tips & tricks
+** Closures
+* Cooking
+Recipes.
+* Cooking
+";
+
+    #[test]
+    fn test_split() {
+        let files = split(&Config::default(), INPUT, SplitBy::Level1).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "rust-tips-tricks.org".to_string(),
+                    "#+TITLE: Rust: tips & tricks\n#+ORIGINAL_TITLE: Notes\n#+STARTUP: overview\nThings to remember.\n* Rust: tips & tricks\n** Closures\n".to_string()
+                ),
+                (
+                    "cooking.org".to_string(),
+                    "#+TITLE: Cooking\n#+ORIGINAL_TITLE: Notes\n#+STARTUP: overview\n* Cooking\nRecipes.\n".to_string()
+                ),
+                (
+                    "cooking-2.org".to_string(),
+                    "#+TITLE: Cooking\n#+ORIGINAL_TITLE: Notes\n#+STARTUP: overview\n* Cooking\n".to_string()
+                ),
+            ]
+        );
+        assert!(split(&Config::default(), "Just text\n", SplitBy::Level1).is_err());
+    }
+
+    #[test]
+    fn test_join() {
+        // Given
+        let files = [
+            (
+                "notes/rust.org".to_string(),
+                "#+TITLE: Rust\n#+STARTUP: overview\nLanguage notes.\n* Closures\n** Move\n"
+                    .to_string(),
+            ),
+            (
+                "notes/cooking.org".to_string(),
+                "#+STARTUP: overview\n* Bread\n".to_string(),
+            ),
+            ("notes/empty.org".to_string(), String::new()),
+        ];
+
+        // When
+        let output = join(&Config::default(), &files).unwrap();
+
+        // Then
+        assert_eq!(
+            output,
+            "#+STARTUP: overview
+* Rust
+Language notes.
+** Closures
+*** Move
+* cooking
+** Bread
+* empty
+"
+        );
+    }
+
+    #[test]
+    fn test_split_and_join_keep_title() {
+        let files = split(&Config::default(), INPUT, SplitBy::Level1).unwrap();
+        let output = join(&Config::default(), &files).unwrap();
+        assert_eq!(output, INPUT);
+    }
+}
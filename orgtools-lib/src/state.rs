@@ -0,0 +1,264 @@
+//! Changing the TODO state of entries.
+
+use anyhow::{bail, Result};
+use chrono::NaiveDateTime;
+
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::timestamp::Timestamp;
+
+/// Change the TODO keyword of an entry.
+///
+/// Moving into a finished keyword adds a `CLOSED:` timestamp and moving
+/// out of one removes it. When `Config::log_state_changes` is set the
+/// change is noted in the `:LOGBOOK:` drawer.
+///
+/// Finishing an entry with a repeating `SCHEDULED:` or `DEADLINE:` keeps it
+/// open instead, see [`repeat`].
+pub fn change_state(
+    entry: &mut Entry,
+    config: &Config,
+    keyword: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<()> {
+    if let Some(keyword) = keyword {
+        if !config.is_keyword(keyword) {
+            bail!("Unknown keyword: {}", keyword);
+        }
+    }
+    let old = entry.keyword().map(str::to_string);
+    if old.as_deref() == keyword {
+        return Ok(());
+    }
+
+    let was_finished = old.as_deref().is_some_and(|old| config.is_finished(old));
+    let is_finished = keyword.is_some_and(|keyword| config.is_finished(keyword));
+
+    if is_finished && !was_finished && is_repeating(entry) {
+        return repeat(entry, config, old.as_deref(), keyword, now);
+    }
+
+    entry.set_keyword(keyword);
+
+    let mut planning = entry.planning().clone();
+    if is_finished && !was_finished {
+        planning.closed = Some(Timestamp::inactive_now(now));
+    } else if !is_finished {
+        planning.closed = None;
+    }
+    entry.set_planning(planning);
+
+    if config.log_state_changes {
+        entry.add_logbook_item(&state_change_note(old.as_deref(), keyword, now));
+    }
+
+    Ok(())
+}
+
+fn is_repeating(entry: &Entry) -> bool {
+    let planning = entry.planning();
+    [&planning.scheduled, &planning.deadline]
+        .into_iter()
+        .flatten()
+        .any(|timestamp| timestamp.repeater.is_some())
+}
+
+/// Complete one repetition of a repeating entry.
+///
+/// The repeating timestamps move to their next occurrence, the keyword goes
+/// back to the first unfinished keyword and the completion is recorded in
+/// the `:LAST_REPEAT:` property and, when `Config::log_repeat` is set, the
+/// `:LOGBOOK:` drawer.
+fn repeat(
+    entry: &mut Entry,
+    config: &Config,
+    from: Option<&str>,
+    to: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<()> {
+    let mut planning = entry.planning().clone();
+    for timestamp in [&mut planning.scheduled, &mut planning.deadline]
+        .into_iter()
+        .flatten()
+    {
+        if let Some(next) = timestamp.next_repeat(now)? {
+            *timestamp = next;
+        }
+    }
+    entry.set_planning(planning);
+
+    let reopened = config
+        .keywords_unfinished
+        .first()
+        .map(String::as_str)
+        .or(from);
+    entry.set_keyword(reopened);
+
+    entry.set_property("LAST_REPEAT", &Timestamp::inactive_now(now).to_string());
+    if config.log_repeat || config.log_state_changes {
+        entry.add_logbook_item(&state_change_note(from, to, now));
+    }
+
+    Ok(())
+}
+
+/// A logbook note such as `- State "DONE" from "TODO" [2026-10-17 Sat 10:00]`.
+pub fn state_change_note(from: Option<&str>, to: Option<&str>, now: NaiveDateTime) -> String {
+    format!(
+        "- State \"{}\" from \"{}\" {}",
+        to.unwrap_or_default(),
+        from.unwrap_or_default(),
+        Timestamp::inactive_now(now)
+    )
+}
+
+/// A state change noted in the `:LOGBOOK:` drawer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateChange {
+    pub to: Option<String>,
+    pub from: Option<String>,
+    pub time: NaiveDateTime,
+}
+
+impl StateChange {
+    /// Parse a note written by [`state_change_note`] or by Org, which pads
+    /// the keywords with spaces.
+    pub fn parse(line: &str) -> Option<StateChange> {
+        let rest = line.trim().strip_prefix("- State")?.trim_start();
+        let (to, rest) = quoted(rest)?;
+        let rest = rest.trim_start().strip_prefix("from")?.trim_start();
+        let (from, rest) = quoted(rest)?;
+        let (timestamp, _) = Timestamp::parse_prefix(rest.trim_start()).ok()?;
+        let keyword = |keyword: &str| (!keyword.is_empty()).then(|| keyword.to_string());
+        Some(StateChange {
+            to: keyword(to),
+            from: keyword(from),
+            time: timestamp.datetime(),
+        })
+    }
+}
+
+/// Split a leading `"quoted"` string from the rest of the input.
+fn quoted(input: &str) -> Option<(&str, &str)> {
+    let rest = input.strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some((&rest[..end], &rest[end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    fn change(input: &str, keyword: Option<&str>, config: &Config) -> String {
+        let mut entry = Entry::parse(input, config).unwrap();
+        change_state(&mut entry, config, keyword, now()).unwrap();
+        entry.to_string()
+    }
+
+    #[test]
+    fn test_finish_adds_closed() {
+        let output = change(
+            "* TODO Task\nSCHEDULED: <2026-10-16 Fri>\nBody\n",
+            Some("DONE"),
+            &Config::default(),
+        );
+        assert_eq!(
+            output,
+            "* DONE Task\nCLOSED: [2026-10-17 Sat 10:00] SCHEDULED: <2026-10-16 Fri>\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_reopen_removes_closed() {
+        let output = change(
+            "* DONE Task\nCLOSED: [2026-10-16 Fri 09:00]\n",
+            Some("TODO"),
+            &Config::default(),
+        );
+        assert_eq!(output, "* TODO Task\n");
+    }
+
+    #[test]
+    fn test_finished_to_finished_keeps_closed() {
+        let input = "* DONE Task\nCLOSED: [2026-10-16 Fri 09:00]\n";
+        let output = change(input, Some("ABANDONED"), &Config::default());
+        assert_eq!(output, "* ABANDONED Task\nCLOSED: [2026-10-16 Fri 09:00]\n");
+    }
+
+    #[test]
+    fn test_log_state_change() {
+        let config = Config {
+            log_state_changes: true,
+            ..Config::default()
+        };
+        let output = change(
+            "* TODO Task\n:PROPERTIES:\n:ID: 1\n:END:\n",
+            Some("DONE"),
+            &config,
+        );
+        assert_eq!(
+            output,
+            "* DONE Task\nCLOSED: [2026-10-17 Sat 10:00]\n:PROPERTIES:\n:ID: 1\n:END:\n:LOGBOOK:\n- State \"DONE\" from \"TODO\" [2026-10-17 Sat 10:00]\n:END:\n"
+        );
+    }
+
+    #[test]
+    fn test_finish_repeating_task() {
+        let output = change(
+            "* TODO Chores\nSCHEDULED: <2026-10-17 Sat +1w>\n",
+            Some("DONE"),
+            &Config::default(),
+        );
+        assert_eq!(
+            output,
+            "* TODO Chores\nSCHEDULED: <2026-10-24 Sat +1w>\n:PROPERTIES:\n:LAST_REPEAT: [2026-10-17 Sat 10:00]\n:END:\n:LOGBOOK:\n- State \"DONE\" from \"TODO\" [2026-10-17 Sat 10:00]\n:END:\n"
+        );
+    }
+
+    #[test]
+    fn test_finish_repeating_deadline_reopens_first_keyword() {
+        let config = Config {
+            log_repeat: false,
+            ..Config::default()
+        };
+        let output = change(
+            "* DOING Report\nDEADLINE: <2026-10-01 Thu .+1m> SCHEDULED: <2026-09-28 Mon>\n",
+            Some("DONE"),
+            &config,
+        );
+        assert_eq!(
+            output,
+            "* TODO Report\nDEADLINE: <2026-11-17 Tue .+1m> SCHEDULED: <2026-09-28 Mon>\n:PROPERTIES:\n:LAST_REPEAT: [2026-10-17 Sat 10:00]\n:END:\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_state_change() {
+        assert_eq!(
+            StateChange::parse(&state_change_note(Some("TODO"), Some("DONE"), now())),
+            Some(StateChange {
+                to: Some("DONE".to_string()),
+                from: Some("TODO".to_string()),
+                time: now(),
+            })
+        );
+        let org_note = "- State \"DOING\"      from \"\"           [2026-10-17 Sat 10:00]";
+        assert_eq!(StateChange::parse(org_note).unwrap().from, None);
+        assert_eq!(StateChange::parse("- Note taken on [2026-10-17 Sat]"), None);
+    }
+
+    #[test]
+    fn test_unknown_keyword() {
+        let config = Config::default();
+        let mut entry = Entry::parse("* TODO Task", &config).unwrap();
+        assert!(change_state(&mut entry, &config, Some("LATER"), now()).is_err());
+    }
+}
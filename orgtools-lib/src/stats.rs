@@ -0,0 +1,200 @@
+//! Statistics about finished tasks.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::matcher::Matcher;
+use crate::org::Org;
+use crate::report::{OutputFormat, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    /// Weeks starting on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+impl FromStr for Period {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            "year" => Ok(Period::Year),
+            _ => bail!("Invalid period, expected day, week, month or year: {}", s),
+        }
+    }
+}
+
+impl Period {
+    /// The first day of the period containing `date`.
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Period::Month => date.with_day(1).expect("every month has a first day"),
+            Period::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).expect("valid year"),
+        }
+    }
+
+    /// The first day of the period after the one starting on `start`.
+    pub fn next(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => start + Duration::days(1),
+            Period::Week => start + Duration::days(7),
+            Period::Month => start + Months::new(1),
+            Period::Year => start + Months::new(12),
+        }
+    }
+}
+
+/// The dates on which the headings matching `matcher`, or all headings,
+/// were closed.
+pub fn closed_dates(config: &Config, input: &str, matcher: Option<&Matcher>) -> Vec<NaiveDate> {
+    let org = Org::from_config(config.clone()).load(input);
+    org.outline()
+        .into_iter()
+        .filter_map(|item| {
+            let entry = item.section.entry();
+            let closed = entry.planning().closed.as_ref()?.date;
+            matcher
+                .is_none_or(|matcher| matcher.matches(&entry, &item.tags, item.level()))
+                .then_some(closed)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeriodCount {
+    /// The first day of the period.
+    pub period: NaiveDate,
+    pub count: usize,
+}
+
+/// Count the dates in each period from `from`, or the earliest date, to
+/// `to`, or the latest date, including periods without any.
+pub fn count_by_period(
+    dates: &[NaiveDate],
+    period: Period,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<PeriodCount> {
+    let mut counts = BTreeMap::new();
+    for date in dates {
+        if from.is_some_and(|from| *date < from) || to.is_some_and(|to| *date > to) {
+            continue;
+        }
+        *counts.entry(period.start(*date)).or_insert(0) += 1;
+    }
+
+    let first = from.or_else(|| counts.keys().next().copied());
+    let last = to.or_else(|| counts.keys().next_back().copied());
+    let (Some(first), Some(last)) = (first, last) else {
+        return vec![];
+    };
+    let mut result = vec![];
+    let mut start = period.start(first);
+    while start <= last {
+        result.push(PeriodCount {
+            period: start,
+            count: counts.get(&start).copied().unwrap_or(0),
+        });
+        start = period.next(start);
+    }
+    result
+}
+
+/// Render counts as a histogram of `=` bars, as CSV or as JSON.
+pub fn render_counts(counts: &[PeriodCount], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Table => counts
+            .iter()
+            .map(|count| {
+                format!(
+                    "{}  {:3}  {}\n",
+                    count.period,
+                    count.count,
+                    "=".repeat(count.count)
+                )
+            })
+            .collect(),
+        OutputFormat::Csv => {
+            let mut table = Table::new(&["period", "count"]);
+            for count in counts {
+                table
+                    .rows
+                    .push(vec![count.period.to_string(), count.count.to_string()]);
+            }
+            table.to_csv()
+        }
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(counts).expect("counts serialize to JSON") + "\n"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_closed_dates() {
+        let input = "* DONE One :work:\nCLOSED: [2026-10-12 Mon 10:00]\n* DONE Two\nCLOSED: [2026-10-14 Wed]\n* TODO Three :work:\n";
+        let config = Config::default();
+        assert_eq!(
+            closed_dates(&config, input, None),
+            vec![date("2026-10-12"), date("2026-10-14")]
+        );
+        let matcher = "work".parse().unwrap();
+        assert_eq!(
+            closed_dates(&config, input, Some(&matcher)),
+            vec![date("2026-10-12")]
+        );
+    }
+
+    #[test]
+    fn test_count_by_period() {
+        // Given
+        let dates = [date("2026-10-01"), date("2026-10-16"), date("2026-10-18")];
+
+        // When
+        let weeks = count_by_period(&dates, Period::Week, None, None);
+
+        // Then
+        assert_eq!(
+            render_counts(&weeks, OutputFormat::Table),
+            "2026-09-28    1  =\n2026-10-05    0  \n2026-10-12    2  ==\n"
+        );
+        assert_eq!(
+            count_by_period(&dates, Period::Month, Some(date("2026-10-10")), None),
+            vec![PeriodCount {
+                period: date("2026-10-01"),
+                count: 2
+            }]
+        );
+        assert_eq!(count_by_period(&[], Period::Day, None, None), vec![]);
+    }
+
+    #[test]
+    fn test_render_counts() {
+        let counts = count_by_period(&[date("2026-10-17")], Period::Year, None, None);
+        assert_eq!(
+            render_counts(&counts, OutputFormat::Csv),
+            "period,count\n2026-01-01,1\n"
+        );
+        assert!(render_counts(&counts, OutputFormat::Json).contains("\"period\": \"2026-01-01\""));
+    }
+}
@@ -0,0 +1,259 @@
+//! Structural edits to subtrees: changing their level, moving them among
+//! their siblings and moving them to another place or file.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::datetree::{add_to_datetree, DatetreeKind};
+use crate::org::{Org, OrgFile, OutputBuilder, Position, Section};
+use crate::outline::{relevel, shift_levels};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtreeEdit {
+    /// Move every headline in the subtree up a level.
+    Promote,
+    /// Move every headline in the subtree down a level.
+    Demote,
+    /// Change the level of the subtree's headline, keeping the levels under it relative.
+    SetLevel(usize),
+    /// Swap the subtree with its previous sibling.
+    MoveUp,
+    /// Swap the subtree with its next sibling.
+    MoveDown,
+}
+
+/// Apply an edit to a section, returning the new text of the whole file.
+///
+/// Only the text of the subtree, or the subtree and the sibling it is
+/// swapped with, is changed.
+pub fn edit_subtree(org_file: &OrgFile, section: &Section, edit: SubtreeEdit) -> Result<String> {
+    let mut output = org_file.output_builder();
+    match edit {
+        SubtreeEdit::Promote | SubtreeEdit::Demote | SubtreeEdit::SetLevel(_) => {
+            let text = match edit {
+                SubtreeEdit::Promote => shift_levels(section.text(), -1)?,
+                SubtreeEdit::Demote => shift_levels(section.text(), 1)?,
+                SubtreeEdit::SetLevel(level) if level > 0 => relevel(section.text(), level)?,
+                _ => bail!("Headline level must be at least 1"),
+            };
+            output.append_up_to_section(section);
+            output.insert_text(&text);
+            output.skip_to(section.end_byte());
+        }
+        SubtreeEdit::MoveUp | SubtreeEdit::MoveDown => {
+            let siblings = org_file.siblings(section);
+            let index = siblings
+                .iter()
+                .position(|sibling| sibling.start_byte() == section.start_byte())
+                .expect("a section is one of its own siblings");
+            let (first, second) = match edit {
+                SubtreeEdit::MoveUp if index > 0 => (&siblings[index - 1], section),
+                SubtreeEdit::MoveDown if index + 1 < siblings.len() => {
+                    (section, &siblings[index + 1])
+                }
+                SubtreeEdit::MoveUp => bail!("Cannot move the first sibling up"),
+                _ => bail!("Cannot move the last sibling down"),
+            };
+            output.append_up_to_section(first);
+            output.insert_text(&swapped(first.text(), second.text()));
+            output.skip_to(second.end_byte());
+        }
+    }
+    Ok(output.append_to_end_of_input())
+}
+
+/// Where to add a subtree.
+#[derive(Debug)]
+pub enum Target<'a> {
+    /// Relative to the first section with a matching headline.
+    Section(Position, &'a str),
+    /// Under the section at the end of an outline path.
+    OutlinePath(&'a [String]),
+    /// Under the day, week or month of a date tree, which is itself under
+    /// the section at the end of an outline path or at the top level.
+    Datetree {
+        kind: DatetreeKind,
+        date: NaiveDate,
+        path: &'a [String],
+    },
+    /// At the end of the file.
+    End,
+}
+
+/// Remove the first section matching `search`, returning the remaining
+/// input and the section's subtree shifted to level 1.
+pub fn cut_subtree(config: &Config, input: &str, search: &str) -> Result<(String, String)> {
+    let org = Org::from_config(config.clone()).load(input);
+    let section = org
+        .find_section(search)
+        .ok_or_else(|| anyhow!("Could not find section with headline: {}", search))?;
+
+    let mut subtree = input[section.start_byte()..section.end_byte()].to_string();
+    if !subtree.ends_with('\n') {
+        subtree.push('\n');
+    }
+    let mut output = org.output_builder();
+    output.append_up_to_section(&section);
+    output.skip_to(section.end_byte());
+
+    Ok((output.append_to_end_of_input(), relevel(&subtree, 1)?))
+}
+
+/// Add a subtree, starting with a level 1 headline, to the input.
+///
+/// The levels of the subtree are adjusted to fit the target.
+pub fn add_subtree(config: &Config, input: &str, subtree: &str, target: Target) -> Result<String> {
+    let org_file = Org::from_config(config.clone()).load(input);
+    let mut output = org_file.output_builder();
+    match target {
+        Target::Section(position, search) => {
+            if let Some(section) = org_file.find_section(search) {
+                add_to_section(&section, &mut output, subtree, position)?;
+                Ok(output.append_to_end_of_input())
+            } else {
+                Err(anyhow!("Could not find section with headline: {}", search))
+            }
+        }
+        Target::OutlinePath(path) => {
+            if let Some(section) = org_file.find_outline_path(path) {
+                add_to_section(&section, &mut output, subtree, Position::Under)?;
+                Ok(output.append_to_end_of_input())
+            } else {
+                Err(anyhow!("Could not find outline path: {}", path.join("/")))
+            }
+        }
+        Target::Datetree { kind, date, path } => {
+            add_to_datetree(config, input, subtree, kind, date, path)
+        }
+        Target::End => {
+            output.append_to(input.len());
+            output.ensure_newline();
+            output.insert_text(subtree);
+            Ok(output.append_to_end_of_input())
+        }
+    }
+}
+
+fn add_to_section(
+    section: &Section,
+    output: &mut OutputBuilder,
+    subtree: &str,
+    position: Position,
+) -> Result<()> {
+    output.append_to_end_of_section(section);
+    output.ensure_newline();
+    let num_stars = match position {
+        Position::After => section.stars(),
+        Position::Under => section.stars() + 1,
+    };
+
+    output.insert_text(&relevel(subtree, num_stars)?);
+    Ok(())
+}
+
+/// `second` followed by `first`, keeping any missing newline at the end.
+fn swapped(first: &str, second: &str) -> String {
+    let mut text = second.to_string();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(first);
+    if !second.ends_with('\n') && text.ends_with('\n') {
+        text.pop();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::org::Org;
+
+    fn edit(input: &str, search: &str, edit: SubtreeEdit) -> Result<String> {
+        let org_file = Org::new().load(input);
+        let section = org_file.find_section(search).unwrap();
+        edit_subtree(&org_file, &section, edit)
+    }
+
+    #[test]
+    fn test_promote_and_demote() {
+        // Given
+        let input = "* A\n** B\nBody\n*** C\n** D\n";
+
+        // When
+        let promoted = edit(input, "B", SubtreeEdit::Promote).unwrap();
+        let demoted = edit(input, "B", SubtreeEdit::Demote).unwrap();
+
+        // Then
+        assert_eq!(promoted, "* A\n* B\nBody\n** C\n** D\n");
+        assert_eq!(demoted, "* A\n*** B\nBody\n**** C\n** D\n");
+        assert!(edit(input, "A", SubtreeEdit::Promote).is_err());
+    }
+
+    #[test]
+    fn test_set_level() {
+        let input = "* A\n** B\n*** C\n";
+        assert_eq!(
+            edit(input, "B", SubtreeEdit::SetLevel(4)).unwrap(),
+            "* A\n**** B\n***** C\n"
+        );
+        assert!(edit(input, "B", SubtreeEdit::SetLevel(0)).is_err());
+    }
+
+    #[test]
+    fn test_move_up_and_down() {
+        // Given
+        let input = "* A\n** B\nBody\n** C\n*** D\n* E";
+
+        // When
+        let moved_up = edit(input, "C", SubtreeEdit::MoveUp).unwrap();
+        let moved_down = edit(input, "A", SubtreeEdit::MoveDown).unwrap();
+
+        // Then
+        assert_eq!(moved_up, "* A\n** C\n*** D\n** B\nBody\n* E");
+        assert_eq!(moved_down, "* E\n* A\n** B\nBody\n** C\n*** D");
+        assert!(edit(input, "B", SubtreeEdit::MoveUp).is_err());
+        assert!(edit(input, "E", SubtreeEdit::MoveDown).is_err());
+    }
+
+    #[test]
+    fn test_add_subtree_under_outline_path() {
+        let config = Config::default();
+        let input = "* Projects\n** Work\n* Areas\n** Work\n*** Old\n";
+        let path = vec!["Areas".to_string(), "Work".to_string()];
+        let result = add_subtree(
+            &config,
+            input,
+            "* New\n** Child\n",
+            Target::OutlinePath(&path),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "* Projects\n** Work\n* Areas\n** Work\n*** Old\n*** New\n**** Child\n"
+        );
+    }
+
+    #[test]
+    fn test_cut_and_add_subtree() {
+        // Given
+        let config = Config::default();
+
+        // When
+        let (output, subtree) =
+            cut_subtree(&config, "* Inbox\n** TODO Call Sam\n*** Number", "Call Sam").unwrap();
+        let target = add_subtree(
+            &config,
+            "* Calls\n",
+            &subtree,
+            Target::Section(Position::Under, "Calls"),
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(output, "* Inbox\n");
+        assert_eq!(subtree, "* TODO Call Sam\n** Number\n");
+        assert_eq!(target, "* Calls\n** TODO Call Sam\n*** Number\n");
+    }
+}
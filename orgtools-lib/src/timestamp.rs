@@ -0,0 +1,568 @@
+//! Parsing and rendering of Org mode timestamps.
+//!
+//! Supports active (`<...>`) and inactive (`[...]`) timestamps with an
+//! optional time or time range, repeater and warning delay, e.g.
+//! `<2026-10-17 Sat 10:00-11:00 +1w -2d>`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timestamp {
+    pub active: bool,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub repeater: Option<Repeater>,
+    pub delay: Option<Delay>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub value: u32,
+    pub unit: TimeUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeaterKind {
+    /// `+1w`: shift the date by the interval once.
+    Cumulate,
+    /// `++1w`: shift the date by the interval until it is in the future.
+    CatchUp,
+    /// `.+1w`: shift the date to the interval from now.
+    Restart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeater {
+    pub kind: RepeaterKind,
+    pub interval: Interval,
+    /// The maximum interval of a habit, e.g. the `3d` in `.+2d/3d`.
+    pub habit_max: Option<Interval>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delay {
+    /// `--2d` applies to all repetitions rather than just the first one.
+    pub all: bool,
+    pub interval: Interval,
+}
+
+impl Timestamp {
+    pub fn new(active: bool, date: NaiveDate, time: Option<NaiveTime>) -> Self {
+        Self {
+            active,
+            date,
+            time,
+            end_time: None,
+            repeater: None,
+            delay: None,
+        }
+    }
+
+    /// An inactive timestamp with a time, as used for `CLOSED:` and log entries.
+    pub fn inactive_now(now: NaiveDateTime) -> Self {
+        Self::new(false, now.date(), Some(truncate_seconds(now.time())))
+    }
+
+    /// The start of the timestamp, defaulting to midnight when there is no time.
+    pub fn datetime(&self) -> NaiveDateTime {
+        self.date.and_time(self.time.unwrap_or(NaiveTime::MIN))
+    }
+
+    /// The next occurrence of a repeating timestamp after it is completed at `now`.
+    ///
+    /// - `+1w` shifts the timestamp by one interval.
+    /// - `++1w` shifts it by whole intervals until it is in the future.
+    /// - `.+1w` shifts it to one interval after `now`.
+    ///
+    /// Returns `None` if the timestamp doesn't repeat.
+    pub fn next_repeat(&self, now: NaiveDateTime) -> Result<Option<Timestamp>> {
+        let Some(repeater) = &self.repeater else {
+            return Ok(None);
+        };
+        let interval = repeater.interval;
+        let next = match repeater.kind {
+            RepeaterKind::Cumulate => interval.add_to(self.datetime())?,
+            RepeaterKind::CatchUp => {
+                let past = match self.time {
+                    Some(_) => now,
+                    None => now.date().and_time(NaiveTime::MIN),
+                };
+                interval.first_after(self.datetime(), past.max(self.datetime()))?
+            }
+            RepeaterKind::Restart => match (interval.unit, self.time) {
+                (TimeUnit::Hour, _) => interval.add_to(now)?,
+                (_, time) => {
+                    interval.add_to(now.date().and_time(time.unwrap_or(NaiveTime::MIN)))?
+                }
+            },
+        };
+
+        let mut timestamp = self.clone();
+        timestamp.date = next.date();
+        if self.time.is_some() {
+            timestamp.time = Some(next.time());
+            if let (Some(start), Some(end)) = (self.time, self.end_time) {
+                timestamp.end_time = Some(next.time() + (end - start));
+            }
+        }
+        Ok(Some(timestamp))
+    }
+
+    /// Parse a timestamp from the start of `input`.
+    ///
+    /// Returns the timestamp and the number of bytes consumed.
+    pub fn parse_prefix(input: &str) -> Result<(Timestamp, usize)> {
+        let (active, close) = match input.chars().next() {
+            Some('<') => (true, '>'),
+            Some('[') => (false, ']'),
+            _ => bail!("Timestamp must start with '<' or '['"),
+        };
+        let end = input
+            .find(close)
+            .ok_or_else(|| anyhow!("Unterminated timestamp: {}", input))?;
+        let inner = &input[1..end];
+        if inner.contains('\n') {
+            bail!("Unterminated timestamp: {}", input);
+        }
+
+        let mut parts = inner.split_whitespace().peekable();
+        let date = parts.next().ok_or_else(|| anyhow!("Missing date"))?;
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid date in timestamp: {}", date))?;
+        let mut timestamp = Timestamp::new(active, date, None);
+        // The day name is informational, the date is authoritative.
+        parts.next_if(|part| is_day_name(part));
+
+        for part in parts {
+            if part.starts_with(|c: char| c.is_ascii_digit()) {
+                let (start, end) = match part.split_once('-') {
+                    Some((start, end)) => (start, Some(end)),
+                    None => (part, None),
+                };
+                timestamp.time = Some(parse_time(start)?);
+                timestamp.end_time = end.map(parse_time).transpose()?;
+            } else if let Some(rest) = part.strip_prefix("++") {
+                timestamp.repeater = Some(parse_repeater(RepeaterKind::CatchUp, rest)?);
+            } else if let Some(rest) = part.strip_prefix(".+") {
+                timestamp.repeater = Some(parse_repeater(RepeaterKind::Restart, rest)?);
+            } else if let Some(rest) = part.strip_prefix('+') {
+                timestamp.repeater = Some(parse_repeater(RepeaterKind::Cumulate, rest)?);
+            } else if let Some(rest) = part.strip_prefix("--") {
+                timestamp.delay = Some(Delay {
+                    all: true,
+                    interval: rest.parse()?,
+                });
+            } else if let Some(rest) = part.strip_prefix('-') {
+                timestamp.delay = Some(Delay {
+                    all: false,
+                    interval: rest.parse()?,
+                });
+            } else {
+                bail!("Unexpected {:?} in timestamp", part);
+            }
+        }
+
+        Ok((timestamp, end + 1))
+    }
+
+    /// Parse a date given on the command line.
+    ///
+    /// Accepts an Org timestamp, `YYYY-MM-DD`, `YYYY-MM-DD HH:MM`, `today`,
+    /// `tomorrow` or a relative offset such as `+3d`. Anything that is not
+    /// already an Org timestamp becomes active or inactive according to
+    /// `active`.
+    pub fn parse_user_input(input: &str, active: bool, today: NaiveDate) -> Result<Timestamp> {
+        let input = input.trim();
+        if input.starts_with('<') || input.starts_with('[') {
+            return input.parse();
+        }
+        let date = match input {
+            "today" => return Ok(Timestamp::new(active, today, None)),
+            "tomorrow" => return Ok(Timestamp::new(active, today + Duration::days(1), None)),
+            _ => input,
+        };
+        if let Some(offset) = date.strip_prefix('+') {
+            let interval: Interval = offset.parse()?;
+            let date = interval.add_to(today.and_time(NaiveTime::MIN))?.date();
+            return Ok(Timestamp::new(active, date, None));
+        }
+        let (date, time) = match date.split_once(' ') {
+            Some((date, time)) => (date, Some(parse_time(time.trim())?)),
+            None => (date, None),
+        };
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid date: {}", input))?;
+        Ok(Timestamp::new(active, date, time))
+    }
+
+    /// Parse a date given on the command line relative to the local date.
+    pub fn parse_user_input_local(input: &str, active: bool) -> Result<Timestamp> {
+        Self::parse_user_input(input, active, Local::now().date_naive())
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (timestamp, consumed) = Timestamp::parse_prefix(s)?;
+        if consumed != s.len() {
+            bail!("Unexpected text after timestamp: {}", &s[consumed..]);
+        }
+        Ok(timestamp)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (open, close) = if self.active { ('<', '>') } else { ('[', ']') };
+        write!(f, "{}{}", open, self.date.format("%Y-%m-%d %a"))?;
+        if let Some(time) = self.time {
+            write!(f, " {}", time.format("%H:%M"))?;
+            if let Some(end_time) = self.end_time {
+                write!(f, "-{}", end_time.format("%H:%M"))?;
+            }
+        }
+        if let Some(repeater) = &self.repeater {
+            write!(f, " {}", repeater)?;
+        }
+        if let Some(delay) = &self.delay {
+            let dashes = if delay.all { "--" } else { "-" };
+            write!(f, " {}{}", dashes, delay.interval)?;
+        }
+        write!(f, "{}", close)
+    }
+}
+
+impl Interval {
+    /// Add the interval to a date and time.
+    pub fn add_to(&self, datetime: NaiveDateTime) -> Result<NaiveDateTime> {
+        let value = self.value;
+        let result = match self.unit {
+            TimeUnit::Hour => datetime.checked_add_signed(Duration::hours(value.into())),
+            TimeUnit::Day => datetime.checked_add_signed(Duration::days(value.into())),
+            TimeUnit::Week => datetime.checked_add_signed(Duration::weeks(value.into())),
+            TimeUnit::Month => datetime.checked_add_months(Months::new(value)),
+            TimeUnit::Year => value
+                .checked_mul(12)
+                .and_then(|months| datetime.checked_add_months(Months::new(months))),
+        };
+        result.ok_or_else(|| anyhow!("Date out of range adding {} to {}", self, datetime))
+    }
+
+    /// Subtract the interval from a date and time.
+    pub fn subtract_from(&self, datetime: NaiveDateTime) -> Result<NaiveDateTime> {
+        let value = self.value;
+        let result = match self.unit {
+            TimeUnit::Hour => datetime.checked_sub_signed(Duration::hours(value.into())),
+            TimeUnit::Day => datetime.checked_sub_signed(Duration::days(value.into())),
+            TimeUnit::Week => datetime.checked_sub_signed(Duration::weeks(value.into())),
+            TimeUnit::Month => datetime.checked_sub_months(Months::new(value)),
+            TimeUnit::Year => value
+                .checked_mul(12)
+                .and_then(|months| datetime.checked_sub_months(Months::new(months))),
+        };
+        result.ok_or_else(|| anyhow!("Date out of range subtracting {} from {}", self, datetime))
+    }
+
+    /// The first of `start` shifted by a whole number of intervals,
+    /// forwards or backwards, that is after `after`.
+    ///
+    /// Each candidate is shifted from `start`, so a month interval from the
+    /// 31st comes back to the 31st after shorter months.
+    pub fn first_after(&self, start: NaiveDateTime, after: NaiveDateTime) -> Result<NaiveDateTime> {
+        let value = i64::from(self.value);
+        let (seconds, months) = match self.unit {
+            TimeUnit::Hour => (value * 3600, 0),
+            TimeUnit::Day => (value * 86_400, 0),
+            TimeUnit::Week => (value * 604_800, 0),
+            TimeUnit::Month => (0, value),
+            TimeUnit::Year => (0, value * 12),
+        };
+        if seconds == 0 && months == 0 {
+            bail!("Interval must not be zero: {}", self);
+        }
+        let nth = |n: i64| -> Result<NaiveDateTime> {
+            let result = if months == 0 {
+                seconds
+                    .checked_mul(n)
+                    .and_then(Duration::try_seconds)
+                    .and_then(|duration| start.checked_add_signed(duration))
+            } else {
+                let months = months.checked_mul(n);
+                match months.map(|months| (months, u32::try_from(months.unsigned_abs()))) {
+                    Some((months, Ok(count))) if months >= 0 => {
+                        start.checked_add_months(Months::new(count))
+                    }
+                    Some((_, Ok(count))) => start.checked_sub_months(Months::new(count)),
+                    _ => None,
+                }
+            };
+            result.ok_or_else(|| anyhow!("Date out of range repeating {} from {}", self, start))
+        };
+
+        // Estimate the count, then correct it for uneven months.
+        let mut n = if months == 0 {
+            (after - start).num_seconds().div_euclid(seconds)
+        } else {
+            let index = |datetime: NaiveDateTime| {
+                i64::from(datetime.year()) * 12 + i64::from(datetime.month0())
+            };
+            (index(after) - index(start)).div_euclid(months)
+        };
+        while nth(n)? <= after {
+            n += 1;
+        }
+        while nth(n - 1)? > after {
+            n -= 1;
+        }
+        nth(n)
+    }
+}
+
+impl FromStr for Interval {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let value = s[..digits]
+            .parse()
+            .map_err(|_| anyhow!("Invalid interval: {}", s))?;
+        let unit = match &s[digits..] {
+            "h" => TimeUnit::Hour,
+            "d" => TimeUnit::Day,
+            "w" => TimeUnit::Week,
+            "m" => TimeUnit::Month,
+            "y" => TimeUnit::Year,
+            _ => bail!("Invalid interval: {}", s),
+        };
+        Ok(Interval { value, unit })
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.unit {
+            TimeUnit::Hour => 'h',
+            TimeUnit::Day => 'd',
+            TimeUnit::Week => 'w',
+            TimeUnit::Month => 'm',
+            TimeUnit::Year => 'y',
+        };
+        write!(f, "{}{}", self.value, unit)
+    }
+}
+
+impl fmt::Display for Repeater {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.kind {
+            RepeaterKind::Cumulate => "+",
+            RepeaterKind::CatchUp => "++",
+            RepeaterKind::Restart => ".+",
+        };
+        write!(f, "{}{}", prefix, self.interval)?;
+        if let Some(habit_max) = &self.habit_max {
+            write!(f, "/{}", habit_max)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_repeater(kind: RepeaterKind, input: &str) -> Result<Repeater> {
+    let (interval, habit_max) = match input.split_once('/') {
+        Some((interval, habit_max)) => (interval, Some(habit_max.parse()?)),
+        None => (input, None),
+    };
+    let interval: Interval = interval.parse()?;
+    if interval.value == 0 {
+        bail!("Repeater interval must not be zero: {}", input);
+    }
+    Ok(Repeater {
+        kind,
+        interval,
+        habit_max,
+    })
+}
+
+/// Whether a part of a timestamp after the date is a day name.
+pub(crate) fn is_day_name(part: &str) -> bool {
+    part.chars().all(char::is_alphabetic)
+}
+
+fn parse_time(input: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(input, "%H:%M").map_err(|_| anyhow!("Invalid time: {}", input))
+}
+
+fn truncate_seconds(time: NaiveTime) -> NaiveTime {
+    NaiveTime::from_hms_opt(time.hour(), time.minute(), 0).expect("valid time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_active_date() {
+        let timestamp: Timestamp = "<2026-10-17 Sat>".parse().unwrap();
+        assert!(timestamp.active);
+        assert_eq!(timestamp.date, date(2026, 10, 17));
+        assert_eq!(timestamp.time, None);
+    }
+
+    #[test]
+    fn test_parse_full_timestamp() {
+        let timestamp: Timestamp = "[2026-10-17 Sat 10:00-11:30 .+2d/3d --1d]".parse().unwrap();
+        assert!(!timestamp.active);
+        assert_eq!(timestamp.time, NaiveTime::from_hms_opt(10, 0, 0));
+        assert_eq!(timestamp.end_time, NaiveTime::from_hms_opt(11, 30, 0));
+        let repeater = timestamp.repeater.unwrap();
+        assert_eq!(repeater.kind, RepeaterKind::Restart);
+        assert_eq!(
+            repeater.habit_max,
+            Some(Interval {
+                value: 3,
+                unit: TimeUnit::Day
+            })
+        );
+        assert!(timestamp.delay.unwrap().all);
+    }
+
+    #[test]
+    fn test_render_is_canonical() {
+        let timestamp: Timestamp = "<2026-10-17   sat  9:05 ++1w>".parse().unwrap();
+        assert_eq!(timestamp.to_string(), "<2026-10-17 Sat 09:05 ++1w>");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("<2026-13-01 Sat>".parse::<Timestamp>().is_err());
+        assert!("<2026-10-17 Sat".parse::<Timestamp>().is_err());
+        assert!("2026-10-17".parse::<Timestamp>().is_err());
+        assert!("<2026-10-17 Sat ++0d>".parse::<Timestamp>().is_err());
+        assert!("[2026-10-17 standup notes]".parse::<Timestamp>().is_err());
+        assert!("<2026-10-17 Sat 10:00 Mon>".parse::<Timestamp>().is_err());
+        assert!("<2026-10-17 Sat .+0w>".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn test_parse_user_input() {
+        let today = date(2026, 10, 17);
+        let timestamp = Timestamp::parse_user_input("2026-10-20 14:00", true, today).unwrap();
+        assert_eq!(timestamp.to_string(), "<2026-10-20 Tue 14:00>");
+        let timestamp = Timestamp::parse_user_input("+1w", false, today).unwrap();
+        assert_eq!(timestamp.to_string(), "[2026-10-24 Sat]");
+        let timestamp = Timestamp::parse_user_input("tomorrow", true, today).unwrap();
+        assert_eq!(timestamp.to_string(), "<2026-10-18 Sun>");
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        date(y, m, d).and_hms_opt(h, min, 0).unwrap()
+    }
+
+    fn next_repeat(timestamp: &str, now: NaiveDateTime) -> String {
+        let timestamp: Timestamp = timestamp.parse().unwrap();
+        timestamp.next_repeat(now).unwrap().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_next_repeat_cumulate() {
+        let now = at(2026, 11, 2, 9, 0);
+        assert_eq!(
+            next_repeat("<2026-10-17 Sat +1w>", now),
+            "<2026-10-24 Sat +1w>"
+        );
+    }
+
+    #[test]
+    fn test_next_repeat_catch_up() {
+        let now = at(2026, 11, 2, 9, 0);
+        assert_eq!(
+            next_repeat("<2026-10-17 Sat ++1w>", now),
+            "<2026-11-07 Sat ++1w>"
+        );
+        assert_eq!(
+            next_repeat("<2026-10-17 Sat 08:00-09:00 ++1d>", now),
+            "<2026-11-03 Tue 08:00-09:00 ++1d>"
+        );
+    }
+
+    #[test]
+    fn test_next_repeat_restart() {
+        let now = at(2026, 11, 2, 9, 0);
+        assert_eq!(
+            next_repeat("<2026-10-17 Sat 18:00 .+1w>", now),
+            "<2026-11-09 Mon 18:00 .+1w>"
+        );
+        assert_eq!(
+            next_repeat("<2026-10-17 Sat 18:00 .+2h>", now),
+            "<2026-11-02 Mon 11:00 .+2h>"
+        );
+    }
+
+    #[test]
+    fn test_next_repeat_without_repeater() {
+        let timestamp: Timestamp = "<2026-10-17 Sat>".parse().unwrap();
+        assert_eq!(timestamp.next_repeat(at(2026, 10, 17, 0, 0)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_add_month_interval() {
+        let interval: Interval = "1m".parse().unwrap();
+        let result = interval
+            .add_to(date(2026, 1, 31).and_time(NaiveTime::MIN))
+            .unwrap();
+        assert_eq!(result.date(), date(2026, 2, 28));
+    }
+
+    #[test]
+    fn test_year_interval_out_of_range() {
+        let interval: Interval = "4000000000y".parse().unwrap();
+        let start = date(2026, 1, 1).and_time(NaiveTime::MIN);
+        assert!(interval.add_to(start).is_err());
+        assert!(interval.subtract_from(start).is_err());
+    }
+
+    #[test]
+    fn test_first_after() {
+        let interval: Interval = "1w".parse().unwrap();
+        let start = at(2026, 10, 12, 8, 0);
+        assert_eq!(
+            interval.first_after(start, at(2026, 10, 19, 8, 0)).unwrap(),
+            at(2026, 10, 26, 8, 0)
+        );
+        assert_eq!(
+            interval.first_after(start, at(2020, 1, 1, 0, 0)).unwrap(),
+            at(2020, 1, 6, 8, 0)
+        );
+
+        let interval: Interval = "1m".parse().unwrap();
+        let start = at(2026, 1, 31, 0, 0);
+        assert_eq!(
+            interval.first_after(start, at(2026, 2, 28, 0, 0)).unwrap(),
+            at(2026, 3, 31, 0, 0)
+        );
+        assert_eq!(
+            interval.first_after(start, at(2025, 11, 30, 0, 0)).unwrap(),
+            at(2025, 12, 31, 0, 0)
+        );
+    }
+}